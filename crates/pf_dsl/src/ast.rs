@@ -1,10 +1,16 @@
 use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
+pub use crate::import_graph::ImportGraph;
+pub use crate::source_map::{FileId, SourceMap};
+
+// byte offsets into the file identified by `file` in the problem's source map
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub struct Span {
     pub start: usize,
     pub end: usize,
+    pub file: FileId,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -30,13 +36,29 @@ pub struct Mark {
 pub struct Problem {
     pub name: String,
     pub span: Span,
-    pub imports: Vec<String>,
+    pub imports: Vec<Import>,
+    pub frames: Vec<Frame>,
+    pub types: Vec<DataType>,
     pub domains: Vec<Domain>,
     pub interfaces: Vec<Interface>,
     pub requirements: Vec<Requirement>,
     pub subproblems: Vec<Subproblem>,
     pub assertion_sets: Vec<AssertionSet>,
     pub correctness_arguments: Vec<CorrectnessArgument>,
+    // files loaded by the resolver and the imports between them; empty for a model
+    // parsed from a string
+    pub sources: SourceMap,
+    pub import_graph: ImportGraph,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub path: String,
+    // `import "lib.pf" as Lib`: the file's declarations are visible as `Lib.Name`
+    pub alias: Option<Reference>,
+    // `import { A, B } from "lib.pf"`: only the listed declarations are merged
+    pub items: Vec<Reference>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -58,16 +80,58 @@ pub enum DomainRole {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Domain {
     pub name: String,
+    pub doc: Option<String>,
     pub kind: DomainKind,
     pub role: DomainRole,
     pub marks: Vec<Mark>,
+    pub behavior: Option<Behavior>,
+    pub schema: Option<Schema>,
     pub span: Span,
     pub source_path: Option<PathBuf>,
 }
 
+// State machine of a causal domain. The first declared state is the initial one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Behavior {
+    pub states: Vec<Reference>,
+    pub transitions: Vec<Transition>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub trigger: Reference,
+    pub from: Reference,
+    pub to: Reference,
+    pub emits: Vec<Reference>,
+    pub span: Span,
+}
+
+// Data layout of a lexical domain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    pub entities: Vec<SchemaEntity>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaEntityKind {
+    Entity,
+    Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaEntity {
+    pub name: String,
+    pub kind: SchemaEntityKind,
+    pub fields: Vec<TypedField>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Interface {
     pub name: String,
+    pub doc: Option<String>,
     pub connects: Vec<Reference>,
     pub shared_phenomena: Vec<Phenomenon>,
     pub span: Span,
@@ -82,9 +146,40 @@ pub enum PhenomenonType {
     Value,
 }
 
+// A phenomenon parameter or record field; `type_ref` names a primitive or declared type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedField {
+    pub name: String,
+    pub type_ref: Reference,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDefinition {
+    Enum(Vec<Reference>),
+    Record(Vec<TypedField>),
+    Range {
+        min: f64,
+        max: f64,
+        unit: Option<String>,
+    },
+    Unit(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataType {
+    pub name: String,
+    pub doc: Option<String>,
+    pub definition: TypeDefinition,
+    pub span: Span,
+    pub source_path: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Phenomenon {
     pub name: String,
+    pub doc: Option<String>,
+    pub parameters: Vec<TypedField>,
     pub type_: PhenomenonType,
     pub from: Reference,
     pub to: Reference,
@@ -102,11 +197,122 @@ pub enum FrameType {
     Custom(String),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameParticipant {
+    Constrains,
+    Reference,
+    Machine,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameSlot {
+    // empty lists accept any kind/role
+    pub kinds: Vec<DomainKind>,
+    pub roles: Vec<DomainRole>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameFlow {
+    // an empty list accepts any phenomenon type
+    pub types: Vec<PhenomenonType>,
+    pub from: FrameParticipant,
+    pub to: FrameParticipant,
+    pub controlled_by: Option<FrameParticipant>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub name: String,
+    pub doc: Option<String>,
+    // a declared slot makes the matching requirement field mandatory
+    pub constrains: Option<FrameSlot>,
+    pub reference: Option<FrameSlot>,
+    // slots whose domain must share phenomena with the machine
+    pub connects: Vec<FrameParticipant>,
+    pub flows: Vec<FrameFlow>,
+    pub span: Span,
+    pub source_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequirementPriority {
+    High,
+    Medium,
+    Low,
+}
+
+impl fmt::Display for RequirementPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            RequirementPriority::High => "high",
+            RequirementPriority::Medium => "medium",
+            RequirementPriority::Low => "low",
+        };
+        write!(f, "{}", keyword)
+    }
+}
+
+impl FromStr for RequirementPriority {
+    type Err = String;
+
+    fn from_str(keyword: &str) -> Result<Self, Self::Err> {
+        match keyword {
+            "high" => Ok(RequirementPriority::High),
+            "medium" => Ok(RequirementPriority::Medium),
+            "low" => Ok(RequirementPriority::Low),
+            _ => Err(format!("unknown requirement priority '{}'", keyword)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequirementStatus {
+    Draft,
+    Approved,
+    Implemented,
+    Deprecated,
+}
+
+impl fmt::Display for RequirementStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            RequirementStatus::Draft => "draft",
+            RequirementStatus::Approved => "approved",
+            RequirementStatus::Implemented => "implemented",
+            RequirementStatus::Deprecated => "deprecated",
+        };
+        write!(f, "{}", keyword)
+    }
+}
+
+impl FromStr for RequirementStatus {
+    type Err = String;
+
+    fn from_str(keyword: &str) -> Result<Self, Self::Err> {
+        match keyword {
+            "draft" => Ok(RequirementStatus::Draft),
+            "approved" => Ok(RequirementStatus::Approved),
+            "implemented" => Ok(RequirementStatus::Implemented),
+            "deprecated" => Ok(RequirementStatus::Deprecated),
+            _ => Err(format!("unknown requirement status '{}'", keyword)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Requirement {
     pub name: String,
+    // stable identifier that survives rewording the display name
+    pub id: Option<Reference>,
+    pub priority: Option<RequirementPriority>,
+    pub status: Option<RequirementStatus>,
+    pub owner: Option<String>,
+    pub doc: Option<String>,
     pub frame: FrameType,
-    pub phenomena: Vec<String>,
+    // shared phenomena named as `Phenomenon` or `Interface.Phenomenon`
+    pub phenomena: Vec<Reference>,
     pub marks: Vec<Mark>,
     // constraint is just text, not a reference to a domain
     pub constraint: String,
@@ -117,9 +323,151 @@ pub struct Requirement {
     pub source_path: Option<PathBuf>,
 }
 
+impl Problem {
+    pub fn requirement(&self, name: &str) -> Option<&Requirement> {
+        self.requirements
+            .iter()
+            .find(|requirement| requirement.is_named(name))
+    }
+
+    // The parser cannot know which file it reads; the resolver stamps each
+    // imported module before merging it.
+    pub fn assign_file(&mut self, file: FileId) {
+        fn reference(reference: &mut Reference, file: FileId) {
+            reference.span.file = file;
+        }
+        fn references(references: &mut [Reference], file: FileId) {
+            references.iter_mut().for_each(|r| reference(r, file));
+        }
+        fn fields(fields: &mut [TypedField], file: FileId) {
+            for field in fields {
+                field.span.file = file;
+                reference(&mut field.type_ref, file);
+            }
+        }
+        fn marks(marks: &mut [Mark], file: FileId) {
+            marks.iter_mut().for_each(|mark| mark.span.file = file);
+        }
+
+        self.span.file = file;
+        for import in &mut self.imports {
+            import.span.file = file;
+            references(&mut import.items, file);
+            if let Some(alias) = &mut import.alias {
+                reference(alias, file);
+            }
+        }
+        for frame in &mut self.frames {
+            frame.span.file = file;
+            for slot in [&mut frame.constrains, &mut frame.reference]
+                .into_iter()
+                .flatten()
+            {
+                slot.span.file = file;
+            }
+            frame
+                .flows
+                .iter_mut()
+                .for_each(|flow| flow.span.file = file);
+        }
+        for data_type in &mut self.types {
+            data_type.span.file = file;
+            match &mut data_type.definition {
+                TypeDefinition::Enum(literals) => references(literals, file),
+                TypeDefinition::Record(record) => fields(record, file),
+                TypeDefinition::Range { .. } | TypeDefinition::Unit(_) => {}
+            }
+        }
+        for domain in &mut self.domains {
+            domain.span.file = file;
+            marks(&mut domain.marks, file);
+            if let Some(behavior) = &mut domain.behavior {
+                behavior.span.file = file;
+                references(&mut behavior.states, file);
+                for transition in &mut behavior.transitions {
+                    transition.span.file = file;
+                    reference(&mut transition.trigger, file);
+                    reference(&mut transition.from, file);
+                    reference(&mut transition.to, file);
+                    references(&mut transition.emits, file);
+                }
+            }
+            if let Some(schema) = &mut domain.schema {
+                schema.span.file = file;
+                for entity in &mut schema.entities {
+                    entity.span.file = file;
+                    fields(&mut entity.fields, file);
+                }
+            }
+        }
+        for interface in &mut self.interfaces {
+            interface.span.file = file;
+            references(&mut interface.connects, file);
+            for phenomenon in &mut interface.shared_phenomena {
+                phenomenon.span.file = file;
+                fields(&mut phenomenon.parameters, file);
+                reference(&mut phenomenon.from, file);
+                reference(&mut phenomenon.to, file);
+                reference(&mut phenomenon.controlled_by, file);
+            }
+        }
+        for requirement in &mut self.requirements {
+            requirement.span.file = file;
+            references(&mut requirement.phenomena, file);
+            marks(&mut requirement.marks, file);
+            for r in [
+                &mut requirement.id,
+                &mut requirement.constrains,
+                &mut requirement.reference,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                reference(r, file);
+            }
+        }
+        for subproblem in &mut self.subproblems {
+            subproblem.span.file = file;
+            if let Some(machine) = &mut subproblem.machine {
+                reference(machine, file);
+            }
+            references(&mut subproblem.participants, file);
+            references(&mut subproblem.requirements, file);
+        }
+        for assertion_set in &mut self.assertion_sets {
+            assertion_set.span.file = file;
+            for assertion in &mut assertion_set.assertions {
+                assertion.span.file = file;
+            }
+        }
+        for argument in &mut self.correctness_arguments {
+            argument.span.file = file;
+            reference(&mut argument.specification_ref, file);
+            reference(&mut argument.world_ref, file);
+            reference(&mut argument.requirement_ref, file);
+        }
+    }
+}
+
+impl Requirement {
+    // exporters key on this so renaming a requirement keeps artifact IDs
+    pub fn key(&self) -> &str {
+        self.id
+            .as_ref()
+            .map(|id| id.name.as_str())
+            .unwrap_or(self.name.as_str())
+    }
+
+    // subproblems may cite a requirement by display name or by ID
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.id.as_ref().is_some_and(|id| id.name == name)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Subproblem {
     pub name: String,
+    pub doc: Option<String>,
     pub machine: Option<Reference>,
     pub participants: Vec<Reference>,
     pub requirements: Vec<Reference>,
@@ -144,6 +492,7 @@ pub struct Assertion {
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssertionSet {
     pub name: String,
+    pub doc: Option<String>,
     pub scope: AssertionScope,
     pub assertions: Vec<Assertion>,
     pub span: Span,
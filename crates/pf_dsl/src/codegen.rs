@@ -9,7 +9,53 @@ pub fn generate_rust(problem: &Problem) -> Result<String> {
     writeln!(code, "#[allow(dead_code)]")?;
     writeln!(code)?;
 
-    // 1. Generate Enums for Interfaces
+    // 1. Generate declared data types
+    for data_type in &problem.types {
+        let type_name = sanitize_name(&data_type.name);
+        match &data_type.definition {
+            TypeDefinition::Enum(literals) => {
+                writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]")?;
+                writeln!(code, "pub enum {} {{", type_name)?;
+                for literal in literals {
+                    writeln!(code, "    {},", sanitize_name(&literal.name))?;
+                }
+                writeln!(code, "}}")?;
+            }
+            TypeDefinition::Record(fields) => {
+                writeln!(code, "#[derive(Debug, Clone, PartialEq)]")?;
+                writeln!(code, "pub struct {} {{", type_name)?;
+                for field in fields {
+                    writeln!(
+                        code,
+                        "    pub {}: {},",
+                        sanitize_field_name(&field.name),
+                        rust_type(&field.type_ref.name)
+                    )?;
+                }
+                writeln!(code, "}}")?;
+            }
+            TypeDefinition::Range { min, max, unit } => {
+                if let Some(unit) = unit {
+                    writeln!(code, "/// Unit: {}", unit)?;
+                }
+                writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq)]")?;
+                writeln!(code, "pub struct {}(pub f64);", type_name)?;
+                writeln!(code)?;
+                writeln!(code, "impl {} {{", type_name)?;
+                writeln!(code, "    pub const MIN: f64 = {:?};", min)?;
+                writeln!(code, "    pub const MAX: f64 = {:?};", max)?;
+                writeln!(code, "}}")?;
+            }
+            TypeDefinition::Unit(unit) => {
+                writeln!(code, "/// Unit: {}", unit)?;
+                writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq)]")?;
+                writeln!(code, "pub struct {}(pub f64);", type_name)?;
+            }
+        }
+        writeln!(code)?;
+    }
+
+    // 2. Generate Enums for Interfaces
     for interface in &problem.interfaces {
         let enum_name = sanitize_name(&interface.name) + "Phenomena";
         writeln!(code, "/// Interface: {}", interface.name)?;
@@ -17,8 +63,31 @@ pub fn generate_rust(problem: &Problem) -> Result<String> {
         writeln!(code, "pub enum {} {{", enum_name)?;
 
         for p in &interface.shared_phenomena {
-            // e.g. Open(String) or just Open
-            // For now, unit variants for Events, maybe wrap value for Value?
+            // Typed parameters become struct variants; untyped values fall back to String.
+            if !p.parameters.is_empty() {
+                let fields = p
+                    .parameters
+                    .iter()
+                    .map(|parameter| {
+                        format!(
+                            "{}: {}",
+                            sanitize_field_name(&parameter.name),
+                            rust_type(&parameter.type_ref.name)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(
+                    code,
+                    "    {} {{ {} }}, // {:?} from {} -> {}",
+                    sanitize_name(&p.name),
+                    fields,
+                    p.type_,
+                    p.from,
+                    p.to
+                )?;
+                continue;
+            }
             match p.type_ {
                 PhenomenonType::Value => {
                     writeln!(
@@ -45,23 +114,105 @@ pub fn generate_rust(problem: &Problem) -> Result<String> {
         writeln!(code)?;
     }
 
-    // 2. Generate Structs for Domains
+    // 3. Generate Structs for Domains
     for domain in &problem.domains {
+        let entities = domain
+            .schema
+            .as_ref()
+            .map(|schema| schema.entities.as_slice())
+            .unwrap_or_default();
+        for entity in entities {
+            writeln!(code, "/// {:?} of {}", entity.kind, domain.name)?;
+            writeln!(code, "#[derive(Debug, Clone, PartialEq)]")?;
+            writeln!(code, "pub struct {} {{", sanitize_name(&entity.name))?;
+            for field in &entity.fields {
+                writeln!(
+                    code,
+                    "    pub {}: {},",
+                    sanitize_field_name(&field.name),
+                    rust_type(&field.type_ref.name)
+                )?;
+            }
+            writeln!(code, "}}")?;
+            writeln!(code)?;
+        }
+
+        // The domain holds a collection per schema entity; value objects only
+        // appear as field types.
+        let collections = entities
+            .iter()
+            .filter(|entity| entity.kind == SchemaEntityKind::Entity)
+            .map(|entity| {
+                (
+                    sanitize_field_name(&format!("{}s", entity.name)),
+                    sanitize_name(&entity.name),
+                )
+            })
+            .collect::<Vec<_>>();
+
         writeln!(
             code,
             "/// Domain: {} [{:?}/{:?}]",
             domain.name, domain.kind, domain.role
         )?;
+        if domain.role == DomainRole::Machine {
+            // Each machine of a decomposed problem only drives its own interfaces.
+            let subproblems = problem
+                .subproblems
+                .iter()
+                .filter(|subproblem| {
+                    subproblem
+                        .machine
+                        .as_ref()
+                        .is_some_and(|machine| machine.name == domain.name)
+                })
+                .map(|subproblem| subproblem.name.as_str())
+                .collect::<Vec<_>>();
+            if !subproblems.is_empty() {
+                writeln!(
+                    code,
+                    "/// Machine of subproblems: {}",
+                    subproblems.join(", ")
+                )?;
+            }
+            let interfaces = problem
+                .interfaces
+                .iter()
+                .filter(|interface| {
+                    interface
+                        .connects
+                        .iter()
+                        .any(|connected| connected.name == domain.name)
+                })
+                .map(|interface| sanitize_name(&interface.name) + "Phenomena")
+                .collect::<Vec<_>>();
+            if !interfaces.is_empty() {
+                writeln!(code, "/// Interfaces: {}", interfaces.join(", "))?;
+            }
+        }
         writeln!(code, "#[derive(Debug)]")?;
         writeln!(code, "pub struct {} {{", sanitize_name(&domain.name))?;
-        writeln!(code, "    // TODO: Add internal state")?;
+        if collections.is_empty() {
+            writeln!(code, "    // TODO: Add internal state")?;
+        }
+        for (field, entity) in &collections {
+            writeln!(code, "    pub {}: Vec<{}>,", field, entity)?;
+        }
         writeln!(code, "}}")?;
         writeln!(code)?;
 
         // Generate impl stub
         writeln!(code, "impl {} {{", sanitize_name(&domain.name))?;
         writeln!(code, "    pub fn new() -> Self {{")?;
-        writeln!(code, "        Self {{}}")?;
+        if collections.is_empty() {
+            writeln!(code, "        Self {{}}")?;
+        } else {
+            writeln!(code, "        Self {{")?;
+            for (field, _) in &collections {
+                writeln!(code, "            {}: Vec::new(),", field)?;
+            }
+            writeln!(code, "        }}")?;
+        }
         writeln!(code, "    }}")?;
         writeln!(code, "}}")?;
         writeln!(code)?;
@@ -70,6 +221,44 @@ pub fn generate_rust(problem: &Problem) -> Result<String> {
     Ok(code)
 }
 
+fn rust_type(type_name: &str) -> String {
+    match type_name {
+        "Integer" => "i64".to_string(),
+        "Decimal" => "f64".to_string(),
+        "Boolean" => "bool".to_string(),
+        "String" => "String".to_string(),
+        _ => sanitize_name(type_name),
+    }
+}
+
+// camelCase parameter names become snake_case fields.
+fn sanitize_field_name(name: &str) -> String {
+    let mut field = String::with_capacity(name.len() + 4);
+    for (index, ch) in name.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if index > 0 && !field.ends_with('_') {
+                field.push('_');
+            }
+            field.push(ch.to_ascii_lowercase());
+        } else if ch.is_ascii_alphanumeric() || ch == '_' {
+            field.push(ch);
+        } else if !field.ends_with('_') {
+            field.push('_');
+        }
+    }
+
+    if field.is_empty() {
+        field.push_str("value");
+    }
+    if field.starts_with(|ch: char| ch.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+    if is_rust_keyword(&field) {
+        field.push('_');
+    }
+    field
+}
+
 fn sanitize_name(name: &str) -> String {
     let mut s = String::with_capacity(name.len());
 
@@ -179,7 +368,8 @@ fn is_rust_keyword(name: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::sanitize_name;
+    use super::{generate_rust, sanitize_field_name, sanitize_name};
+    use crate::parser::parse;
 
     #[test]
     fn sanitize_name_removes_invalid_chars_and_prefers_valid_start() {
@@ -194,4 +384,125 @@ mod tests {
         assert_eq!(sanitize_name("hello world"), "Hello_world");
         assert_eq!(sanitize_name("привет"), "Generated");
     }
+
+    #[test]
+    fn sanitize_field_name_produces_snake_case_fields() {
+        assert_eq!(sanitize_field_name("targetLevel"), "target_level");
+        assert_eq!(sanitize_field_name("type"), "type_");
+        assert_eq!(sanitize_field_name("2nd"), "_2nd");
+    }
+
+    #[test]
+    fn generate_rust_emits_declared_types_and_typed_parameters() {
+        let problem = parse(
+            r#"
+            problem: Tank
+            type Percent = range 0..100 unit "%"
+            type Mode = enum { Auto, Manual }
+            type Reading = record { level: Percent, mode: Mode, stale: Boolean }
+            domain Controller kind causal role machine
+            domain Operator kind biddable role given
+            interface "Operator-Controller" connects Operator, Controller {
+                shared: {
+                    phenomenon SetLevel(targetLevel: Percent, mode: Mode) : command [Operator -> Controller] controlledBy Operator
+                    phenomenon Status : value [Controller -> Operator] controlledBy Controller
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        let code = generate_rust(&problem).unwrap();
+        assert!(code.contains("pub struct Percent(pub f64);"));
+        assert!(code.contains("    pub const MAX: f64 = 100.0;"));
+        assert!(code.contains("pub enum Mode {\n    Auto,\n    Manual,\n}"));
+        assert!(code.contains("    pub stale: bool,"));
+        assert!(code.contains("    SetLevel { target_level: Percent, mode: Mode }, // Command"));
+        assert!(code.contains("    Status(String), // Value"));
+    }
+
+    #[test]
+    fn generate_rust_emits_schema_structs_for_lexical_domains() {
+        let problem = parse(
+            r#"
+            problem: Orders
+            domain Clerk kind causal role machine
+            domain Ledger kind lexical role designed schema {
+                entity OrderLine {
+                    field quantity: Integer
+                    field price: Money
+                }
+                value Money {
+                    field amount: Decimal
+                    field currency: String
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        let code = generate_rust(&problem).unwrap();
+        assert!(code
+            .contains("pub struct OrderLine {\n    pub quantity: i64,\n    pub price: Money,\n}"));
+        assert!(code.contains(
+            "/// Value of Ledger\n#[derive(Debug, Clone, PartialEq)]\npub struct Money {"
+        ));
+        assert!(code.contains("pub struct Ledger {\n    pub order_lines: Vec<OrderLine>,\n}"));
+        assert!(code.contains("        Self {\n            order_lines: Vec::new(),\n        }"));
+        assert!(code.contains("pub struct Clerk {\n    // TODO: Add internal state\n}"));
+    }
+
+    #[test]
+    fn generate_rust_documents_the_subproblems_and_interfaces_of_each_machine() {
+        let problem = parse(
+            r#"
+            problem: Crossing
+            domain GateController kind causal role machine
+            domain LightController kind causal role machine
+            domain Gate kind causal role given
+            domain Light kind causal role given
+            interface "GateController-Gate" connects GateController, Gate {
+                shared: {
+                    phenomenon Lower : event [GateController -> Gate] controlledBy GateController
+                }
+            }
+            interface "LightController-Light" connects LightController, Light {
+                shared: {
+                    phenomenon Flash : event [LightController -> Light] controlledBy LightController
+                    phenomenon Burnt : state [Light -> LightController] controlledBy Light
+                }
+            }
+            requirement "Lower gate" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            requirement "Flash light" {
+                frame: RequiredBehavior
+                constrains: Light
+            }
+            subproblem GateControl {
+                machine: GateController
+                participants: GateController, Gate
+                requirements: "Lower gate"
+            }
+            subproblem LightControl {
+                machine: LightController
+                participants: LightController, Light
+                requirements: "Flash light"
+            }
+        "#,
+        )
+        .unwrap();
+
+        let code = generate_rust(&problem).unwrap();
+        assert!(code.contains(
+            "/// Machine of subproblems: GateControl\n/// Interfaces: GateController_GatePhenomena\n#[derive(Debug)]\npub struct GateController {"
+        ));
+        assert!(code.contains(
+            "/// Machine of subproblems: LightControl\n/// Interfaces: LightController_LightPhenomena\n"
+        ));
+        assert!(
+            code.contains("/// Domain: Gate [Causal/Given]\n#[derive(Debug)]\npub struct Gate {")
+        );
+    }
 }
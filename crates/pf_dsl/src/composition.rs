@@ -0,0 +1,201 @@
+use crate::ast::*;
+use crate::validator::ValidationError;
+use std::collections::{BTreeMap, BTreeSet};
+
+// `@composition.decision("Other requirement, R-7")` on a requirement records that
+// its composition with the named requirements (by name or ID) has been decided.
+pub const COMPOSITION_DECISION_MARK: &str = "composition.decision";
+
+// A phenomenon the machine controls in more than one subproblem: each subproblem
+// machine was specified on its own, so nothing says which one drives it when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedControl {
+    pub phenomenon: String,
+    pub interface: String,
+    pub controller: String,
+    pub subproblems: Vec<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedConstraint {
+    pub domain: String,
+    pub subproblems: Vec<String>,
+    pub requirements: Vec<String>,
+}
+
+// Two requirements of different subproblems constraining the same domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequirementPair {
+    pub first: String,
+    pub first_subproblem: String,
+    pub second: String,
+    pub second_subproblem: String,
+    pub domain: String,
+    pub decided: bool,
+    // the second requirement, where the finding is reported
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompositionConcerns {
+    pub shared_control: Vec<SharedControl>,
+    pub shared_constraints: Vec<SharedConstraint>,
+    pub requirement_pairs: Vec<RequirementPair>,
+}
+
+impl CompositionConcerns {
+    pub fn undecided_pairs(&self) -> impl Iterator<Item = &RequirementPair> {
+        self.requirement_pairs.iter().filter(|pair| !pair.decided)
+    }
+}
+
+pub fn analyze(problem: &Problem) -> CompositionConcerns {
+    let mut concerns = CompositionConcerns::default();
+
+    for interface in &problem.interfaces {
+        for phenomenon in &interface.shared_phenomena {
+            let subproblems: Vec<String> = problem
+                .subproblems
+                .iter()
+                .filter(|subproblem| {
+                    subproblem
+                        .machine
+                        .as_ref()
+                        .is_some_and(|machine| machine.name == phenomenon.controlled_by.name)
+                        && interface.connects.iter().all(|domain| {
+                            subproblem
+                                .participants
+                                .iter()
+                                .any(|participant| participant.name == domain.name)
+                        })
+                })
+                .map(|subproblem| subproblem.name.clone())
+                .collect();
+            if subproblems.len() > 1 {
+                concerns.shared_control.push(SharedControl {
+                    phenomenon: phenomenon.name.clone(),
+                    interface: interface.name.clone(),
+                    controller: phenomenon.controlled_by.name.clone(),
+                    subproblems,
+                    span: phenomenon.span,
+                });
+            }
+        }
+    }
+
+    let mut constrained: BTreeMap<&str, Vec<(&Requirement, Vec<&str>)>> = BTreeMap::new();
+    for requirement in &problem.requirements {
+        let Some(domain) = &requirement.constrains else {
+            continue;
+        };
+        let subproblems: Vec<&str> = problem
+            .subproblems
+            .iter()
+            .filter(|subproblem| {
+                subproblem
+                    .requirements
+                    .iter()
+                    .any(|reference| requirement.is_named(&reference.name))
+            })
+            .map(|subproblem| subproblem.name.as_str())
+            .collect();
+        if !subproblems.is_empty() {
+            constrained
+                .entry(domain.name.as_str())
+                .or_default()
+                .push((requirement, subproblems));
+        }
+    }
+
+    for (domain, requirements) in constrained {
+        let subproblems: BTreeSet<&str> = requirements
+            .iter()
+            .flat_map(|(_, subproblems)| subproblems.iter().copied())
+            .collect();
+        if subproblems.len() < 2 {
+            continue;
+        }
+        concerns.shared_constraints.push(SharedConstraint {
+            domain: domain.to_string(),
+            subproblems: subproblems.iter().map(|name| name.to_string()).collect(),
+            requirements: requirements
+                .iter()
+                .map(|(requirement, _)| requirement.key().to_string())
+                .collect(),
+        });
+
+        for (index, (first, first_subproblems)) in requirements.iter().enumerate() {
+            for (second, second_subproblems) in &requirements[index + 1..] {
+                let Some((first_subproblem, second_subproblem)) = first_subproblems
+                    .iter()
+                    .flat_map(|left| second_subproblems.iter().map(move |right| (left, right)))
+                    .find(|(left, right)| left != right)
+                else {
+                    continue;
+                };
+                concerns.requirement_pairs.push(RequirementPair {
+                    first: first.key().to_string(),
+                    first_subproblem: first_subproblem.to_string(),
+                    second: second.key().to_string(),
+                    second_subproblem: second_subproblem.to_string(),
+                    domain: domain.to_string(),
+                    decided: decides(first, second) || decides(second, first),
+                    span: second.span,
+                });
+            }
+        }
+    }
+
+    concerns
+}
+
+fn decides(requirement: &Requirement, other: &Requirement) -> bool {
+    requirement
+        .marks
+        .iter()
+        .filter(|mark| mark.name == COMPOSITION_DECISION_MARK)
+        .filter_map(|mark| mark.value.as_deref())
+        .flat_map(|value| value.split(','))
+        .any(|name| other.is_named(name.trim()))
+}
+
+// Shared control and undecided requirement pairs as warnings; `lints::lint`
+// reports them next to `validate`.
+pub fn check_composition(problem: &Problem) -> Result<(), Vec<ValidationError>> {
+    let concerns = analyze(problem);
+    let mut errors = Vec::new();
+
+    for shared in &concerns.shared_control {
+        errors.push(ValidationError::CompositionConcern(
+            shared.phenomenon.clone(),
+            format!(
+                "controlled by {} on interface '{}' in subproblems {}",
+                shared.controller,
+                shared.interface,
+                shared.subproblems.join(", ")
+            ),
+            shared.span,
+        ));
+    }
+    for pair in concerns.undecided_pairs() {
+        errors.push(ValidationError::CompositionConcern(
+            pair.second.clone(),
+            format!(
+                "constrains {} in subproblem {}, as '{}' does in subproblem {}, with no @{} between them",
+                pair.domain,
+                pair.second_subproblem,
+                pair.first,
+                pair.first_subproblem,
+                COMPOSITION_DECISION_MARK
+            ),
+            pair.span,
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
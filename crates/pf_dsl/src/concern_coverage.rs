@@ -45,10 +45,12 @@ pub fn summarize(problem: &Problem) -> ConcernCoverageSummary {
     let mut requirement_to_subproblems: BTreeMap<String, Vec<String>> = BTreeMap::new();
     for subproblem in &problem.subproblems {
         for requirement in &subproblem.requirements {
-            requirement_to_subproblems
-                .entry(requirement.name.clone())
-                .or_default()
-                .push(subproblem.name.clone());
+            if let Some(requirement) = problem.requirement(&requirement.name) {
+                requirement_to_subproblems
+                    .entry(requirement.key().to_string())
+                    .or_default()
+                    .push(subproblem.name.clone());
+            }
         }
     }
 
@@ -72,7 +74,7 @@ pub fn summarize(problem: &Problem) -> ConcernCoverageSummary {
 
     for requirement in &problem.requirements {
         let subproblems = requirement_to_subproblems
-            .get(&requirement.name)
+            .get(requirement.key())
             .cloned()
             .unwrap_or_default();
 
@@ -106,15 +108,15 @@ pub fn summarize(problem: &Problem) -> ConcernCoverageSummary {
                 covered_requirements += 1;
             }
             ConcernCoverageState::Uncovered => {
-                uncovered_requirements.push(requirement.name.clone());
+                uncovered_requirements.push(requirement.key().to_string());
             }
             ConcernCoverageState::Deferred => {
-                deferred_requirements.push(requirement.name.clone());
+                deferred_requirements.push(requirement.key().to_string());
             }
         }
 
         requirement_rows.push(RequirementConcernCoverage {
-            requirement: requirement.name.clone(),
+            requirement: requirement.key().to_string(),
             subproblems,
             correctness_arguments: correctness_arguments.clone(),
             wrspm_w_sets: wrspm_projection.artifacts.w_sets.clone(),
@@ -1,4 +1,6 @@
 use crate::ast::Problem;
+use crate::composition;
+use crate::formal_closure::escape_field;
 use std::collections::{BTreeMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,15 +34,17 @@ pub fn summarize(problem: &Problem) -> DecompositionClosureSummary {
             subproblems_without_machine.push(subproblem.name.clone());
         }
         for requirement in &subproblem.requirements {
-            covered_requirements.insert(requirement.name.as_str());
+            if let Some(requirement) = problem.requirement(&requirement.name) {
+                covered_requirements.insert(requirement.key());
+            }
         }
     }
 
     let mut uncovered_requirements = problem
         .requirements
         .iter()
-        .filter(|requirement| !covered_requirements.contains(requirement.name.as_str()))
-        .map(|requirement| requirement.name.clone())
+        .filter(|requirement| !covered_requirements.contains(requirement.key()))
+        .map(|requirement| requirement.key().to_string())
         .collect::<Vec<_>>();
 
     uncovered_requirements.sort();
@@ -62,10 +66,12 @@ pub fn generate_markdown(problem: &Problem) -> String {
     let mut requirement_to_subproblems: BTreeMap<String, Vec<String>> = BTreeMap::new();
     for subproblem in &problem.subproblems {
         for requirement in &subproblem.requirements {
-            requirement_to_subproblems
-                .entry(requirement.name.clone())
-                .or_default()
-                .push(subproblem.name.clone());
+            if let Some(requirement) = problem.requirement(&requirement.name) {
+                requirement_to_subproblems
+                    .entry(requirement.key().to_string())
+                    .or_default()
+                    .push(subproblem.name.clone());
+            }
         }
     }
 
@@ -113,7 +119,7 @@ pub fn generate_markdown(problem: &Problem) -> String {
     output.push_str("| --- | --- | --- |\n");
     for requirement in &problem.requirements {
         let linked_subproblems = requirement_to_subproblems
-            .get(&requirement.name)
+            .get(requirement.key())
             .cloned()
             .unwrap_or_default();
         let mut linked_subproblems = linked_subproblems;
@@ -130,7 +136,9 @@ pub fn generate_markdown(problem: &Problem) -> String {
         };
         output.push_str(&format!(
             "| {} | {} | {} |\n",
-            requirement.name, subproblems, status
+            escape_field(requirement.key()),
+            subproblems,
+            status
         ));
     }
     output.push('\n');
@@ -140,7 +148,7 @@ pub fn generate_markdown(problem: &Problem) -> String {
         output.push_str("- (none)\n");
     } else {
         for requirement in &summary.uncovered_requirements {
-            output.push_str(&format!("- {}\n", requirement));
+            output.push_str(&format!("- {}\n", escape_field(requirement)));
         }
     }
     output.push('\n');
@@ -165,6 +173,75 @@ pub fn generate_markdown(problem: &Problem) -> String {
     }
     output.push('\n');
 
+    let concerns = composition::analyze(problem);
+    output.push_str("## Composition Concerns\n");
+    output.push_str(&format!(
+        "- Undecided requirement pairs: {}\n\n",
+        concerns.undecided_pairs().count()
+    ));
+
+    output.push_str("### Shared Controlled Phenomena\n");
+    if concerns.shared_control.is_empty() {
+        output.push_str("- None.\n");
+    } else {
+        output.push_str("| Phenomenon | Interface | Controlled by | Subproblems |\n");
+        output.push_str("| --- | --- | --- | --- |\n");
+        for shared in &concerns.shared_control {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                shared.phenomenon,
+                escape_field(&shared.interface),
+                shared.controller,
+                shared.subproblems.join(", ")
+            ));
+        }
+    }
+    output.push('\n');
+
+    output.push_str("### Domains Constrained by Several Subproblems\n");
+    if concerns.shared_constraints.is_empty() {
+        output.push_str("- None.\n");
+    } else {
+        output.push_str("| Domain | Subproblems | Requirements |\n");
+        output.push_str("| --- | --- | --- |\n");
+        for shared in &concerns.shared_constraints {
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                shared.domain,
+                shared.subproblems.join(", "),
+                shared
+                    .requirements
+                    .iter()
+                    .map(|requirement| escape_field(requirement))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    output.push('\n');
+
+    output.push_str("### Requirement Pairs Needing a Composition Decision\n");
+    if concerns.requirement_pairs.is_empty() {
+        output.push_str("- None.\n");
+    } else {
+        output.push_str(
+            "| Requirement | Subproblem | Requirement | Subproblem | Domain | Decision |\n",
+        );
+        output.push_str("| --- | --- | --- | --- | --- | --- |\n");
+        for pair in &concerns.requirement_pairs {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                escape_field(&pair.first),
+                pair.first_subproblem,
+                escape_field(&pair.second),
+                pair.second_subproblem,
+                pair.domain,
+                if pair.decided { "decided" } else { "undecided" }
+            ));
+        }
+    }
+    output.push('\n');
+
     output
 }
 
@@ -253,4 +330,65 @@ subproblem S1 {
         assert!(markdown.contains("Closure status: PASS"));
         assert!(markdown.contains("Uncovered requirements: 0"));
     }
+
+    #[test]
+    fn decomposition_markdown_lists_composition_concerns() {
+        let input = r#"
+problem: Crossing
+
+domain Controller kind causal role machine
+domain Gate kind causal role given
+domain Light kind causal role given
+
+interface "Controller-Gate" connects Controller, Gate {
+  shared: { phenomenon Lower : event [Controller -> Gate] controlledBy Controller }
+}
+
+interface "Controller-Light" connects Controller, Light {
+  shared: { phenomenon Flash : event [Controller -> Light] controlledBy Controller }
+}
+
+requirement "Lower gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+
+requirement "Hold gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+
+requirement "Keep gate up" {
+  frame: RequiredBehavior
+  constrains: Gate
+  marks: { @composition.decision("Lower gate") }
+}
+
+subproblem Closing {
+  machine: Controller
+  participants: Controller, Gate, Light
+  requirements: "Lower gate"
+}
+
+subproblem Maintenance {
+  machine: Controller
+  participants: Controller, Gate
+  requirements: "Hold gate", "Keep gate up"
+}
+"#;
+        let problem = parse(input).expect("parse must succeed");
+        let markdown = generate_markdown(&problem);
+        assert!(markdown.contains("- Undecided requirement pairs: 1\n"));
+        assert!(
+            markdown.contains("| Lower | Controller-Gate | Controller | Closing, Maintenance |")
+        );
+        assert!(!markdown.contains("| Flash |"));
+        assert!(markdown
+            .contains("| Gate | Closing, Maintenance | Lower gate, Hold gate, Keep gate up |"));
+        assert!(markdown
+            .contains("| Lower gate | Closing | Hold gate | Maintenance | Gate | undecided |"));
+        assert!(markdown
+            .contains("| Lower gate | Closing | Keep gate up | Maintenance | Gate | decided |"));
+        assert!(!markdown.contains("| Hold gate | Maintenance | Keep gate up |"));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::ast::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write;
+use std::path::Path;
 
 #[derive(Clone, Copy)]
 enum DotView {
@@ -25,6 +26,154 @@ pub fn to_decomposition_dot(problem: &Problem) -> String {
     build_dot(problem, DotView::Decomposition)
 }
 
+// State diagrams of the domains that declare a behavior, one cluster per domain.
+pub fn to_behavior_dot(problem: &Problem) -> String {
+    let mut dot = String::new();
+    writeln!(
+        &mut dot,
+        "digraph \"{}\" {{",
+        escape_dot_string(&problem.name)
+    )
+    .unwrap();
+    writeln!(&mut dot, "    rankdir=LR;").unwrap();
+    writeln!(&mut dot, "    node [shape=box, style=rounded];").unwrap();
+
+    for domain in &problem.domains {
+        let Some(behavior) = &domain.behavior else {
+            continue;
+        };
+        let name = escape_dot_string(&domain.name);
+        writeln!(&mut dot, "    subgraph \"cluster_{}\" {{", name).unwrap();
+        writeln!(
+            &mut dot,
+            "        label=\"{}\"{};",
+            name,
+            tooltip_attribute(&domain.doc)
+        )
+        .unwrap();
+        for state in &behavior.states {
+            writeln!(
+                &mut dot,
+                "        \"{}:{}\" [label=\"{}\"];",
+                name,
+                escape_dot_string(&state.name),
+                escape_dot_string(&state.name)
+            )
+            .unwrap();
+        }
+        if let Some(initial) = behavior.states.first() {
+            writeln!(&mut dot, "        \"{}:<initial>\" [shape=point];", name).unwrap();
+            writeln!(
+                &mut dot,
+                "        \"{}:<initial>\" -> \"{}:{}\";",
+                name,
+                name,
+                escape_dot_string(&initial.name)
+            )
+            .unwrap();
+        }
+        for transition in &behavior.transitions {
+            let mut label = transition.trigger.name.clone();
+            if !transition.emits.is_empty() {
+                let emits = transition
+                    .emits
+                    .iter()
+                    .map(|reference| reference.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                label = format!("{label} / {emits}");
+            }
+            writeln!(
+                &mut dot,
+                "        \"{}:{}\" -> \"{}:{}\" [label=\"{}\"];",
+                name,
+                escape_dot_string(&transition.from.name),
+                name,
+                escape_dot_string(&transition.to.name),
+                escape_dot_string(&label)
+            )
+            .unwrap();
+        }
+        writeln!(&mut dot, "    }}").unwrap();
+    }
+
+    writeln!(&mut dot, "}}").unwrap();
+    dot
+}
+
+// File-level import structure. Paths are shown relative to the entry file's
+// directory; imports that close a cycle are red, repeated imports dashed.
+pub fn to_imports_dot(problem: &Problem) -> String {
+    let graph = &problem.import_graph;
+    let label = |path: &Path| escape_dot_string(&file_label(problem, path));
+
+    let mut dot = String::new();
+    writeln!(
+        &mut dot,
+        "digraph \"{}\" {{",
+        escape_dot_string(&problem.name)
+    )
+    .unwrap();
+    writeln!(&mut dot, "    rankdir=LR;").unwrap();
+    writeln!(&mut dot, "    node [shape=note];").unwrap();
+
+    for (index, file) in graph.files.iter().enumerate() {
+        let style = if index == 0 { ", style=bold" } else { "" };
+        writeln!(
+            &mut dot,
+            "    \"{}\" [label=\"{}\"{}];",
+            label(file),
+            label(file),
+            style
+        )
+        .unwrap();
+    }
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+        let mut attributes = Vec::new();
+        let mut scope = Vec::new();
+        if !edge.items.is_empty() {
+            scope.push(format!("{{ {} }}", edge.items.join(", ")));
+        }
+        if let Some(alias) = &edge.alias {
+            scope.push(format!("as {alias}"));
+        }
+        if !scope.is_empty() {
+            attributes.push(format!("label=\"{}\"", escape_dot_string(&scope.join(" "))));
+        }
+        if graph
+            .cycles
+            .iter()
+            .any(|cycle| cycle.last() == Some(&index))
+        {
+            attributes.push("color=red".to_string());
+        }
+        if graph
+            .redundant
+            .iter()
+            .any(|(repeated, _)| *repeated == index)
+        {
+            attributes.push("style=dashed".to_string());
+        }
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attributes.join(", "))
+        };
+        writeln!(
+            &mut dot,
+            "    \"{}\" -> \"{}\"{};",
+            label(&edge.from),
+            label(&edge.to),
+            attributes
+        )
+        .unwrap();
+    }
+
+    writeln!(&mut dot, "}}").unwrap();
+    dot
+}
+
 fn build_dot(problem: &Problem, view: DotView) -> String {
     let mut dot = String::new();
     writeln!(
@@ -60,30 +209,75 @@ fn build_dot(problem: &Problem, view: DotView) -> String {
     dot
 }
 
+// Domains of a model that spans several files are clustered by the file that
+// declares them.
 fn write_domain_nodes(problem: &Problem, dot: &mut String) {
-    for domain in &problem.domains {
-        let (shape, color) = match domain.kind {
-            DomainKind::Causal => ("box", "white"),
-            DomainKind::Biddable => ("ellipse", "white"),
-            DomainKind::Lexical => ("parallelogram", "white"),
-            _ => ("box", "red"),
-        };
-        let (shape, color) = if domain.role == DomainRole::Machine {
-            ("doublebox", "lightgrey")
-        } else {
-            (shape, color)
-        };
-        let label = format!("{} <<{:?}/{:?}>>", domain.name, domain.kind, domain.role);
-        writeln!(
-            dot,
-            "    \"{}\" [label=\"{}\", shape={}, fillcolor={}];",
-            escape_dot_string(&domain.name),
-            escape_dot_string(&label),
-            shape,
-            color
-        )
-        .unwrap();
+    let files: BTreeSet<FileId> = problem
+        .domains
+        .iter()
+        .map(|domain| domain.span.file)
+        .collect();
+    if files.len() < 2 {
+        for domain in &problem.domains {
+            write_domain_node(domain, dot, "    ");
+        }
+        return;
     }
+
+    for file in files {
+        let label = problem
+            .sources
+            .path(file)
+            .map(|path| file_label(problem, path))
+            .unwrap_or_default();
+        writeln!(dot, "    subgraph \"cluster_file_{}\" {{", file.0).unwrap();
+        writeln!(dot, "        label=\"{}\";", escape_dot_string(&label)).unwrap();
+        writeln!(dot, "        style=dashed;").unwrap();
+        for domain in problem.domains.iter().filter(|d| d.span.file == file) {
+            write_domain_node(domain, dot, "        ");
+        }
+        writeln!(dot, "    }}").unwrap();
+    }
+}
+
+fn write_domain_node(domain: &Domain, dot: &mut String, indent: &str) {
+    let (shape, color) = match domain.kind {
+        DomainKind::Causal => ("box", "white"),
+        DomainKind::Biddable => ("ellipse", "white"),
+        DomainKind::Lexical => ("parallelogram", "white"),
+        _ => ("box", "red"),
+    };
+    let (shape, color) = if domain.role == DomainRole::Machine {
+        ("doublebox", "lightgrey")
+    } else {
+        (shape, color)
+    };
+    let label = format!("{} <<{:?}/{:?}>>", domain.name, domain.kind, domain.role);
+    writeln!(
+        dot,
+        "{}\"{}\" [label=\"{}\", shape={}, fillcolor={}{}];",
+        indent,
+        escape_dot_string(&domain.name),
+        escape_dot_string(&label),
+        shape,
+        color,
+        tooltip_attribute(&domain.doc)
+    )
+    .unwrap();
+}
+
+// Paths relative to the entry file's directory where possible.
+fn file_label(problem: &Problem, path: &Path) -> String {
+    let base = problem
+        .import_graph
+        .files
+        .first()
+        .and_then(|entry| entry.parent());
+    base.and_then(|base| path.strip_prefix(base).ok())
+        .or_else(|| path.file_name().map(Path::new))
+        .unwrap_or(path)
+        .display()
+        .to_string()
 }
 
 fn write_requirement_nodes(problem: &Problem, dot: &mut String, include_domain_links: bool) {
@@ -96,11 +290,17 @@ fn write_requirement_nodes(problem: &Problem, dot: &mut String, include_domain_l
             FrameType::Transformation => "Transformation",
             FrameType::Custom(s) => s.as_str(),
         };
+        let title = match &req.id {
+            Some(id) => format!("{}: {}", id.name, req.name),
+            None => req.name.clone(),
+        };
         writeln!(
             dot,
-            "    \"{}\" [shape=note, style=dashed, label=\"{}\"];",
-            escape_dot_string(&req.name),
-            escape_dot_string(&format!("{}\\n[{}]", req.name, frame_label)),
+            "    \"{}\" [shape=note, style=dashed, label=\"{}\\n[{}]\"{}];",
+            escape_dot_string(req.key()),
+            escape_dot_string(&title),
+            escape_dot_string(frame_label),
+            tooltip_attribute(&req.doc),
         )
         .unwrap();
 
@@ -112,7 +312,7 @@ fn write_requirement_nodes(problem: &Problem, dot: &mut String, include_domain_l
             writeln!(
                 dot,
                 "    \"{}\" -> \"{}\" [style=dashed, arrowhead=none, label=\"constrains\"];",
-                escape_dot_string(&req.name),
+                escape_dot_string(req.key()),
                 escape_dot_string(&c.name)
             )
             .unwrap();
@@ -121,7 +321,7 @@ fn write_requirement_nodes(problem: &Problem, dot: &mut String, include_domain_l
             writeln!(
                 dot,
                 "    \"{}\" -> \"{}\" [style=dashed, arrowhead=none, label=\"references\"];",
-                escape_dot_string(&req.name),
+                escape_dot_string(req.key()),
                 escape_dot_string(&r.name)
             )
             .unwrap();
@@ -133,6 +333,7 @@ fn write_interface_edges(problem: &Problem, dot: &mut String) {
     // Aggregate all phenomena by unordered domain pair so we do not lose connections
     // when one interface contains multiple pairs.
     let mut edges: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    let mut tooltips: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
     for interface in &problem.interfaces {
         for phen in &interface.shared_phenomena {
             let pair = (phen.from.name.clone(), phen.to.name.clone());
@@ -147,6 +348,12 @@ fn write_interface_edges(problem: &Problem, dot: &mut String) {
                 PhenomenonType::State => "S",
                 PhenomenonType::Value => "V",
             };
+            if let Some(doc) = &phen.doc {
+                tooltips
+                    .entry(key.clone())
+                    .or_default()
+                    .push(format!("{}: {}", phen.name, doc));
+            }
             edges.entry(key).or_default().push(format!(
                 "{} -> {}: {} [{}]",
                 phen.from.name, phen.to.name, phen.name, symbol
@@ -156,27 +363,65 @@ fn write_interface_edges(problem: &Problem, dot: &mut String) {
 
     for ((src, dst), labels) in edges {
         let label_str = labels.join("\\n");
+        let tooltip = tooltips
+            .remove(&(src.clone(), dst.clone()))
+            .map(|docs| docs.join("\n"));
         writeln!(
             dot,
-            "    \"{}\" -> \"{}\" [dir=both, label=\"{}\"];",
+            "    \"{}\" -> \"{}\" [dir=both, label=\"{}\"{}];",
             escape_dot_string(&src),
             escape_dot_string(&dst),
-            escape_dot_string(&label_str)
+            escape_dot_string(&label_str),
+            tooltip_attribute(&tooltip)
         )
         .unwrap();
     }
 }
 
+// With several machines, the subproblems are grouped by the machine that solves
+// them.
 fn write_subproblem_nodes(problem: &Problem, dot: &mut String) {
+    let machines: Vec<&str> = problem
+        .domains
+        .iter()
+        .filter(|domain| domain.role == DomainRole::Machine)
+        .map(|domain| domain.name.as_str())
+        .collect();
+    let solved_by = |subproblem: &Subproblem, machine: &str| {
+        machines.len() > 1
+            && subproblem
+                .machine
+                .as_ref()
+                .is_some_and(|solver| solver.name == machine)
+    };
+    for (index, machine) in machines.iter().enumerate() {
+        let subproblems: Vec<&Subproblem> = problem
+            .subproblems
+            .iter()
+            .filter(|subproblem| solved_by(subproblem, machine))
+            .collect();
+        if subproblems.is_empty() {
+            continue;
+        }
+        writeln!(dot, "    subgraph \"cluster_machine_{index}\" {{").unwrap();
+        writeln!(dot, "        label=\"{}\";", escape_dot_string(machine)).unwrap();
+        writeln!(dot, "        style=rounded;").unwrap();
+        for subproblem in subproblems {
+            write_subproblem_node(subproblem, dot, "        ");
+        }
+        writeln!(dot, "    }}").unwrap();
+    }
+
     for subproblem in &problem.subproblems {
-        writeln!(
-            dot,
-            "    \"subproblem:{}\" [shape=folder, fillcolor=lightyellow, label=\"{}\"];",
-            escape_dot_string(&subproblem.name),
-            escape_dot_string(&format!("subproblem\\n{}", subproblem.name))
-        )
-        .unwrap();
+        if !machines
+            .iter()
+            .any(|machine| solved_by(subproblem, machine))
+        {
+            write_subproblem_node(subproblem, dot, "    ");
+        }
+    }
 
+    for subproblem in &problem.subproblems {
         if let Some(machine) = &subproblem.machine {
             writeln!(
                 dot,
@@ -198,17 +443,40 @@ fn write_subproblem_nodes(problem: &Problem, dot: &mut String) {
         }
 
         for requirement in &subproblem.requirements {
+            let key = problem
+                .requirement(&requirement.name)
+                .map(|requirement| requirement.key())
+                .unwrap_or(requirement.name.as_str());
             writeln!(
                 dot,
                 "    \"subproblem:{}\" -> \"{}\" [label=\"includes\", style=dashed];",
                 escape_dot_string(&subproblem.name),
-                escape_dot_string(&requirement.name)
+                escape_dot_string(key)
             )
             .unwrap();
         }
     }
 }
 
+fn write_subproblem_node(subproblem: &Subproblem, dot: &mut String, indent: &str) {
+    writeln!(
+        dot,
+        "{}\"subproblem:{}\" [shape=folder, fillcolor=lightyellow, label=\"subproblem\\n{}\"{}];",
+        indent,
+        escape_dot_string(&subproblem.name),
+        escape_dot_string(&subproblem.name),
+        tooltip_attribute(&subproblem.doc)
+    )
+    .unwrap();
+}
+
+fn tooltip_attribute(doc: &Option<String>) -> String {
+    match doc {
+        Some(doc) => format!(", tooltip=\"{}\"", escape_dot_string(doc)),
+        None => String::new(),
+    }
+}
+
 fn escape_dot_string(input: &str) -> String {
     let mut escaped = String::with_capacity(input.len() + 4);
     for ch in input.chars() {
@@ -226,19 +494,31 @@ fn escape_dot_string(input: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{to_context_dot, to_decomposition_dot, to_dot, to_problem_dot};
+    use super::{
+        to_behavior_dot, to_context_dot, to_decomposition_dot, to_dot, to_imports_dot,
+        to_problem_dot,
+    };
     use crate::ast::*;
+    use crate::import_graph::ImportEdge;
+    use std::path::{Path, PathBuf};
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn domain(name: &str, kind: DomainKind, role: DomainRole) -> Domain {
         Domain {
             name: name.to_string(),
+            doc: None,
             kind,
             role,
             marks: vec![],
+            behavior: None,
+            schema: None,
             span: span(),
             source_path: None,
         }
@@ -257,6 +537,8 @@ mod tests {
             name: "P".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("A", DomainKind::Causal, DomainRole::Machine),
                 domain("B", DomainKind::Causal, DomainRole::Given),
@@ -265,6 +547,7 @@ mod tests {
             ],
             interfaces: vec![Interface {
                 name: "mixed".to_string(),
+                doc: None,
                 connects: vec![
                     reference("A"),
                     reference("B"),
@@ -274,6 +557,8 @@ mod tests {
                 shared_phenomena: vec![
                     Phenomenon {
                         name: "e1".to_string(),
+                        doc: None,
+                        parameters: vec![],
                         type_: PhenomenonType::Event,
                         from: reference("A"),
                         to: reference("B"),
@@ -282,6 +567,8 @@ mod tests {
                     },
                     Phenomenon {
                         name: "e2".to_string(),
+                        doc: None,
+                        parameters: vec![],
                         type_: PhenomenonType::Event,
                         from: reference("C"),
                         to: reference("D"),
@@ -296,6 +583,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let dot = to_dot(&problem);
@@ -309,15 +598,20 @@ mod tests {
             name: "ContextOnly".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Sensor", DomainKind::Causal, DomainRole::Given),
             ],
             interfaces: vec![Interface {
                 name: "M-S".to_string(),
+                doc: None,
                 connects: vec![reference("Machine"), reference("Sensor")],
                 shared_phenomena: vec![Phenomenon {
                     name: "Observe".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Event,
                     from: reference("Sensor"),
                     to: reference("Machine"),
@@ -329,6 +623,11 @@ mod tests {
             }],
             requirements: vec![Requirement {
                 name: "ReqA".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(reference("Sensor")),
                 reference: None,
@@ -341,6 +640,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let dot = to_context_dot(&problem);
@@ -356,6 +657,8 @@ mod tests {
             name: "ProblemView".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Operator", DomainKind::Biddable, DomainRole::Given),
@@ -363,6 +666,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "ReqB".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::CommandedBehavior,
                 constrains: Some(reference("Machine")),
                 reference: Some(reference("Operator")),
@@ -375,6 +683,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let dot = to_problem_dot(&problem);
@@ -389,15 +699,20 @@ mod tests {
             name: "DecompositionView".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Ledger", DomainKind::Lexical, DomainRole::Given),
             ],
             interfaces: vec![Interface {
                 name: "M-L".to_string(),
+                doc: None,
                 connects: vec![reference("Machine"), reference("Ledger")],
                 shared_phenomena: vec![Phenomenon {
                     name: "Persist".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Value,
                     from: reference("Machine"),
                     to: reference("Ledger"),
@@ -409,6 +724,11 @@ mod tests {
             }],
             requirements: vec![Requirement {
                 name: "ReqStore".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::Transformation,
                 constrains: Some(reference("Ledger")),
                 reference: None,
@@ -420,6 +740,7 @@ mod tests {
             }],
             subproblems: vec![Subproblem {
                 name: "StorageFlow".to_string(),
+                doc: None,
                 machine: Some(reference("Machine")),
                 participants: vec![reference("Machine"), reference("Ledger")],
                 requirements: vec![reference("ReqStore")],
@@ -428,6 +749,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let dot = to_decomposition_dot(&problem);
@@ -451,10 +774,17 @@ mod tests {
             name: "P".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain(r#"D"1"#, DomainKind::Causal, DomainRole::Given)],
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "Req\n1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: None,
                 reference: None,
@@ -467,17 +797,179 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let dot = to_dot(&problem);
         let escaped_domain_id = super::escape_dot_string("D\"1");
         let escaped_domain_label = super::escape_dot_string("D\"1 <<Causal/Given>>");
-        let escaped_req_label =
-            super::escape_dot_string(&format!("{}\\n[{}]", "Req\n1", "RequiredBehavior"));
+        let escaped_req_label = r"Req\n1\n[RequiredBehavior]";
 
         assert!(dot.contains(&format!("\"{}\"", escaped_domain_id)));
         assert!(dot.contains(&format!(r#"label="{}","#, escaped_domain_label)));
         assert!(dot.contains(&format!(r#"label="{}""#, escaped_req_label)));
         assert!(dot.contains(r#"digraph "P" {"#));
     }
+
+    #[test]
+    fn doc_comments_become_tooltips() {
+        let mut documented = domain("Gate", DomainKind::Causal, DomainRole::Given);
+        documented.doc = Some("Sluice \"gate\"\nMotor driven".to_string());
+        let problem = Problem {
+            name: "P".to_string(),
+            span: span(),
+            imports: vec![],
+            frames: vec![],
+            types: vec![],
+            domains: vec![
+                documented,
+                domain("M", DomainKind::Causal, DomainRole::Machine),
+            ],
+            interfaces: vec![],
+            requirements: vec![],
+            subproblems: vec![],
+            assertion_sets: vec![],
+            correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
+        };
+
+        let dot = to_context_dot(&problem);
+        assert!(dot.contains(r#"fillcolor=white, tooltip="Sluice \"gate\"\nMotor driven"];"#));
+        assert!(dot.contains(r#"fillcolor=lightgrey];"#));
+    }
+
+    #[test]
+    fn behavior_view_draws_state_machines_of_causal_domains() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: GateStates
+            domain Controller kind causal role machine
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                on PulseOpen from Closed to Open emits GateOpened
+                on PulseClose from Open to Closed
+            }
+            domain Operator kind biddable role given
+            "#,
+        )
+        .expect("parse failed");
+
+        let dot = to_behavior_dot(&problem);
+        assert!(dot.contains("subgraph \"cluster_Gate\" {"));
+        assert!(dot.contains("\"Gate:<initial>\" -> \"Gate:Closed\";"));
+        assert!(
+            dot.contains("\"Gate:Closed\" -> \"Gate:Open\" [label=\"PulseOpen / GateOpened\"];")
+        );
+        assert!(dot.contains("\"Gate:Open\" -> \"Gate:Closed\" [label=\"PulseClose\"];"));
+        assert!(!dot.contains("Operator"));
+        assert!(!dot.contains("Controller"));
+    }
+
+    #[test]
+    fn imports_view_draws_files_and_marks_cycles_and_repeats() {
+        let mut problem = crate::parser::parse("problem: Plant").expect("parse failed");
+        let edge = |from: &str, to: &str, alias: Option<&str>, start: usize| ImportEdge {
+            from: PathBuf::from(from),
+            to: PathBuf::from(to),
+            path: to.to_string(),
+            alias: alias.map(str::to_string),
+            items: vec![],
+            span: Span {
+                start,
+                end: start,
+                file: FileId::default(),
+            },
+        };
+        let graph = &mut problem.import_graph;
+        graph.add_file(Path::new("/work/root.pf"));
+        graph.add_edge(edge("/work/root.pf", "/work/lib/a.pf", Some("A"), 0));
+        graph.add_edge(edge("/work/lib/a.pf", "/work/root.pf", None, 0));
+        graph.add_edge(edge("/work/root.pf", "/work/lib/a.pf", Some("A"), 20));
+        graph.add_cycle(vec![0, 1]);
+        graph.add_redundant(2, 0);
+
+        let dot = to_imports_dot(&problem);
+        assert!(dot.contains("\"root.pf\" [label=\"root.pf\", style=bold];"));
+        assert!(dot.contains("\"lib/a.pf\" [label=\"lib/a.pf\"];"));
+        assert!(dot.contains("\"root.pf\" -> \"lib/a.pf\" [label=\"as A\"];"));
+        assert!(dot.contains("\"lib/a.pf\" -> \"root.pf\" [color=red];"));
+        assert!(dot.contains("\"root.pf\" -> \"lib/a.pf\" [label=\"as A\", style=dashed];"));
+    }
+
+    #[test]
+    fn domains_from_several_files_are_clustered_by_file() {
+        let mut problem = crate::parser::parse("problem: Plant").expect("parse failed");
+        let entry = problem.sources.add("/work/root.pf", "");
+        let library = problem.sources.add("/work/lib/gates.pf", "");
+        problem.import_graph.add_file(Path::new("/work/root.pf"));
+        let mut gate = domain("Gate", DomainKind::Causal, DomainRole::Given);
+        gate.span.file = library;
+        let mut machine = domain("Controller", DomainKind::Causal, DomainRole::Machine);
+        machine.span.file = entry;
+        problem.domains = vec![gate, machine];
+
+        let dot = to_context_dot(&problem);
+        assert!(dot.contains("subgraph \"cluster_file_0\" {\n        label=\"root.pf\";"));
+        assert!(dot.contains("subgraph \"cluster_file_1\" {\n        label=\"lib/gates.pf\";"));
+        assert!(dot.contains("        \"Gate\" [label="));
+
+        problem.domains.retain(|d| d.name == "Gate");
+        assert!(!to_context_dot(&problem).contains("cluster_file_"));
+    }
+
+    #[test]
+    fn decomposition_view_groups_subproblems_by_machine() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: Crossing
+            domain GateController kind causal role machine
+            domain LightController kind causal role machine
+            domain Gate kind causal role given
+            domain Light kind causal role given
+            interface "GateController-Gate" connects GateController, Gate {
+                shared: {
+                    phenomenon Lower : event [GateController -> Gate] controlledBy GateController
+                }
+            }
+            interface "LightController-Light" connects LightController, Light {
+                shared: {
+                    phenomenon Flash : event [LightController -> Light] controlledBy LightController
+                    phenomenon Burnt : state [Light -> LightController] controlledBy Light
+                }
+            }
+            requirement "Lower gate" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            requirement "Flash light" {
+                frame: RequiredBehavior
+                constrains: Light
+            }
+            subproblem GateControl {
+                machine: GateController
+                participants: GateController, Gate
+                requirements: "Lower gate"
+            }
+            subproblem LightControl {
+                machine: LightController
+                participants: LightController, Light
+                requirements: "Flash light"
+            }
+            "#,
+        )
+        .expect("parse failed");
+
+        let dot = to_decomposition_dot(&problem);
+        assert!(dot.contains(
+            "    subgraph \"cluster_machine_0\" {\n        label=\"GateController\";\n        style=rounded;\n        \"subproblem:GateControl\""
+        ));
+        assert!(dot.contains(
+            "    subgraph \"cluster_machine_1\" {\n        label=\"LightController\";\n        style=rounded;\n        \"subproblem:LightControl\""
+        ));
+        assert!(dot.contains(
+            "\"subproblem:LightControl\" -> \"LightController\" [label=\"machine\", style=bold];"
+        ));
+    }
 }
@@ -0,0 +1,346 @@
+use crate::ast::*;
+use crate::formatter::quote;
+use crate::validator::ValidationError;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+// Replacing `span` with `replacement` resolves the finding it is attached to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+}
+
+// Machine-applicable fixes for references to names that are not declared: the
+// closest name of the same kind that the file holding the reference can use.
+pub fn fixes_for(problem: &Problem, error: &ValidationError) -> Vec<Fix> {
+    let fix = match error {
+        ValidationError::UndefinedDomainInInterface(name, _, span, _)
+        | ValidationError::UndefinedDomainInRequirement(name, _, span)
+        | ValidationError::UndefinedDomainInSubproblem(name, _, span) => {
+            let scope = Scope::of(problem, span.file);
+            let domains = problem
+                .domains
+                .iter()
+                .filter_map(|domain| scope.name(&domain.name, domain.span));
+            did_you_mean(name, domains).map(|domain| Fix {
+                span: *span,
+                replacement: domain.to_string(),
+            })
+        }
+        ValidationError::UndefinedRequirementInSubproblem(name, _, span) => {
+            let scope = Scope::of(problem, span.file);
+            let requirements = problem
+                .requirements
+                .iter()
+                .filter_map(|requirement| scope.name(&requirement.name, requirement.span));
+            did_you_mean(name, requirements).map(|requirement| Fix {
+                span: *span,
+                replacement: quote(requirement),
+            })
+        }
+        ValidationError::InvalidCorrectnessArgument(name, message, span) => {
+            correctness_argument_fix(problem, name, message, *span)
+        }
+        _ => None,
+    };
+    fix.into_iter().collect()
+}
+
+// An undefined assertion set is reported on the whole argument; the message
+// tells which of its three references it is. Only sets of the scope expected at
+// that position are suggested.
+fn correctness_argument_fix(
+    problem: &Problem,
+    name: &str,
+    message: &str,
+    span: Span,
+) -> Option<Fix> {
+    let argument = problem
+        .correctness_arguments
+        .iter()
+        .find(|argument| argument.name == name && argument.span == span)?;
+    let (reference, scope) = [
+        (
+            "specification set",
+            &argument.specification_ref,
+            AssertionScope::Specification,
+        ),
+        (
+            "world set",
+            &argument.world_ref,
+            AssertionScope::WorldProperties,
+        ),
+        (
+            "requirement set",
+            &argument.requirement_ref,
+            AssertionScope::RequirementAssertions,
+        ),
+    ]
+    .into_iter()
+    .find(|(label, reference, _)| message == format!("{label} '{}' is not defined", reference.name))
+    .map(|(_, reference, scope)| (reference, scope))?;
+    let visible = Scope::of(problem, reference.span.file);
+    let sets = problem
+        .assertion_sets
+        .iter()
+        .filter(|set| set.scope == scope)
+        .filter_map(|set| visible.name(&set.name, set.span));
+    did_you_mean(&reference.name, sets).map(|set| Fix {
+        span: reference.span,
+        replacement: set.to_string(),
+    })
+}
+
+// What the file holding a reference can name: its own declarations and those of
+// the files it imports, spelled as that file spells them, plus the unqualified
+// names of the merged model that its unqualified references fall through to.
+// Every merged copy of a file is identified by the alias prefix the merge put on
+// its names.
+struct Scope {
+    // `None` for a model parsed from a string
+    copies: Option<Vec<(FileId, String)>>,
+    visible: HashSet<(FileId, String)>,
+    prefix: String,
+}
+
+impl Scope {
+    fn of(problem: &Problem, file: FileId) -> Scope {
+        let graph = &problem.import_graph;
+        let unrestricted = Scope {
+            copies: None,
+            visible: HashSet::new(),
+            prefix: String::new(),
+        };
+        let Some(entry) = graph.files.first() else {
+            return unrestricted;
+        };
+        let file_of = |path: &Path| {
+            problem.sources.file_id(path).or_else(|| {
+                // the graph records the entry canonicalized
+                (path == entry)
+                    .then(|| problem.sources.files().next().map(|(id, _)| id))
+                    .flatten()
+            })
+        };
+        let copies_from = |start: (PathBuf, String)| -> Vec<(PathBuf, String)> {
+            let mut copies = vec![start.clone()];
+            let mut queue = VecDeque::from([start]);
+            while let Some((from, prefix)) = queue.pop_front() {
+                let edges = graph
+                    .edges
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, edge)| edge.from == from && graph.is_followed(*index));
+                for (_, edge) in edges {
+                    let copy = match &edge.alias {
+                        Some(alias) => (edge.to.clone(), format!("{prefix}{alias}.")),
+                        None => (edge.to.clone(), prefix.clone()),
+                    };
+                    if !copies.contains(&copy) {
+                        copies.push(copy.clone());
+                        queue.push_back(copy);
+                    }
+                }
+            }
+            copies
+        };
+
+        let copies = copies_from((entry.clone(), String::new()));
+        let Some((path, prefix)) = copies
+            .iter()
+            .filter(|(path, _)| file_of(path) == Some(file))
+            .min_by_key(|(_, prefix)| prefix.len())
+            .cloned()
+        else {
+            return unrestricted;
+        };
+        let with_ids = |copies: Vec<(PathBuf, String)>| -> Vec<(FileId, String)> {
+            copies
+                .into_iter()
+                .filter_map(|(path, prefix)| Some((file_of(&path)?, prefix)))
+                .collect()
+        };
+        Scope {
+            visible: with_ids(copies_from((path, prefix.clone())))
+                .into_iter()
+                .collect(),
+            copies: Some(with_ids(copies)),
+            prefix,
+        }
+    }
+
+    // The name a declaration has in this scope, if it is in it.
+    fn name<'a>(&self, name: &'a str, span: Span) -> Option<&'a str> {
+        let Some(copies) = &self.copies else {
+            return Some(name);
+        };
+        let copy = copies
+            .iter()
+            .filter(|(file, prefix)| *file == span.file && name.starts_with(prefix.as_str()))
+            .max_by_key(|(_, prefix)| prefix.len())?;
+        if self.visible.contains(copy) {
+            name.strip_prefix(self.prefix.as_str())
+        } else if copy.1.is_empty() {
+            Some(name)
+        } else {
+            None
+        }
+    }
+}
+
+// The candidate closest to `name`, ignoring case, within a third of its length
+// in single-character edits.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+    let name = name.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+// Applies the fixes that lie in `file` to its text, skipping any that overlap a
+// fix further on in the text.
+pub fn apply_fixes<'a>(
+    text: &str,
+    file: FileId,
+    fixes: impl IntoIterator<Item = &'a Fix>,
+) -> String {
+    let mut fixes: Vec<&Fix> = fixes
+        .into_iter()
+        .filter(|fix| fix.span.file == file)
+        .collect();
+    fixes.sort_by_key(|fix| std::cmp::Reverse(fix.span.start));
+    let mut text = text.to_string();
+    let mut limit = text.len();
+    for fix in fixes {
+        if fix.span.end > limit {
+            continue;
+        }
+        text.replace_range(fix.span.start..fix.span.end, &fix.replacement);
+        limit = fix.span.start;
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::resolve_with_files;
+    use crate::validator::validate_with_sources;
+    use crate::vfs::MemoryFiles;
+    use std::path::Path;
+
+    const MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+interface "Controller-Gate" connects Controler, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+  }
+}
+requirement "Open gate" {
+  frame: RequiredBehavior
+  constrains: gate
+}
+subproblem GateControl {
+  machine: Controller
+  participants: Controller, Gate
+  requirements: "Open gates"
+}
+specification S {
+  assert "the controller pulses the gate"
+}
+worldProperties World {
+  assert "a pulsed gate opens"
+}
+requirementAssertions R {
+  assert "the gate opens"
+}
+correctnessArgument A {
+  prove S and Wrld entail R
+}
+"#;
+
+    #[test]
+    fn suggests_declared_names_and_applies_the_fixes() {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/plant.pf", MODEL);
+        let problem =
+            resolve_with_files(Path::new("/work/plant.pf"), &files).expect("model resolves");
+        let issues = validate_with_sources(&problem).expect_err("misspelled references");
+
+        let helps: Vec<String> = issues.iter().filter_map(|issue| issue.help()).collect();
+        assert!(helps.contains(&"did you mean `Controller`?".to_string()));
+        assert!(helps.contains(&"did you mean `Gate`?".to_string()));
+        assert!(helps.contains(&"did you mean `\"Open gate\"`?".to_string()));
+
+        let file = problem
+            .sources
+            .file_id(Path::new("/work/plant.pf"))
+            .unwrap();
+        let fixed = apply_fixes(MODEL, file, issues.iter().flat_map(|issue| &issue.fixes));
+        assert_eq!(
+            fixed,
+            MODEL
+                .replace("Controler", "Controller")
+                .replace("constrains: gate", "constrains: Gate")
+                .replace("Open gates", "Open gate")
+                .replace("Wrld", "World")
+        );
+    }
+
+    #[test]
+    fn suggests_names_as_the_declaring_file_spells_them() {
+        let mut files = MemoryFiles::new();
+        files.insert(
+            "/work/lib.pf",
+            "domain Controller kind causal role machine\ndomain Gate kind causal role given\ninterface \"Drive\" connects Controler, Gate {\n  shared: {\n    phenomenon Open : event [Controller -> Gate] controlledBy Controller\n  }\n}\n",
+        );
+        files.insert(
+            "/work/plant.pf",
+            "problem: Plant\nimport \"lib.pf\" as Lib\ndomain Valve kind causal role given\nrequirement \"Shut\" {\n  frame: RequiredBehavior\n  constrains: Lib.Gat\n}\n",
+        );
+        let problem =
+            resolve_with_files(Path::new("/work/plant.pf"), &files).expect("model resolves");
+        let issues = validate_with_sources(&problem).expect_err("misspelled references");
+        let fixes: Vec<&Fix> = issues.iter().flat_map(|issue| &issue.fixes).collect();
+        let replacements: Vec<&str> = fixes.iter().map(|fix| fix.replacement.as_str()).collect();
+        assert!(replacements.contains(&"Controller"), "{replacements:?}");
+        assert!(replacements.contains(&"Lib.Gate"), "{replacements:?}");
+        assert!(!replacements.contains(&"Lib.Controller"));
+
+        let lib = problem.sources.file_id(Path::new("/work/lib.pf")).unwrap();
+        let text = problem.sources.file(lib).unwrap().text();
+        assert!(apply_fixes(text, lib, fixes).contains("connects Controller, Gate {"));
+    }
+
+    #[test]
+    fn did_you_mean_keeps_to_close_candidates() {
+        let names = ["Gate", "Controller", "Operator"];
+        assert_eq!(did_you_mean("Gat", names), Some("Gate"));
+        assert_eq!(did_you_mean("operater", names), Some("Operator"));
+        assert_eq!(did_you_mean("Valve", names), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
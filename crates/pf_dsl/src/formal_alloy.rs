@@ -6,6 +6,9 @@ pub fn generate_alloy(problem: &Problem) -> String {
     let module_name = sanitize_name(&problem.name);
 
     writeln!(&mut output, "module {}", module_name).unwrap();
+    if uses_boolean(problem) {
+        writeln!(&mut output, "open util/boolean").unwrap();
+    }
     writeln!(&mut output).unwrap();
     writeln!(&mut output, "abstract sig Domain {{}}").unwrap();
     writeln!(&mut output).unwrap();
@@ -19,6 +22,13 @@ pub fn generate_alloy(problem: &Problem) -> String {
         .unwrap();
     }
 
+    if !problem.types.is_empty() {
+        writeln!(&mut output).unwrap();
+        for data_type in &problem.types {
+            write_type_sig(&mut output, data_type);
+        }
+    }
+
     writeln!(&mut output).unwrap();
     writeln!(
         &mut output,
@@ -33,7 +43,13 @@ pub fn generate_alloy(problem: &Problem) -> String {
                 phenomenon_index,
                 sanitize_name(&phenomenon.name)
             );
-            writeln!(&mut output, "one sig {} extends Phenomenon {{}}", symbol).unwrap();
+            writeln!(
+                &mut output,
+                "one sig {} extends Phenomenon {{{}}}",
+                symbol,
+                alloy_fields(&phenomenon.parameters)
+            )
+            .unwrap();
             writeln!(
                 &mut output,
                 "fact {}_mapping {{ {}.from = {} and {}.to = {} and {}.controlledBy = {} }}",
@@ -49,15 +65,30 @@ pub fn generate_alloy(problem: &Problem) -> String {
         }
     }
 
+    for domain in &problem.domains {
+        if let Some(behavior) = &domain.behavior {
+            write_behavior(&mut output, problem, domain, behavior);
+        }
+    }
+
     writeln!(&mut output).unwrap();
     writeln!(&mut output, "// Requirement metadata").unwrap();
     for requirement in &problem.requirements {
-        writeln!(
-            &mut output,
-            "// - {} [{}]",
-            requirement.name,
-            frame_name(&requirement.frame)
-        )
+        match &requirement.id {
+            Some(id) => writeln!(
+                &mut output,
+                "// - {} \"{}\" [{}]",
+                id.name,
+                comment_text(&requirement.name),
+                frame_name(&requirement.frame)
+            ),
+            None => writeln!(
+                &mut output,
+                "// - {} [{}]",
+                comment_text(&requirement.name),
+                frame_name(&requirement.frame)
+            ),
+        }
         .unwrap();
     }
 
@@ -79,7 +110,12 @@ pub fn generate_alloy(problem: &Problem) -> String {
             let requirements = subproblem
                 .requirements
                 .iter()
-                .map(|requirement| requirement.name.as_str())
+                .map(|requirement| {
+                    problem
+                        .requirement(&requirement.name)
+                        .map(|requirement| requirement.key())
+                        .unwrap_or(requirement.name.as_str())
+                })
                 .collect::<Vec<_>>()
                 .join(", ");
             writeln!(
@@ -110,14 +146,14 @@ pub fn generate_alloy(problem: &Problem) -> String {
                 && matches!(set.scope, AssertionScope::Specification)
         }) {
             for assertion in &spec_set.assertions {
-                writeln!(&mut output, "  // S: {}", assertion.text).unwrap();
+                writeln!(&mut output, "  // S: {}", comment_text(&assertion.text)).unwrap();
             }
         }
         if let Some(world_set) = problem.assertion_sets.iter().find(|set| {
             set.name == argument.world_set && matches!(set.scope, AssertionScope::WorldProperties)
         }) {
             for assertion in &world_set.assertions {
-                writeln!(&mut output, "  // W: {}", assertion.text).unwrap();
+                writeln!(&mut output, "  // W: {}", comment_text(&assertion.text)).unwrap();
             }
         }
         if let Some(req_set) = problem.assertion_sets.iter().find(|set| {
@@ -125,7 +161,7 @@ pub fn generate_alloy(problem: &Problem) -> String {
                 && matches!(set.scope, AssertionScope::RequirementAssertions)
         }) {
             for assertion in &req_set.assertions {
-                writeln!(&mut output, "  // R: {}", assertion.text).unwrap();
+                writeln!(&mut output, "  // R: {}", comment_text(&assertion.text)).unwrap();
             }
         }
 
@@ -178,6 +214,224 @@ pub fn generate_alloy(problem: &Problem) -> String {
     output
 }
 
+fn phenomenon_symbol(problem: &Problem, matches: impl Fn(&Phenomenon) -> bool) -> Option<String> {
+    problem
+        .interfaces
+        .iter()
+        .enumerate()
+        .find_map(|(index, interface)| {
+            interface
+                .shared_phenomena
+                .iter()
+                .enumerate()
+                .find(|(_, phenomenon)| matches(phenomenon))
+                .map(|(phenomenon_index, phenomenon)| {
+                    format!(
+                        "Phen_{}_{}_{}",
+                        index,
+                        phenomenon_index,
+                        sanitize_name(&phenomenon.name)
+                    )
+                })
+        })
+}
+
+fn write_behavior(output: &mut String, problem: &Problem, domain: &Domain, behavior: &Behavior) {
+    let domain_name = sanitize_name(&domain.name);
+    let state_sig = format!("{}_State", domain_name);
+    let state = |name: &str| sanitize_name(&format!("{}_{}", domain_name, name));
+
+    writeln!(output).unwrap();
+    writeln!(output, "// Behavior of {}", domain.name).unwrap();
+    writeln!(output, "abstract sig {} {{}}", state_sig).unwrap();
+    if !behavior.states.is_empty() {
+        let states = behavior
+            .states
+            .iter()
+            .map(|reference| state(&reference.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(output, "one sig {} extends {} {{}}", states, state_sig).unwrap();
+    }
+    if let Some(initial) = behavior.states.first() {
+        writeln!(
+            output,
+            "fun {}_initial: {} {{ {} }}",
+            domain_name,
+            state_sig,
+            state(&initial.name)
+        )
+        .unwrap();
+    }
+
+    // Unresolved phenomena map to `none`, which keeps the guard unsatisfiable.
+    let guards = behavior
+        .transitions
+        .iter()
+        .map(|transition| {
+            let trigger = phenomenon_symbol(problem, |p| {
+                p.name == transition.trigger.name && p.to.name == domain.name
+            })
+            .unwrap_or_else(|| "none".to_string());
+            format!(
+                "p = {} and s = {} and s2 = {}",
+                trigger,
+                state(&transition.from.name),
+                state(&transition.to.name)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    writeln!(
+        output,
+        "pred {}_step[s, s2: {}, p: Phenomenon] {{",
+        domain_name, state_sig
+    )
+    .unwrap();
+    if guards.is_empty() {
+        writeln!(output, "  some none").unwrap();
+    }
+    for (index, guard) in guards.iter().enumerate() {
+        let prefix = if index == 0 { "" } else { "or " };
+        writeln!(output, "  {}({})", prefix, guard).unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+
+    let emitting = behavior
+        .transitions
+        .iter()
+        .zip(&guards)
+        .filter(|(transition, _)| !transition.emits.is_empty())
+        .collect::<Vec<_>>();
+    if emitting.is_empty() {
+        return;
+    }
+    writeln!(
+        output,
+        "fun {}_emits[s, s2: {}, p: Phenomenon]: set Phenomenon {{",
+        domain_name, state_sig
+    )
+    .unwrap();
+    for (index, (transition, guard)) in emitting.iter().enumerate() {
+        let emitted = transition
+            .emits
+            .iter()
+            .map(|reference| {
+                phenomenon_symbol(problem, |p| {
+                    p.name == reference.name && p.controlled_by.name == domain.name
+                })
+                .unwrap_or_else(|| "none".to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let prefix = if index == 0 { "" } else { "+ " };
+        writeln!(
+            output,
+            "  {}(({}) => ({}) else none)",
+            prefix, guard, emitted
+        )
+        .unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+}
+
+fn write_type_sig(output: &mut String, data_type: &DataType) {
+    let name = sanitize_name(&data_type.name);
+    match &data_type.definition {
+        TypeDefinition::Enum(literals) => {
+            writeln!(output, "abstract sig {} {{}}", name).unwrap();
+            let literals = literals
+                .iter()
+                .map(|literal| sanitize_name(&format!("{}_{}", name, literal.name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(output, "one sig {} extends {} {{}}", literals, name).unwrap();
+        }
+        TypeDefinition::Record(fields) => {
+            writeln!(output, "sig {} {{{}}}", name, alloy_fields(fields)).unwrap();
+        }
+        TypeDefinition::Range { min, max, unit } => {
+            writeln!(output, "sig {} {{ value: one Int }}", name).unwrap();
+            // Alloy only has integers, so fractional bounds stay as a comment.
+            if min.fract() == 0.0 && max.fract() == 0.0 {
+                writeln!(
+                    output,
+                    "fact {}_range {{ all x: {} | x.value >= {} and x.value <= {} }}",
+                    name, name, min, max
+                )
+                .unwrap();
+            } else {
+                writeln!(output, "// {} ranges over {}..{}", name, min, max).unwrap();
+            }
+            if let Some(unit) = unit {
+                writeln!(output, "// {} unit: {}", name, comment_text(unit)).unwrap();
+            }
+        }
+        TypeDefinition::Unit(unit) => {
+            writeln!(output, "sig {} {{ value: one Int }}", name).unwrap();
+            writeln!(output, "// {} unit: {}", name, comment_text(unit)).unwrap();
+        }
+    }
+}
+
+fn alloy_fields(fields: &[TypedField]) -> String {
+    if fields.is_empty() {
+        return String::new();
+    }
+    let fields = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{}: one {}",
+                sanitize_field_name(&field.name),
+                alloy_type_name(&field.type_ref.name)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" {} ", fields)
+}
+
+fn alloy_type_name(type_name: &str) -> String {
+    match type_name {
+        "Integer" | "Decimal" => "Int".to_string(),
+        "Boolean" => "Bool".to_string(),
+        "String" => "String".to_string(),
+        _ => sanitize_name(type_name),
+    }
+}
+
+fn uses_boolean(problem: &Problem) -> bool {
+    let record_fields = problem
+        .types
+        .iter()
+        .filter_map(|data_type| match &data_type.definition {
+            TypeDefinition::Record(fields) => Some(fields),
+            _ => None,
+        })
+        .flatten();
+    let parameters = problem
+        .interfaces
+        .iter()
+        .flat_map(|interface| &interface.shared_phenomena)
+        .flat_map(|phenomenon| &phenomenon.parameters);
+    record_fields
+        .chain(parameters)
+        .any(|field| field.type_ref.name == "Boolean")
+}
+
+// Alloy fields are relations, so they stay lower-case and avoid keywords.
+fn sanitize_field_name(name: &str) -> String {
+    let mut output = sanitize_name(name);
+    if let Some(first) = output.chars().next() {
+        output.replace_range(0..first.len_utf8(), &first.to_ascii_lowercase().to_string());
+    }
+    if is_alloy_keyword(&output) {
+        output.push('_');
+    }
+    output
+}
+
 fn find_alloy_assertions<'a>(
     problem: &'a Problem,
     set_name: &str,
@@ -214,6 +468,11 @@ fn frame_name(frame: &FrameType) -> &str {
     }
 }
 
+// Line comments end at the first line break, so multi-line strings are folded.
+fn comment_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 fn sanitize_name(name: &str) -> String {
     let mut output = String::with_capacity(name.len());
     for ch in name.chars() {
@@ -318,15 +577,22 @@ mod tests {
     use crate::ast::*;
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn domain(name: &str, kind: DomainKind, role: DomainRole) -> Domain {
         Domain {
             name: name.to_string(),
+            doc: None,
             kind,
             role,
             marks: vec![],
+            behavior: None,
+            schema: None,
             span: span(),
             source_path: None,
         }
@@ -346,6 +612,8 @@ mod tests {
             name: "1 bad thermostat".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("my domain", DomainKind::Causal, DomainRole::Machine),
                 domain("sig", DomainKind::Causal, DomainRole::Given),
@@ -353,6 +621,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "req".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 phenomena: vec![],
                 marks: vec![],
@@ -366,6 +639,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![],
                     span: span(),
@@ -373,6 +647,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![],
                     span: span(),
@@ -380,6 +655,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![],
                     span: span(),
@@ -406,6 +682,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let output = generate_alloy(&problem);
@@ -427,15 +705,20 @@ mod tests {
             name: "Thermostat".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Room", DomainKind::Causal, DomainRole::Given),
             ],
             interfaces: vec![Interface {
                 name: "M-R".to_string(),
+                doc: None,
                 connects: vec![reference("Machine"), reference("Room")],
                 shared_phenomena: vec![Phenomenon {
                     name: "Observe".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Value,
                     from: reference("Room"),
                     to: reference("Machine"),
@@ -447,6 +730,11 @@ mod tests {
             }],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 phenomena: vec![],
                 marks: vec![],
@@ -460,6 +748,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S_control".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "controller observes room".to_string(),
@@ -471,6 +760,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W_base".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "room physics is stable".to_string(),
@@ -482,6 +772,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R_goal".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "room reaches target".to_string(),
@@ -503,6 +794,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let alloy = generate_alloy(&problem);
@@ -518,6 +811,8 @@ mod tests {
             name: "Adequacy".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Device", DomainKind::Causal, DomainRole::Given),
@@ -528,6 +823,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S_control".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "some Machine".to_string(),
@@ -539,6 +835,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W_base".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "some Device".to_string(),
@@ -550,6 +847,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R_goal".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "some Device".to_string(),
@@ -571,6 +869,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let alloy = generate_alloy(&problem);
@@ -578,4 +878,69 @@ mod tests {
         assert!(alloy.contains("(some Device)"));
         assert!(alloy.contains("not (some Device)"));
     }
+
+    #[test]
+    fn emits_type_signatures_and_phenomenon_parameters() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: TypedAlloy
+            type Percent = range 0..100 unit "%"
+            type Mode = enum { Auto, Manual }
+            type Setting = record { level: Percent, locked: Boolean }
+            domain Tool kind causal role machine
+            domain Operator kind biddable role given
+            interface "Operator-Tool" connects Operator, Tool {
+                shared: {
+                    phenomenon Configure(setting: Setting, mode: Mode) : command [Operator -> Tool] controlledBy Operator
+                }
+            }
+            "#,
+        )
+        .expect("parse failed");
+
+        let alloy = generate_alloy(&problem);
+        assert!(alloy.contains("open util/boolean"));
+        assert!(alloy.contains("sig Percent { value: one Int }"));
+        assert!(alloy
+            .contains("fact Percent_range { all x: Percent | x.value >= 0 and x.value <= 100 }"));
+        assert!(alloy.contains("one sig Mode_Auto, Mode_Manual extends Mode {}"));
+        assert!(alloy.contains("sig Setting { level: one Percent, locked: one Bool }"));
+        assert!(alloy.contains(
+            "one sig Phen_0_0_Configure extends Phenomenon { setting: one Setting, mode: one Mode }"
+        ));
+    }
+
+    #[test]
+    fn emits_state_machine_for_domain_behavior() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: GateStates
+            domain Controller kind causal role machine
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                on PulseOpen from Closed to Open emits GateOpened
+                on PulseClose from Open to Closed
+            }
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                    phenomenon PulseClose : event [Controller -> Gate] controlledBy Controller
+                    phenomenon GateOpened : state [Gate -> Controller] controlledBy Gate
+                }
+            }
+            "#,
+        )
+        .expect("parse failed");
+
+        let alloy = generate_alloy(&problem);
+        assert!(alloy.contains("abstract sig Gate_State {}"));
+        assert!(alloy.contains("one sig Gate_Closed, Gate_Open extends Gate_State {}"));
+        assert!(alloy.contains("fun Gate_initial: Gate_State { Gate_Closed }"));
+        assert!(alloy.contains(
+            "pred Gate_step[s, s2: Gate_State, p: Phenomenon] {\n  (p = Phen_0_0_PulseOpen and s = Gate_Closed and s2 = Gate_Open)\n  or (p = Phen_0_1_PulseClose and s = Gate_Open and s2 = Gate_Closed)\n}"
+        ));
+        assert!(alloy.contains(
+            "  ((p = Phen_0_0_PulseOpen and s = Gate_Closed and s2 = Gate_Open) => (Phen_0_2_GateOpened) else none)"
+        ));
+    }
 }
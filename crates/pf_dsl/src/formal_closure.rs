@@ -31,7 +31,7 @@ pub fn formal_closure_rows(problem: &Problem) -> Vec<(String, String)> {
         .iter()
         .filter_map(|requirement| {
             requirement_formal_argument(requirement)
-                .map(|argument| (requirement.name.clone(), argument))
+                .map(|argument| (requirement.key().to_string(), argument))
         })
         .collect::<Vec<_>>();
 
@@ -39,10 +39,31 @@ pub fn formal_closure_rows(problem: &Problem) -> Vec<(String, String)> {
     rows
 }
 
+// Names may contain any character through string escapes; these keep them on one
+// `|`-separated row or markdown table cell.
+pub fn escape_field(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '|' => escaped.push_str("\\|"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 pub fn generate_formal_closure_map_tsv(problem: &Problem) -> String {
     let mut output = String::from("# requirement|correctness_argument\n");
     for (requirement, argument) in formal_closure_rows(problem) {
-        output.push_str(&format!("{}|{}\n", requirement, argument));
+        output.push_str(&format!(
+            "{}|{}\n",
+            escape_field(&requirement),
+            escape_field(&argument)
+        ));
     }
     output
 }
@@ -64,7 +85,7 @@ pub fn generate_requirements_tsv(problem: &Problem) -> String {
         .iter()
         .map(|requirement| {
             (
-                requirement.name.clone(),
+                requirement.key().to_string(),
                 frame_name(&requirement.frame),
                 requirement_mda_layer(requirement).unwrap_or_else(|| "UNSPECIFIED".to_string()),
             )
@@ -74,7 +95,12 @@ pub fn generate_requirements_tsv(problem: &Problem) -> String {
 
     let mut output = String::from("# requirement|frame|layer\n");
     for (requirement, frame, layer) in rows {
-        output.push_str(&format!("{}|{}|{}\n", requirement, frame, layer));
+        output.push_str(&format!(
+            "{}|{}|{}\n",
+            escape_field(&requirement),
+            escape_field(&frame),
+            escape_field(&layer)
+        ));
     }
     output
 }
@@ -101,16 +127,26 @@ mod tests {
         generate_requirements_tsv,
     };
     use crate::ast::{
-        Domain, DomainKind, DomainRole, FrameType, Mark, Problem, Reference, Requirement, Span,
+        Domain, DomainKind, DomainRole, FileId, FrameType, ImportGraph, Mark, Problem, Reference,
+        Requirement, SourceMap, Span,
     };
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn requirement(name: &str, mark_value: Option<&str>) -> Requirement {
         Requirement {
             name: name.to_string(),
+            id: None,
+            priority: None,
+            status: None,
+            owner: None,
+            doc: None,
             frame: FrameType::SimpleWorkpieces,
             phenomena: vec![],
             marks: mark_value
@@ -139,11 +175,16 @@ mod tests {
             name: "FormalClosure".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "Tool".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: span(),
                 source_path: None,
             }],
@@ -156,6 +197,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         assert_eq!(
@@ -173,17 +216,78 @@ mod tests {
         assert!(!tsv.contains("R3|"));
     }
 
+    #[test]
+    fn keys_rows_on_stable_requirement_id() {
+        let mut renamed = requirement("Tool writes the report", Some("A1"));
+        renamed.id = Some(Reference {
+            name: "REQ-1".to_string(),
+            span: span(),
+        });
+        let problem = Problem {
+            name: "StableIds".to_string(),
+            span: span(),
+            imports: vec![],
+            frames: vec![],
+            types: vec![],
+            domains: vec![],
+            interfaces: vec![],
+            requirements: vec![renamed],
+            subproblems: vec![],
+            assertion_sets: vec![],
+            correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
+        };
+
+        assert!(generate_formal_closure_map_tsv(&problem).contains("REQ-1|A1\n"));
+        assert!(
+            generate_requirements_tsv(&problem).contains("REQ-1|SimpleWorkpieces|UNSPECIFIED\n")
+        );
+    }
+
+    #[test]
+    fn escapes_separators_in_names() {
+        let problem = Problem {
+            name: "Escapes".to_string(),
+            span: span(),
+            imports: vec![],
+            frames: vec![],
+            types: vec![],
+            domains: vec![],
+            interfaces: vec![],
+            requirements: vec![requirement("Open | close\n\tnow", Some("A1"))],
+            subproblems: vec![],
+            assertion_sets: vec![],
+            correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
+        };
+
+        assert!(
+            generate_formal_closure_map_tsv(&problem).ends_with("\nOpen \\| close\\n\\tnow|A1\n")
+        );
+        assert_eq!(
+            generate_requirements_tsv(&problem).lines().nth(1),
+            Some("Open \\| close\\n\\tnow|SimpleWorkpieces|UNSPECIFIED")
+        );
+    }
+
     #[test]
     fn generates_requirement_and_argument_inventories() {
         let problem = Problem {
             name: "Inventory".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "Tool".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: span(),
                 source_path: None,
             }],
@@ -191,6 +295,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "R2".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::SimpleWorkpieces,
                     phenomena: vec![],
                     marks: vec![],
@@ -205,6 +314,11 @@ mod tests {
                 },
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::Transformation,
                     phenomena: vec![],
                     marks: vec![],
@@ -262,6 +376,8 @@ mod tests {
                     source_path: None,
                 },
             ],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let requirements_tsv = generate_requirements_tsv(&problem);
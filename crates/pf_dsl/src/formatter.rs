@@ -0,0 +1,1208 @@
+use crate::ast::*;
+use crate::language::{domain_kind_keyword, domain_role_keyword, phenomenon_type_keyword};
+use crate::parser::{parse_module, string_end, PFParser, Rule};
+use anyhow::{anyhow, Result};
+use pest::iterators::Pair;
+use pest::Parser;
+use std::collections::HashMap;
+
+const INDENT: &str = "    ";
+const END_OF_FILE: usize = usize::MAX;
+
+// Canonical `.pf` rendering of a model. Comments are not part of the AST, so this
+// is the entry point for programmatic edits; use `format_source` to keep comments.
+pub fn format_problem(problem: &Problem) -> String {
+    Printer::new(Layout::default(), Trivia::default()).print(problem)
+}
+
+pub fn format_source(input: &str) -> Result<String> {
+    let problem = parse_module(input)?;
+    let program = PFParser::parse(Rule::program, input)
+        .map_err(|err| anyhow!("{err}"))?
+        .next()
+        .ok_or_else(|| anyhow!("program did not produce a parse tree"))?;
+
+    let mut layout = Layout::default();
+    collect_layout(program, 0, &mut layout);
+    layout.anchors.sort_unstable();
+    layout.anchors.dedup();
+
+    let trivia = attach_comments(input, &scan_comments(input), &layout.anchors);
+    Ok(Printer::new(layout, trivia).print(&problem))
+}
+
+pub(crate) struct Comment {
+    pub(crate) start: usize,
+    pub(crate) text: String,
+    detached: bool,
+}
+
+// The grammar drops comments as implicit trivia, so they are recovered from the
+// raw text. Input has already parsed, so string tracking is reliable here.
+pub(crate) fn scan_comments(input: &str) -> Vec<Comment> {
+    let bytes = input.as_bytes();
+    let mut comments = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => {
+                index = string_end(bytes, index);
+                continue;
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'/') => {
+                let end = input[index..]
+                    .find('\n')
+                    .map(|offset| index + offset)
+                    .unwrap_or(input.len());
+                // `///` doc comments live on the AST and are printed from there; a
+                // `///` line with nothing to document is kept as a plain comment.
+                if PFParser::parse(Rule::doc_comment, &input[index..]).is_ok() {
+                    index = end;
+                    continue;
+                }
+                let rest = &input[end..];
+                let gap = &rest[..rest.len() - rest.trim_start().len()];
+                comments.push(Comment {
+                    start: index,
+                    text: input[index..end].trim_end().to_string(),
+                    detached: gap.matches('\n').count() > 1,
+                });
+                index = end;
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    comments
+}
+
+// Source offsets of everything the printer emits as its own line: declarations,
+// body fields, phenomena, marks, assertions and the closing brace of each block.
+#[derive(Default)]
+struct Layout {
+    anchors: Vec<usize>,
+    problem: Option<usize>,
+    imports: Vec<usize>,
+    fields: HashMap<(usize, Rule), Span>,
+}
+
+impl Layout {
+    fn field_start(&self, owner: &Span, rule: Rule) -> Option<usize> {
+        self.fields.get(&(owner.start, rule)).map(|span| span.start)
+    }
+
+    fn field_close(&self, owner: &Span, rule: Rule) -> Option<usize> {
+        self.fields
+            .get(&(owner.start, rule))
+            .map(|span| span.end.saturating_sub(1))
+    }
+}
+
+fn is_declaration(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::import_decl
+            | Rule::problem_decl
+            | Rule::frame_decl
+            | Rule::type_decl
+            | Rule::domain_decl
+            | Rule::interface_decl
+            | Rule::requirement_decl
+            | Rule::subproblem_decl
+            | Rule::world_properties_decl
+            | Rule::specification_decl
+            | Rule::requirement_assertions_decl
+            | Rule::correctness_argument_decl
+    )
+}
+
+fn is_line(rule: Rule) -> bool {
+    is_declaration(rule)
+        || matches!(
+            rule,
+            Rule::frame_constrains
+                | Rule::frame_reference
+                | Rule::frame_connects
+                | Rule::frame_flow
+                | Rule::shared_phenomena
+                | Rule::phenomenon
+                | Rule::frame_type
+                | Rule::requirement_id
+                | Rule::requirement_priority
+                | Rule::requirement_status
+                | Rule::requirement_owner
+                | Rule::constraint
+                | Rule::constrains
+                | Rule::reference
+                | Rule::requirement_phenomena
+                | Rule::marks_block
+                | Rule::mark_decl
+                | Rule::behavior_block
+                | Rule::state_decl
+                | Rule::transition
+                | Rule::schema_block
+                | Rule::schema_entity
+                | Rule::schema_field
+                | Rule::subproblem_machine
+                | Rule::subproblem_participants
+                | Rule::subproblem_requirements
+                | Rule::assertion_stmt
+                | Rule::prove_stmt
+        )
+}
+
+fn is_block(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::frame_decl
+            | Rule::interface_decl
+            | Rule::shared_phenomena
+            | Rule::requirement_decl
+            | Rule::marks_block
+            | Rule::behavior_block
+            | Rule::schema_block
+            | Rule::schema_entity
+            | Rule::subproblem_decl
+            | Rule::world_properties_decl
+            | Rule::specification_decl
+            | Rule::requirement_assertions_decl
+            | Rule::correctness_argument_decl
+    )
+}
+
+fn collect_layout(pair: Pair<'_, Rule>, owner: usize, layout: &mut Layout) {
+    let rule = pair.as_rule();
+    let span = Span {
+        start: pair.as_span().start(),
+        end: pair.as_span().end(),
+        file: FileId::default(),
+    };
+    let owner = if is_declaration(rule) {
+        span.start
+    } else {
+        owner
+    };
+
+    match rule {
+        Rule::problem_decl => layout.problem = Some(span.start),
+        Rule::import_decl => layout.imports.push(span.start),
+        _ => {}
+    }
+    if is_line(rule) {
+        layout.anchors.push(span.start);
+        layout.fields.insert((owner, rule), span);
+    }
+    if is_block(rule) {
+        layout.anchors.push(span.end.saturating_sub(1));
+    }
+
+    for inner in pair.into_inner() {
+        collect_layout(inner, owner, layout);
+    }
+}
+
+// Comments keyed by anchor; `detached` comments keep the blank line that followed them.
+#[derive(Default)]
+struct Trivia {
+    leading: HashMap<usize, Vec<(usize, String, bool)>>,
+    trailing: HashMap<usize, Vec<(usize, String, bool)>>,
+}
+
+// A comment on a line of its own belongs to the next anchor; a comment after code
+// stays at the end of the line it annotates.
+fn attach_comments(input: &str, comments: &[Comment], anchors: &[usize]) -> Trivia {
+    let mut trivia = Trivia::default();
+
+    for comment in comments {
+        let line_start = input[..comment.start]
+            .rfind('\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let own_line = input[line_start..comment.start].trim().is_empty();
+        let entry = (comment.start, comment.text.clone(), comment.detached);
+
+        if !own_line {
+            if let Some(anchor) = anchors.iter().rev().find(|anchor| **anchor < comment.start) {
+                trivia.trailing.entry(*anchor).or_default().push(entry);
+                continue;
+            }
+        }
+
+        let anchor = anchors
+            .iter()
+            .find(|anchor| **anchor > comment.start)
+            .copied()
+            .unwrap_or(END_OF_FILE);
+        trivia.leading.entry(anchor).or_default().push(entry);
+    }
+
+    trivia
+}
+
+struct Printer {
+    output: String,
+    layout: Layout,
+    trivia: Trivia,
+}
+
+impl Printer {
+    fn new(layout: Layout, trivia: Trivia) -> Self {
+        Self {
+            output: String::new(),
+            layout,
+            trivia,
+        }
+    }
+
+    fn print(mut self, problem: &Problem) -> String {
+        if !problem.name.is_empty() {
+            let anchor = self.layout.problem;
+            self.line(0, &format!("problem: {}", problem.name), anchor);
+        }
+
+        self.blank();
+        for (index, import) in problem.imports.iter().enumerate() {
+            let anchor = self.layout.imports.get(index).copied();
+            self.line(0, &import_text(import), anchor);
+        }
+
+        for frame in &problem.frames {
+            self.blank();
+            self.frame(frame);
+        }
+
+        self.blank();
+        for data_type in &problem.types {
+            self.doc(0, &data_type.doc, data_type.span.start);
+            self.line(0, &type_text(data_type), Some(data_type.span.start));
+        }
+
+        self.blank();
+        for domain in &problem.domains {
+            self.domain(domain);
+        }
+        for interface in &problem.interfaces {
+            self.blank();
+            self.interface(interface);
+        }
+        for requirement in &problem.requirements {
+            self.blank();
+            self.requirement(requirement);
+        }
+        for subproblem in &problem.subproblems {
+            self.blank();
+            self.subproblem(subproblem);
+        }
+        for set in &problem.assertion_sets {
+            self.blank();
+            self.assertion_set(set);
+        }
+        for argument in &problem.correctness_arguments {
+            self.blank();
+            self.correctness_argument(argument);
+        }
+
+        // Anything not claimed by a printed line (end-of-file comments) goes last.
+        let mut remaining: Vec<(usize, String, bool)> = self
+            .trivia
+            .leading
+            .drain()
+            .chain(self.trivia.trailing.drain())
+            .flat_map(|(_, comments)| comments)
+            .collect();
+        remaining.sort();
+        if !remaining.is_empty() {
+            self.blank();
+        }
+        for (_, text, _) in remaining {
+            self.output.push_str(&text);
+            self.output.push('\n');
+        }
+
+        while self.output.ends_with("\n\n") {
+            self.output.pop();
+        }
+        self.output
+    }
+
+    fn blank(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    fn leading(&mut self, depth: usize, anchor: Option<usize>) {
+        let leading = anchor
+            .and_then(|anchor| self.trivia.leading.remove(&anchor))
+            .unwrap_or_default();
+        for (_, comment, detached) in leading {
+            self.push_line(depth, &comment);
+            if detached {
+                self.blank();
+            }
+        }
+    }
+
+    fn doc(&mut self, depth: usize, doc: &Option<String>, anchor: usize) {
+        let Some(doc) = doc else {
+            return;
+        };
+        self.leading(depth, Some(anchor));
+        for line in doc.lines() {
+            if line.is_empty() {
+                self.push_line(depth, "///");
+            } else {
+                self.push_line(depth, &format!("/// {line}"));
+            }
+        }
+    }
+
+    fn line(&mut self, depth: usize, text: &str, anchor: Option<usize>) {
+        self.leading(depth, anchor);
+
+        let trailing = anchor
+            .and_then(|anchor| self.trivia.trailing.remove(&anchor))
+            .unwrap_or_default();
+        let mut trailing = trailing.into_iter();
+        match trailing.next() {
+            Some((_, comment, _)) => self.push_line(depth, &format!("{text} {comment}")),
+            None => self.push_line(depth, text),
+        }
+        for (_, comment, _) in trailing {
+            self.push_line(depth, &comment);
+        }
+    }
+
+    fn close(&mut self, depth: usize, anchor: Option<usize>) {
+        self.close_with(depth, anchor, "}");
+    }
+
+    fn close_with(&mut self, depth: usize, anchor: Option<usize>, text: &str) {
+        let leading = anchor
+            .and_then(|anchor| self.trivia.leading.remove(&anchor))
+            .unwrap_or_default();
+        for (_, comment, _) in leading {
+            self.push_line(depth + 1, &comment);
+        }
+        self.line(depth, text, anchor);
+    }
+
+    fn push_line(&mut self, depth: usize, text: &str) {
+        for _ in 0..depth {
+            self.output.push_str(INDENT);
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn marks(&mut self, depth: usize, marks: &[Mark]) {
+        for mark in marks {
+            let text = match &mark.value {
+                Some(value) => format!("@{}({})", mark.name, quote(value)),
+                None => format!("@{}", mark.name),
+            };
+            self.line(depth, &text, Some(mark.span.start));
+        }
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        let span = frame.span;
+        self.doc(0, &frame.doc, span.start);
+        self.line(0, &format!("frame {} {{", frame.name), Some(span.start));
+
+        if let Some(slot) = &frame.constrains {
+            let anchor = self.layout.field_start(&span, Rule::frame_constrains);
+            self.line(1, &format!("constrains: {}", frame_slot_text(slot)), anchor);
+        }
+        if let Some(slot) = &frame.reference {
+            let anchor = self.layout.field_start(&span, Rule::frame_reference);
+            self.line(1, &format!("reference: {}", frame_slot_text(slot)), anchor);
+        }
+        if !frame.connects.is_empty() {
+            let anchor = self.layout.field_start(&span, Rule::frame_connects);
+            let connects = frame
+                .connects
+                .iter()
+                .map(frame_participant_keyword)
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.line(1, &format!("connects: {connects}"), anchor);
+        }
+        for flow in &frame.flows {
+            let types = if flow.types.is_empty() {
+                "any".to_string()
+            } else {
+                flow.types
+                    .iter()
+                    .map(phenomenon_type_keyword)
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            };
+            let mut text = format!(
+                "phenomenon: {types} [{} -> {}]",
+                frame_participant_keyword(&flow.from),
+                frame_participant_keyword(&flow.to)
+            );
+            if let Some(controller) = &flow.controlled_by {
+                text.push_str(&format!(
+                    " controlledBy {}",
+                    frame_participant_keyword(controller)
+                ));
+            }
+            self.line(1, &text, Some(flow.span.start));
+        }
+
+        self.close(0, Some(span.end.saturating_sub(1)));
+    }
+
+    fn domain(&mut self, domain: &Domain) {
+        let header = format!(
+            "domain {} kind {} role {}",
+            domain.name,
+            domain_kind_keyword(&domain.kind),
+            domain_role_keyword(&domain.role)
+        );
+        if domain.marks.is_empty() && domain.behavior.is_none() && domain.schema.is_none() {
+            self.doc(0, &domain.doc, domain.span.start);
+            self.line(0, &header, Some(domain.span.start));
+            return;
+        }
+
+        // Trailing blocks chain onto one another: `} behavior {`, `} schema {`.
+        self.blank();
+        self.doc(0, &domain.doc, domain.span.start);
+        let mut open_close: Option<Option<usize>> = None;
+        let mut open = |printer: &mut Self, keyword: &str, close: Option<usize>| match open_close
+            .replace(close)
+        {
+            Some(previous) => printer.close_with(0, previous, &format!("}} {keyword} {{")),
+            None => printer.line(
+                0,
+                &format!("{header} {keyword} {{"),
+                Some(domain.span.start),
+            ),
+        };
+
+        if !domain.marks.is_empty() {
+            let close = self.layout.field_close(&domain.span, Rule::marks_block);
+            open(self, "marks:", close);
+            self.marks(1, &domain.marks);
+        }
+        if let Some(behavior) = &domain.behavior {
+            let close = self.layout.field_close(&domain.span, Rule::behavior_block);
+            open(self, "behavior", close);
+            if !behavior.states.is_empty() {
+                let anchor = self.layout.field_start(&domain.span, Rule::state_decl);
+                self.line(
+                    1,
+                    &format!("state {}", join_references(&behavior.states)),
+                    anchor,
+                );
+            }
+            for transition in &behavior.transitions {
+                let mut text = format!(
+                    "on {} from {} to {}",
+                    transition.trigger.name, transition.from.name, transition.to.name
+                );
+                if !transition.emits.is_empty() {
+                    text.push_str(&format!(" emits {}", join_references(&transition.emits)));
+                }
+                self.line(1, &text, Some(transition.span.start));
+            }
+        }
+        if let Some(schema) = &domain.schema {
+            let close = self.layout.field_close(&domain.span, Rule::schema_block);
+            open(self, "schema", close);
+            for entity in &schema.entities {
+                let keyword = match entity.kind {
+                    SchemaEntityKind::Entity => "entity",
+                    SchemaEntityKind::Value => "value",
+                };
+                self.line(
+                    1,
+                    &format!("{keyword} {} {{", entity.name),
+                    Some(entity.span.start),
+                );
+                for field in &entity.fields {
+                    let text = format!("field {}: {}", field.name, field.type_ref.name);
+                    self.line(2, &text, Some(field.span.start));
+                }
+                self.close(1, Some(entity.span.end.saturating_sub(1)));
+            }
+        }
+        if let Some(close) = open_close {
+            self.close(0, close);
+        }
+        self.blank();
+    }
+
+    fn interface(&mut self, interface: &Interface) {
+        let connects = join_references(&interface.connects);
+        self.doc(0, &interface.doc, interface.span.start);
+        self.line(
+            0,
+            &format!(
+                "interface {} connects {} {{",
+                quote(&interface.name),
+                connects
+            ),
+            Some(interface.span.start),
+        );
+        let shared = self
+            .layout
+            .field_start(&interface.span, Rule::shared_phenomena);
+        self.line(1, "shared: {", shared);
+
+        let name_width = column_width(
+            interface
+                .shared_phenomena
+                .iter()
+                .map(|p| phenomenon_signature(p).len()),
+        );
+        let type_width = column_width(
+            interface
+                .shared_phenomena
+                .iter()
+                .map(|p| phenomenon_type_keyword(&p.type_).len()),
+        );
+        let direction_width = column_width(
+            interface
+                .shared_phenomena
+                .iter()
+                .map(|p| direction(p).len()),
+        );
+        for phenomenon in &interface.shared_phenomena {
+            let text = format!(
+                "phenomenon {:name_width$} : {:type_width$} {:direction_width$} controlledBy {}",
+                phenomenon_signature(phenomenon),
+                phenomenon_type_keyword(&phenomenon.type_),
+                direction(phenomenon),
+                phenomenon.controlled_by.name
+            );
+            self.doc(2, &phenomenon.doc, phenomenon.span.start);
+            self.line(2, &text, Some(phenomenon.span.start));
+        }
+
+        let shared_close = self
+            .layout
+            .field_close(&interface.span, Rule::shared_phenomena);
+        self.close(1, shared_close);
+        self.close(0, Some(interface.span.end.saturating_sub(1)));
+    }
+
+    fn requirement(&mut self, requirement: &Requirement) {
+        let span = requirement.span;
+        self.doc(0, &requirement.doc, span.start);
+        self.line(
+            0,
+            &format!("requirement {} {{", quote(&requirement.name)),
+            Some(span.start),
+        );
+
+        if let Some(id) = &requirement.id {
+            let anchor = self.layout.field_start(&span, Rule::requirement_id);
+            self.line(1, &format!("id: {}", id.name), anchor);
+        }
+        if let Some(priority) = requirement.priority {
+            let anchor = self.layout.field_start(&span, Rule::requirement_priority);
+            self.line(1, &format!("priority: {priority}"), anchor);
+        }
+        if let Some(status) = requirement.status {
+            let anchor = self.layout.field_start(&span, Rule::requirement_status);
+            self.line(1, &format!("status: {status}"), anchor);
+        }
+        if let Some(owner) = &requirement.owner {
+            let anchor = self.layout.field_start(&span, Rule::requirement_owner);
+            let owner = if is_identifier(owner) {
+                owner.clone()
+            } else {
+                quote(owner)
+            };
+            self.line(1, &format!("owner: {owner}"), anchor);
+        }
+        if let Some(frame) = frame_text(&requirement.frame) {
+            let anchor = self.layout.field_start(&span, Rule::frame_type);
+            self.line(1, &format!("frame: {frame}"), anchor);
+        }
+        if !requirement.constraint.is_empty() {
+            let anchor = self.layout.field_start(&span, Rule::constraint);
+            self.line(
+                1,
+                &format!("constraint: {}", quote_text(&requirement.constraint, 1)),
+                anchor,
+            );
+        }
+        if let Some(constrains) = &requirement.constrains {
+            let anchor = self.layout.field_start(&span, Rule::constrains);
+            self.line(1, &format!("constrains: {}", constrains.name), anchor);
+        }
+        if let Some(reference) = &requirement.reference {
+            let anchor = self.layout.field_start(&span, Rule::reference);
+            self.line(1, &format!("reference: {}", reference.name), anchor);
+        }
+        if !requirement.phenomena.is_empty() {
+            let anchor = self.layout.field_start(&span, Rule::requirement_phenomena);
+            self.line(
+                1,
+                &format!(
+                    "phenomena: {}",
+                    join_phenomenon_references(&requirement.phenomena)
+                ),
+                anchor,
+            );
+        }
+        if !requirement.marks.is_empty() {
+            let anchor = self.layout.field_start(&span, Rule::marks_block);
+            self.line(1, "marks: {", anchor);
+            self.marks(2, &requirement.marks);
+            let close = self.layout.field_close(&span, Rule::marks_block);
+            self.close(1, close);
+        }
+
+        self.close(0, Some(span.end.saturating_sub(1)));
+    }
+
+    fn subproblem(&mut self, subproblem: &Subproblem) {
+        let span = subproblem.span;
+        self.doc(0, &subproblem.doc, span.start);
+        self.line(
+            0,
+            &format!("subproblem {} {{", subproblem.name),
+            Some(span.start),
+        );
+
+        if let Some(machine) = &subproblem.machine {
+            let anchor = self.layout.field_start(&span, Rule::subproblem_machine);
+            self.line(1, &format!("machine: {}", machine.name), anchor);
+        }
+        if !subproblem.participants.is_empty() {
+            let anchor = self
+                .layout
+                .field_start(&span, Rule::subproblem_participants);
+            self.line(
+                1,
+                &format!(
+                    "participants: {}",
+                    join_references(&subproblem.participants)
+                ),
+                anchor,
+            );
+        }
+        if !subproblem.requirements.is_empty() {
+            let anchor = self
+                .layout
+                .field_start(&span, Rule::subproblem_requirements);
+            let requirements = subproblem
+                .requirements
+                .iter()
+                .map(|requirement| quote(&requirement.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.line(1, &format!("requirements: {requirements}"), anchor);
+        }
+
+        self.close(0, Some(span.end.saturating_sub(1)));
+    }
+
+    fn assertion_set(&mut self, set: &AssertionSet) {
+        let keyword = match set.scope {
+            AssertionScope::WorldProperties => "worldProperties",
+            AssertionScope::Specification => "specification",
+            AssertionScope::RequirementAssertions => "requirementAssertions",
+        };
+        self.doc(0, &set.doc, set.span.start);
+        self.line(
+            0,
+            &format!("{keyword} {} {{", set.name),
+            Some(set.span.start),
+        );
+        for assertion in &set.assertions {
+            let text = match &assertion.language {
+                Some(language) => {
+                    format!("assert {} @{language}", quote_text(&assertion.text, 1))
+                }
+                None => format!("assert {}", quote_text(&assertion.text, 1)),
+            };
+            self.line(1, &text, Some(assertion.span.start));
+        }
+        self.close(0, Some(set.span.end.saturating_sub(1)));
+    }
+
+    fn correctness_argument(&mut self, argument: &CorrectnessArgument) {
+        let span = argument.span;
+        self.line(
+            0,
+            &format!("correctnessArgument {} {{", argument.name),
+            Some(span.start),
+        );
+        let anchor = self.layout.field_start(&span, Rule::prove_stmt);
+        self.line(
+            1,
+            &format!(
+                "prove {} and {} entail {}",
+                argument.specification_set, argument.world_set, argument.requirement_set
+            ),
+            anchor,
+        );
+        self.close(0, Some(span.end.saturating_sub(1)));
+    }
+}
+
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            ch if ch.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", ch as u32)),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Multi-line text becomes a triple-quoted block one level deeper than the line
+// it starts on, with the closing quotes setting the indentation to strip. Text
+// the block form cannot reproduce (whitespace-only lines, control characters)
+// stays on one line with escapes.
+fn quote_text(text: &str, depth: usize) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let fits_block = lines.len() > 1
+        && lines.iter().all(|line| {
+            (line.is_empty() || !line.trim().is_empty())
+                && !line.chars().any(|ch| ch.is_control() && ch != '\t')
+        });
+    if !fits_block {
+        return quote(text);
+    }
+
+    let indent = INDENT.repeat(depth + 1);
+    let mut block = String::from("\"\"\"\n");
+    for line in lines {
+        if !line.is_empty() {
+            block.push_str(&indent);
+            for (index, ch) in line.char_indices() {
+                match ch {
+                    '\\' => block.push_str("\\\\"),
+                    // break up any run of three quotes so it cannot close the block
+                    '"' if line[index + 1..].starts_with("\"\"") => block.push_str("\\\""),
+                    ch => block.push(ch),
+                }
+            }
+        }
+        block.push('\n');
+    }
+    block.push_str(&indent);
+    block.push_str("\"\"\"");
+    block
+}
+
+fn import_text(import: &Import) -> String {
+    let mut text = String::from("import ");
+    if !import.items.is_empty() {
+        let items = import
+            .items
+            .iter()
+            .map(|item| {
+                if is_identifier(&item.name) {
+                    item.name.clone()
+                } else {
+                    quote(&item.name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        text.push_str(&format!("{{ {items} }} from "));
+    }
+    text.push_str(&quote(&import.path));
+    if let Some(alias) = &import.alias {
+        text.push_str(&format!(" as {}", alias.name));
+    }
+    text
+}
+
+fn join_references(references: &[Reference]) -> String {
+    references
+        .iter()
+        .map(|reference| reference.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Requirement phenomenon references quote an interface qualifier that is not
+// a plain name, e.g. `"Gate panel".Open`.
+fn join_phenomenon_references(references: &[Reference]) -> String {
+    references
+        .iter()
+        .map(|reference| match reference.name.rsplit_once('.') {
+            Some((qualifier, name)) if !is_plain_qualifier(qualifier) => {
+                format!("{}.{name}", quote(qualifier))
+            }
+            _ => reference.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn is_plain_qualifier(qualifier: &str) -> bool {
+    qualifier.split('.').all(|segment| {
+        segment.starts_with(|ch: char| ch.is_ascii_alphabetic())
+            && segment
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    })
+}
+
+fn column_width(widths: impl Iterator<Item = usize>) -> usize {
+    widths.max().unwrap_or(0)
+}
+
+fn typed_fields_text(fields: &[TypedField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.type_ref.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn phenomenon_signature(phenomenon: &Phenomenon) -> String {
+    if phenomenon.parameters.is_empty() {
+        return phenomenon.name.clone();
+    }
+    format!(
+        "{}({})",
+        phenomenon.name,
+        typed_fields_text(&phenomenon.parameters)
+    )
+}
+
+fn type_text(data_type: &DataType) -> String {
+    let definition = match &data_type.definition {
+        TypeDefinition::Enum(literals) => format!("enum {{ {} }}", join_references(literals)),
+        TypeDefinition::Record(fields) if fields.is_empty() => "record {}".to_string(),
+        TypeDefinition::Record(fields) => format!("record {{ {} }}", typed_fields_text(fields)),
+        TypeDefinition::Range { min, max, unit } => match unit {
+            Some(unit) => format!("range {min}..{max} unit {}", quote(unit)),
+            None => format!("range {min}..{max}"),
+        },
+        TypeDefinition::Unit(unit) => format!("unit {}", quote(unit)),
+    };
+    format!("type {} = {}", data_type.name, definition)
+}
+
+fn direction(phenomenon: &Phenomenon) -> String {
+    format!("[{} -> {}]", phenomenon.from.name, phenomenon.to.name)
+}
+
+fn frame_text(frame: &FrameType) -> Option<String> {
+    let name = match frame {
+        FrameType::RequiredBehavior => "RequiredBehavior",
+        FrameType::CommandedBehavior => "CommandedBehavior",
+        FrameType::InformationDisplay => "InformationDisplay",
+        FrameType::SimpleWorkpieces => "SimpleWorkpieces",
+        FrameType::Transformation => "Transformation",
+        FrameType::Custom(name) if name.is_empty() => return None,
+        FrameType::Custom(name) if is_identifier(name) => name,
+        FrameType::Custom(name) => return Some(quote(name)),
+    };
+    Some(name.to_string())
+}
+
+fn frame_slot_text(slot: &FrameSlot) -> String {
+    let mut text = if slot.kinds.is_empty() {
+        "any".to_string()
+    } else {
+        slot.kinds
+            .iter()
+            .map(domain_kind_keyword)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    if !slot.roles.is_empty() {
+        let roles = slot
+            .roles
+            .iter()
+            .map(domain_role_keyword)
+            .collect::<Vec<_>>()
+            .join(" | ");
+        text.push_str(&format!(" role {roles}"));
+    }
+    text
+}
+
+fn frame_participant_keyword(participant: &FrameParticipant) -> &'static str {
+    match participant {
+        FrameParticipant::Constrains => "constrains",
+        FrameParticipant::Reference => "reference",
+        FrameParticipant::Machine => "machine",
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = r#"// Sluice gate
+
+problem:   Sluice
+requirement "Open" { constrains: Gate
+  frame: CommandedBehavior   // operator driven
+reference: Operator }
+domain Gate kind causal role given
+  // the controller
+domain Controller kind causal role machine
+interface "Controller-Gate" connects Controller, Gate { shared: {
+  phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+  phenomenon TopPosition : state [Gate -> Controller] controlledBy Gate
+  // more to come
+} }
+domain Operator kind biddable role given
+// trailing note
+"#;
+
+    #[test]
+    fn test_format_source_normalizes_layout_and_keeps_comments() {
+        let formatted = format_source(MESSY).unwrap();
+        let expected = r#"// Sluice gate
+
+problem: Sluice
+
+domain Gate kind causal role given
+// the controller
+domain Controller kind causal role machine
+domain Operator kind biddable role given
+
+interface "Controller-Gate" connects Controller, Gate {
+    shared: {
+        phenomenon Pulse       : event [Controller -> Gate] controlledBy Controller
+        phenomenon TopPosition : state [Gate -> Controller] controlledBy Gate
+        // more to come
+    }
+}
+
+requirement "Open" {
+    frame: CommandedBehavior // operator driven
+    constrains: Gate
+    reference: Operator
+}
+
+// trailing note
+"#;
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_format_source_is_idempotent() {
+        let once = format_source(MESSY).unwrap();
+        let twice = format_source(&once).unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_format_problem_round_trips_through_parser() {
+        let source = r#"problem: Full
+import "lib.pf"
+domain M kind causal role machine
+domain D kind lexical role designed marks: {
+    @ddd.aggregate_root
+    @sysml.block("Ledger")
+}
+interface "M-D" connects M, D {
+    shared: {
+        phenomenon Write : command [M -> D] controlledBy M
+    }
+}
+requirement "Keep" {
+    frame: "Custom Frame"
+    constraint: "records persist"
+    constrains: D
+    phenomena: M-D.Write
+    marks: {
+        @sysml.requirement("REQ-1")
+    }
+}
+subproblem Persist {
+    machine: M
+    participants: M, D
+    requirements: "Keep"
+}
+worldProperties W {
+    assert "disk is durable" @LTL
+}
+specification S {
+    assert "machine writes"
+}
+requirementAssertions R {
+    assert "records kept"
+}
+correctnessArgument A {
+    prove S and W entail R
+}
+"#;
+        let problem = crate::parser::parse(source).unwrap();
+        let printed = format_problem(&problem);
+        let reparsed = crate::parser::parse(&printed).unwrap();
+
+        assert_eq!(format_problem(&reparsed), printed);
+        let import_paths = |problem: &Problem| {
+            problem
+                .imports
+                .iter()
+                .map(|import| import.path.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(import_paths(&reparsed), import_paths(&problem));
+        assert_eq!(reparsed.domains[1].marks.len(), 2);
+        assert_eq!(
+            reparsed.requirements[0].frame,
+            FrameType::Custom("Custom Frame".to_string())
+        );
+        assert_eq!(reparsed.requirements[0].phenomena[0].name, "M-D.Write");
+        assert_eq!(reparsed.assertion_sets.len(), 3);
+        assert_eq!(reparsed.correctness_arguments[0].world_set, "W");
+    }
+
+    #[test]
+    fn test_format_source_prints_doc_comments_from_the_model() {
+        let source = "problem: P\n// note\n///   Runs things.\n///\n/// Second paragraph.\ndomain M kind causal role machine\ndomain G kind causal role given\ninterface \"M-G\" connects M, G { shared: {\n/// Go signal.\nphenomenon Go : event [M -> G] controlledBy M } }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert!(formatted.contains(
+            "// note\n///   Runs things.\n///\n/// Second paragraph.\ndomain M kind causal role machine\n"
+        ));
+        assert!(formatted.contains("        /// Go signal.\n        phenomenon Go"));
+        assert_eq!(formatted.matches("Go signal").count(), 1);
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_rejects_unparseable_input() {
+        assert!(format_source("domain Broken kind").is_err());
+    }
+
+    #[test]
+    fn test_format_source_prints_frame_declarations() {
+        let source = "problem: P\ndomain M kind causal role machine\n/// House frame.\nframe Approval { reference: biddable\nconstrains:   lexical|causal role given // slot\n  phenomenon: command [reference -> machine] connects: constrains }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\n/// House frame.\nframe Approval {\n    constrains: lexical | causal role given // slot\n    reference: biddable\n    connects: constrains\n    phenomenon: command [reference -> machine]\n}\n\ndomain M kind causal role machine\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_prints_types_and_phenomenon_parameters() {
+        let source = "problem: P\ndomain M kind causal role machine\ndomain U kind biddable role given\ntype Mode=enum{Auto,Manual}\n/// Fraction of full scale.\ntype Percent = range 0 .. 100.5 unit \"%\"\ntype Empty = record { }\ninterface \"U-M\" connects U, M { shared: {\nphenomenon Set( level : Percent,mode:Mode ) : command [U -> M] controlledBy U\nphenomenon Stop : event [U -> M] controlledBy U } }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\ntype Mode = enum { Auto, Manual }\n/// Fraction of full scale.\ntype Percent = range 0..100.5 unit \"%\"\ntype Empty = record {}\n\ndomain M kind causal role machine\ndomain U kind biddable role given\n\ninterface \"U-M\" connects U, M {\n    shared: {\n        phenomenon Set(level: Percent, mode: Mode) : command [U -> M] controlledBy U\n        phenomenon Stop                            : event   [U -> M] controlledBy U\n    }\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_keeps_unattached_triple_slash_comments() {
+        let source = "problem: P\ndomain G kind causal role given\nrequirement \"R\" {\n/// why\nframe: RequiredBehavior\nconstrains: G }\n/// the end\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\ndomain G kind causal role given\n\nrequirement \"R\" {\n    /// why\n    frame: RequiredBehavior\n    constrains: G\n}\n\n/// the end\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_keeps_quoted_interface_qualifiers() {
+        let source = "problem: P\nrequirement \"R\" { frame: RequiredBehavior phenomena: \"Gate panel\".Open,M-D.Write,Open }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\nrequirement \"R\" {\n    frame: RequiredBehavior\n    phenomena: \"Gate panel\".Open, M-D.Write, Open\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_prints_domain_behavior() {
+        let source = "problem: P\ndomain M kind causal role machine\ndomain Gate kind causal role given marks: { @ddd.entity } behavior { state Closed ,Open\n// opening\non PulseOpen from Closed to Open emits GateOpened,Moving on PulseClose from Open to Closed }\ndomain Lamp kind causal role given behavior {\nstate Off }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\ndomain M kind causal role machine\n\ndomain Gate kind causal role given marks: {\n    @ddd.entity\n} behavior {\n    state Closed, Open\n    // opening\n    on PulseOpen from Closed to Open emits GateOpened, Moving\n    on PulseClose from Open to Closed\n}\n\ndomain Lamp kind causal role given behavior {\n    state Off\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_prints_domain_schema() {
+        let source = "problem: P\ndomain Ledger kind lexical role designed marks: { @ddd.aggregate_root } schema { entity Order { field id: String\n// money\nfield total: Money }\nvalue Money { field amount: Decimal } }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\ndomain Ledger kind lexical role designed marks: {\n    @ddd.aggregate_root\n} schema {\n    entity Order {\n        field id: String\n        // money\n        field total: Money\n    }\n    value Money {\n        field amount: Decimal\n    }\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_prints_requirement_metadata() {
+        let source = "problem: P\nrequirement \"Gate opens\" { frame: RequiredBehavior owner: \"Gate Team\"\nstatus: approved id: REQ-1.2 priority: low }\nrequirement \"R2\" { owner: Ops frame: RequiredBehavior }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\nrequirement \"Gate opens\" {\n    id: REQ-1.2\n    priority: low\n    status: approved\n    owner: \"Gate Team\"\n    frame: RequiredBehavior\n}\n\nrequirement \"R2\" {\n    owner: Ops\n    frame: RequiredBehavior\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_round_trips_escapes_and_multiline_strings() {
+        let source = "problem: P\nrequirement \"Say \\\"hi\\\"\" {\n    frame: RequiredBehavior\n    constraint: \"\"\"\n      Line one // kept\n        indented \\\"\"\" end\n      \"\"\" // trailing\n}\nworldProperties W { assert \"tab\\there\" }\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\nrequirement \"Say \\\"hi\\\"\" {\n    frame: RequiredBehavior\n    constraint: \"\"\"\n        Line one // kept\n          indented \\\"\"\" end\n        \"\"\" // trailing\n}\n\nworldProperties W {\n    assert \"tab\\there\"\n}\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+        assert_eq!(
+            parse_module(&formatted).unwrap().requirements[0].constraint,
+            "Line one // kept\n  indented \"\"\" end"
+        );
+    }
+
+    #[test]
+    fn test_format_source_prints_aliased_and_selective_imports() {
+        let source = "problem: P\nimport   \"lib.pf\"   as Lib\nimport {Gate,\"Keep open\"} from \"gate.pf\"\n";
+        let formatted = format_source(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "problem: P\n\nimport \"lib.pf\" as Lib\nimport { Gate, \"Keep open\" } from \"gate.pf\"\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::ast::Frame;
+use crate::language::FRAME_TYPES;
+use crate::parser::parse_module;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// The `std/` frame library is compiled in, so `import "std/<Frame>.pf"` works
+// wherever the model lives. The path returned is where the file sits in the repo.
+pub(crate) fn load_standard_import(import_path_str: &str) -> Option<(&'static str, PathBuf)> {
+    let content = match import_path_str {
+        "std/RequiredBehavior.pf" => include_str!("../../../models/std/RequiredBehavior.pf"),
+        "std/CommandedBehavior.pf" => include_str!("../../../models/std/CommandedBehavior.pf"),
+        "std/InformationDisplay.pf" => include_str!("../../../models/std/InformationDisplay.pf"),
+        "std/SimpleWorkpieces.pf" => include_str!("../../../models/std/SimpleWorkpieces.pf"),
+        "std/Transformation.pf" => include_str!("../../../models/std/Transformation.pf"),
+        _ => return None,
+    };
+
+    let source_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("models")
+        .join(import_path_str);
+
+    Some((content, source_path))
+}
+
+// The five core frames are declared in `models/std/<Frame>.pf` like any house frame.
+pub fn builtin_frames() -> &'static [Frame] {
+    static FRAMES: OnceLock<Vec<Frame>> = OnceLock::new();
+    FRAMES.get_or_init(|| {
+        let mut frames = Vec::new();
+        for frame_name in FRAME_TYPES {
+            let import_path = format!("std/{frame_name}.pf");
+            let Some((content, source_path)) = load_standard_import(&import_path) else {
+                continue;
+            };
+            let module = parse_module(content).expect("bundled std frame library must parse");
+            for mut frame in module.frames {
+                frame.source_path = Some(source_path.clone());
+                frames.push(frame);
+            }
+        }
+        frames
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_frames_are_declared_in_standard_library() {
+        let frames = builtin_frames();
+        let names: Vec<&str> = frames.iter().map(|frame| frame.name.as_str()).collect();
+        assert_eq!(names, FRAME_TYPES);
+        assert!(frames.iter().all(|frame| frame
+            .source_path
+            .as_ref()
+            .is_some_and(|path| path.ends_with(format!("std/{}.pf", frame.name)))));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::ast::Span;
+use crate::source_map::SourceMap;
+use std::path::{Path, PathBuf};
+
+// One `import` declaration. `from` and `to` are the canonical paths of the files
+// (the embedded path for `std/` imports); `span` lies in the importing file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportEdge {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub path: String,
+    pub alias: Option<String>,
+    pub items: Vec<String>,
+    pub span: Span,
+}
+
+// File-level import structure recorded by the resolver. A file imported through
+// several paths (a diamond) is merged once but keeps all of its edges.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportGraph {
+    // entry file first, then in load order
+    pub files: Vec<PathBuf>,
+    pub edges: Vec<ImportEdge>,
+    // edge indices from the first file on the cycle back to it; the closing import
+    // is not followed
+    pub cycles: Vec<Vec<usize>>,
+    // (repeated edge, first edge): a file importing the same file twice under the
+    // same alias and selection
+    pub redundant: Vec<(usize, usize)>,
+}
+
+impl ImportGraph {
+    pub fn add_file(&mut self, path: &Path) {
+        if !self.files.iter().any(|file| file == path) {
+            self.files.push(path.to_path_buf());
+        }
+    }
+
+    // A file merged under several aliases is walked once per alias; its import
+    // declarations map back to the same edges.
+    pub fn add_edge(&mut self, edge: ImportEdge) -> usize {
+        let existing = self.edges.iter().position(|known| {
+            known.from == edge.from
+                && known.to == edge.to
+                && (known.span.start, known.span.end) == (edge.span.start, edge.span.end)
+        });
+        if let Some(index) = existing {
+            return index;
+        }
+        self.add_file(&edge.from);
+        self.add_file(&edge.to);
+        self.edges.push(edge);
+        self.edges.len() - 1
+    }
+
+    pub fn add_cycle(&mut self, cycle: Vec<usize>) {
+        if !self.cycles.contains(&cycle) {
+            self.cycles.push(cycle);
+        }
+    }
+
+    // Earlier import of the same file, alias and selection from the same file.
+    pub fn first_import_like(&self, index: usize) -> Option<usize> {
+        let edge = &self.edges[index];
+        self.edges[..index].iter().position(|earlier| {
+            earlier.from == edge.from
+                && earlier.to == edge.to
+                && earlier.alias == edge.alias
+                && earlier.items == edge.items
+        })
+    }
+
+    pub fn add_redundant(&mut self, repeated: usize, first: usize) {
+        if !self.redundant.contains(&(repeated, first)) {
+            self.redundant.push((repeated, first));
+        }
+    }
+
+    // Imports that close a cycle or repeat an earlier one are not merged.
+    pub fn is_followed(&self, edge: usize) -> bool {
+        !self.cycles.iter().any(|cycle| cycle.last() == Some(&edge))
+            && !self.redundant.iter().any(|(repeated, _)| *repeated == edge)
+    }
+
+    // `a.pf:2:1 imports "b.pf" -> b.pf:3:1 imports "a.pf"`
+    pub fn describe_cycle(&self, cycle: &[usize], sources: &SourceMap) -> String {
+        cycle
+            .iter()
+            .map(|&index| {
+                let edge = &self.edges[index];
+                let location = sources
+                    .location(edge.span)
+                    .unwrap_or_else(|| edge.from.display().to_string());
+                format!("{location} imports \"{}\"", edge.path)
+            })
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
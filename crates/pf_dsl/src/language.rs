@@ -1,10 +1,23 @@
 use crate::ast::{DomainKind, DomainRole, FrameType, PhenomenonType};
 
 pub const STATEMENT_KEYWORDS: &[&str] = &[
+    "import",
+    "as",
     "problem:",
+    "frame",
+    "any",
+    "type",
     "domain",
     "kind",
     "role",
+    "behavior",
+    "on",
+    "from",
+    "to",
+    "emits",
+    "schema",
+    "entity",
+    "field",
     "interface",
     "connects",
     "phenomenon",
@@ -25,7 +38,21 @@ pub const STATEMENT_KEYWORDS: &[&str] = &[
     "entail",
 ];
 
-pub const REQUIREMENT_FIELDS: &[&str] = &["frame:", "constraint:", "constrains:", "reference:"];
+pub const REQUIREMENT_FIELDS: &[&str] = &[
+    "id:",
+    "priority:",
+    "status:",
+    "owner:",
+    "frame:",
+    "constraint:",
+    "constrains:",
+    "reference:",
+    "phenomena:",
+];
+
+pub const REQUIREMENT_PRIORITIES: &[&str] = &["high", "medium", "low"];
+
+pub const REQUIREMENT_STATUSES: &[&str] = &["draft", "approved", "implemented", "deprecated"];
 
 pub const DOMAIN_KINDS: &[&str] = &["biddable", "causal", "lexical"];
 
@@ -33,6 +60,10 @@ pub const DOMAIN_ROLES: &[&str] = &["given", "designed", "machine"];
 
 pub const PHENOMENON_TYPES: &[&str] = &["event", "command", "state", "value"];
 
+pub const PRIMITIVE_TYPES: &[&str] = &["Integer", "Decimal", "Boolean", "String"];
+
+pub const TYPE_FORMS: &[&str] = &["enum", "record", "range", "unit"];
+
 pub const FRAME_TYPES: &[&str] = &[
     "RequiredBehavior",
     "CommandedBehavior",
@@ -41,6 +72,44 @@ pub const FRAME_TYPES: &[&str] = &[
     "Transformation",
 ];
 
+pub fn domain_kind_keyword(kind: &DomainKind) -> &str {
+    match kind {
+        DomainKind::Biddable => "biddable",
+        DomainKind::Causal => "causal",
+        DomainKind::Lexical => "lexical",
+        DomainKind::Unknown(value) => value,
+    }
+}
+
+pub fn domain_role_keyword(role: &DomainRole) -> &str {
+    match role {
+        DomainRole::Given => "given",
+        DomainRole::Designed => "designed",
+        DomainRole::Machine => "machine",
+        DomainRole::Unknown(value) => value,
+    }
+}
+
+pub fn phenomenon_type_keyword(type_: &PhenomenonType) -> &'static str {
+    match type_ {
+        PhenomenonType::Event => "event",
+        PhenomenonType::Command => "command",
+        PhenomenonType::State => "state",
+        PhenomenonType::Value => "value",
+    }
+}
+
+pub fn frame_type_name(frame: &FrameType) -> &str {
+    match frame {
+        FrameType::RequiredBehavior => "RequiredBehavior",
+        FrameType::CommandedBehavior => "CommandedBehavior",
+        FrameType::InformationDisplay => "InformationDisplay",
+        FrameType::SimpleWorkpieces => "SimpleWorkpieces",
+        FrameType::Transformation => "Transformation",
+        FrameType::Custom(name) => name,
+    }
+}
+
 pub fn parse_domain_kind(value: &str) -> DomainKind {
     match value {
         "biddable" => DomainKind::Biddable,
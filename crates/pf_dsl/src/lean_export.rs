@@ -1,7 +1,9 @@
 use crate::ast::{
-    AssertionScope, AssertionSet, CorrectnessArgument, Domain, DomainKind, DomainRole, FrameType,
-    Interface, Phenomenon, PhenomenonType, Problem, Requirement,
+    AssertionScope, AssertionSet, CorrectnessArgument, DataType, Domain, DomainKind, DomainRole,
+    FrameType, Interface, Phenomenon, PhenomenonType, Problem, Requirement, TypeDefinition,
+    TypedField,
 };
+use crate::language::PRIMITIVE_TYPES;
 use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -14,6 +16,7 @@ fn escape_lean_string(value: &str) -> String {
             '\n' => escaped.push_str("\\n"),
             '\r' => escaped.push_str("\\r"),
             '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
             _ => escaped.push(ch),
         }
     }
@@ -93,6 +96,126 @@ fn emit_domains(domains: &[Domain], output: &mut String) {
     output.push_str("]\n\n");
 }
 
+fn parameters_expr(fields: &[TypedField]) -> String {
+    format!(
+        "[{}]",
+        fields
+            .iter()
+            .map(|field| format!(
+                "{{ name := \"{}\", typeName := \"{}\" }}",
+                escape_lean_string(&field.name),
+                escape_lean_string(&field.type_ref.name)
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn emit_data_types(types: &[DataType], output: &mut String) {
+    let mut sorted = types.to_vec();
+    sorted.sort_by(|left, right| left.name.cmp(&right.name));
+
+    output.push_str("def dataTypes : List DataType := [\n");
+    for data_type in sorted {
+        // Range bounds stay textual so the structure keeps decidable equality.
+        let (form, literals, fields, bounds, unit) = match &data_type.definition {
+            TypeDefinition::Enum(literals) => (
+                "DataTypeForm.enumeration",
+                literals
+                    .iter()
+                    .map(|literal| literal.name.clone())
+                    .collect::<Vec<_>>(),
+                Vec::new(),
+                None,
+                None,
+            ),
+            TypeDefinition::Record(fields) => (
+                "DataTypeForm.record",
+                Vec::new(),
+                fields.clone(),
+                None,
+                None,
+            ),
+            TypeDefinition::Range { min, max, unit } => (
+                "DataTypeForm.range",
+                Vec::new(),
+                Vec::new(),
+                Some((min.to_string(), max.to_string())),
+                unit.as_deref(),
+            ),
+            TypeDefinition::Unit(unit) => (
+                "DataTypeForm.unit",
+                Vec::new(),
+                Vec::new(),
+                None,
+                Some(unit.as_str()),
+            ),
+        };
+        let bounds = match bounds {
+            Some((min, max)) => format!(
+                "some (\"{}\", \"{}\")",
+                escape_lean_string(&min),
+                escape_lean_string(&max)
+            ),
+            None => "none".to_string(),
+        };
+        output.push_str(&format!(
+            "  {{ name := \"{}\", form := {}, literals := {}, fields := {}, bounds := {}, unit := {} }},\n",
+            escape_lean_string(&data_type.name),
+            form,
+            lean_string_list_expr(&literals),
+            parameters_expr(&fields),
+            bounds,
+            option_string_expr(unit),
+        ));
+    }
+    output.push_str("]\n\n");
+}
+
+fn emit_behaviors(domains: &[Domain], output: &mut String) {
+    let mut sorted = domains
+        .iter()
+        .filter_map(|domain| domain.behavior.as_ref().map(|behavior| (domain, behavior)))
+        .collect::<Vec<_>>();
+    sorted.sort_by(|left, right| left.0.name.cmp(&right.0.name));
+
+    output.push_str("def behaviors : List Behavior := [\n");
+    for (domain, behavior) in sorted {
+        let states = behavior
+            .states
+            .iter()
+            .map(|state| state.name.clone())
+            .collect::<Vec<_>>();
+        let transitions = behavior
+            .transitions
+            .iter()
+            .map(|transition| {
+                format!(
+                    "{{ trigger := \"{}\", source := \"{}\", target := \"{}\", emits := {} }}",
+                    escape_lean_string(&transition.trigger.name),
+                    escape_lean_string(&transition.from.name),
+                    escape_lean_string(&transition.to.name),
+                    lean_string_list_expr(
+                        &transition
+                            .emits
+                            .iter()
+                            .map(|reference| reference.name.clone())
+                            .collect::<Vec<_>>()
+                    ),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!(
+            "  {{ domain := \"{}\", states := {}, transitions := [{}] }},\n",
+            escape_lean_string(&domain.name),
+            lean_string_list_expr(&states),
+            transitions,
+        ));
+    }
+    output.push_str("]\n\n");
+}
+
 fn emit_phenomena(phenomena: &[Phenomenon], output: &mut String) {
     let mut sorted = phenomena.to_vec();
     sorted.sort_by(|left, right| left.name.cmp(&right.name));
@@ -100,12 +223,13 @@ fn emit_phenomena(phenomena: &[Phenomenon], output: &mut String) {
     output.push_str("[\n");
     for phenomenon in sorted {
         output.push_str(&format!(
-            "      {{ name := \"{}\", kind := {}, fromDomain := \"{}\", toDomain := \"{}\", controlledBy := \"{}\" }},\n",
+            "      {{ name := \"{}\", kind := {}, fromDomain := \"{}\", toDomain := \"{}\", controlledBy := \"{}\", parameters := {} }},\n",
             escape_lean_string(&phenomenon.name),
             phenomenon_type_expr(&phenomenon.type_),
             escape_lean_string(&phenomenon.from.name),
             escape_lean_string(&phenomenon.to.name),
             escape_lean_string(&phenomenon.controlled_by.name),
+            parameters_expr(&phenomenon.parameters),
         ));
     }
     output.push_str("    ]");
@@ -135,12 +259,13 @@ fn emit_interfaces(interfaces: &[Interface], output: &mut String) {
 
 fn emit_requirements(requirements: &[Requirement], output: &mut String) {
     let mut sorted = requirements.to_vec();
-    sorted.sort_by(|left, right| left.name.cmp(&right.name));
+    sorted.sort_by(|left, right| left.key().cmp(right.key()));
 
     output.push_str("def requirements : List Requirement := [\n");
     for requirement in sorted {
         output.push_str(&format!(
-            "  {{ name := \"{}\", frame := {}, constraint := \"{}\", constrains := {}, reference := {} }},\n",
+            "  {{ id := \"{}\", name := \"{}\", frame := {}, constraint := \"{}\", constrains := {}, reference := {} }},\n",
+            escape_lean_string(requirement.key()),
             escape_lean_string(&requirement.name),
             frame_type_expr(&requirement.frame),
             escape_lean_string(&requirement.constraint),
@@ -647,6 +772,24 @@ pub fn generate_lean_model(problem: &Problem) -> String {
     output.push_str("  | specification\n");
     output.push_str("  | requirementAssertions\n");
     output.push_str("  deriving Repr, DecidableEq\n\n");
+    output.push_str("inductive DataTypeForm where\n");
+    output.push_str("  | enumeration\n");
+    output.push_str("  | record\n");
+    output.push_str("  | range\n");
+    output.push_str("  | unit\n");
+    output.push_str("  deriving Repr, DecidableEq\n\n");
+    output.push_str("structure Parameter where\n");
+    output.push_str("  name : String\n");
+    output.push_str("  typeName : String\n");
+    output.push_str("  deriving Repr, DecidableEq\n\n");
+    output.push_str("structure DataType where\n");
+    output.push_str("  name : String\n");
+    output.push_str("  form : DataTypeForm\n");
+    output.push_str("  literals : List String\n");
+    output.push_str("  fields : List Parameter\n");
+    output.push_str("  bounds : Option (String × String)\n");
+    output.push_str("  unit : Option String\n");
+    output.push_str("  deriving Repr, DecidableEq\n\n");
     output.push_str("structure Domain where\n");
     output.push_str("  name : String\n");
     output.push_str("  kind : DomainKind\n");
@@ -658,6 +801,18 @@ pub fn generate_lean_model(problem: &Problem) -> String {
     output.push_str("  fromDomain : String\n");
     output.push_str("  toDomain : String\n");
     output.push_str("  controlledBy : String\n");
+    output.push_str("  parameters : List Parameter\n");
+    output.push_str("  deriving Repr, DecidableEq\n\n");
+    output.push_str("structure Transition where\n");
+    output.push_str("  trigger : String\n");
+    output.push_str("  source : String\n");
+    output.push_str("  target : String\n");
+    output.push_str("  emits : List String\n");
+    output.push_str("  deriving Repr, DecidableEq\n\n");
+    output.push_str("structure Behavior where\n");
+    output.push_str("  domain : String\n");
+    output.push_str("  states : List String\n");
+    output.push_str("  transitions : List Transition\n");
     output.push_str("  deriving Repr, DecidableEq\n\n");
     output.push_str("structure Interface where\n");
     output.push_str("  name : String\n");
@@ -665,6 +820,7 @@ pub fn generate_lean_model(problem: &Problem) -> String {
     output.push_str("  phenomena : List Phenomenon\n");
     output.push_str("  deriving Repr, DecidableEq\n\n");
     output.push_str("structure Requirement where\n");
+    output.push_str("  id : String\n");
     output.push_str("  name : String\n");
     output.push_str("  frame : FrameType\n");
     output.push_str("  constraint : String\n");
@@ -687,8 +843,19 @@ pub fn generate_lean_model(problem: &Problem) -> String {
         escape_lean_string(&problem.name)
     ));
 
+    output.push_str(&format!(
+        "def primitiveTypes : List String := {}\n\n",
+        lean_string_list_expr(
+            &PRIMITIVE_TYPES
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        )
+    ));
+    emit_data_types(&problem.types, &mut output);
     emit_domains(&problem.domains, &mut output);
     emit_interfaces(&problem.interfaces, &mut output);
+    emit_behaviors(&problem.domains, &mut output);
     emit_requirements(&problem.requirements, &mut output);
     emit_assertion_sets(problem, &mut output);
     emit_correctness_arguments(&problem.correctness_arguments, &mut output);
@@ -698,6 +865,25 @@ pub fn generate_lean_model(problem: &Problem) -> String {
     output.push_str("def interfaceControllersDeclaredBool : Bool :=\n");
     output.push_str("  interfaces.all fun iface =>\n");
     output.push_str("    iface.phenomena.all fun p => p.controlledBy ∈ iface.connects\n\n");
+    output.push_str("def typeDeclared (name : String) : Bool :=\n");
+    output.push_str("  name ∈ primitiveTypes || dataTypes.any fun t => t.name = name\n\n");
+    output.push_str("def parameterTypesDeclaredBool : Bool :=\n");
+    output.push_str("  interfaces.all fun iface =>\n");
+    output.push_str(
+        "    iface.phenomena.all fun p => p.parameters.all fun param => typeDeclared param.typeName\n\n",
+    );
+    output.push_str("def sharedPhenomenon (pred : Phenomenon -> Bool) : Bool :=\n");
+    output.push_str("  interfaces.any fun iface => iface.phenomena.any pred\n\n");
+    output.push_str("def behaviorsConsistentBool : Bool :=\n");
+    output.push_str("  behaviors.all fun b =>\n");
+    output.push_str("    b.transitions.all fun t =>\n");
+    output.push_str("      t.source ∈ b.states && t.target ∈ b.states &&\n");
+    output.push_str(
+        "      sharedPhenomenon (fun p => p.name = t.trigger && p.toDomain = b.domain) &&\n",
+    );
+    output.push_str(
+        "      t.emits.all fun e => sharedPhenomenon (fun p => p.name = e && p.controlledBy = b.domain)\n\n",
+    );
     output
         .push_str("def assertionSetHasScope (name : String) (scope : AssertionScope) : Bool :=\n");
     output.push_str("  assertionSets.any fun set => set.name = name && set.scope = scope\n\n");
@@ -718,6 +904,14 @@ pub fn generate_lean_model(problem: &Problem) -> String {
         "theorem interfaceControllersDeclared : interfaceControllersDeclaredBool = true := by\n",
     );
     output.push_str("  decide\n\n");
+    output.push_str("/-- Every phenomenon parameter has a primitive or declared type. -/\n");
+    output.push_str("theorem parameterTypesDeclared : parameterTypesDeclaredBool = true := by\n");
+    output.push_str("  decide\n\n");
+    output.push_str(
+        "/-- Domain behaviors only use declared states, received triggers and controlled emissions. -/\n",
+    );
+    output.push_str("theorem behaviorsConsistent : behaviorsConsistentBool = true := by\n");
+    output.push_str("  decide\n\n");
     output.push_str(
         "/-- Every correctness argument references assertion sets with valid W/S/R scopes. -/\n",
     );
@@ -734,11 +928,16 @@ mod tests {
     use super::{generate_lean_coverage_json, generate_lean_model};
     use crate::ast::{
         Assertion, AssertionScope, AssertionSet, CorrectnessArgument, Domain, DomainKind,
-        DomainRole, FrameType, Interface, Phenomenon, PhenomenonType, Problem, Reference, Span,
+        DomainRole, FileId, FrameType, ImportGraph, Interface, Phenomenon, PhenomenonType, Problem,
+        Reference, SourceMap, Span,
     };
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn reference(name: &str) -> Reference {
@@ -754,29 +953,40 @@ mod tests {
             name: "LeanExport".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 Domain {
                     name: "Controller".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Machine,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: span(),
                     source_path: None,
                 },
                 Domain {
                     name: "Operator".to_string(),
+                    doc: None,
                     kind: DomainKind::Biddable,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: span(),
                     source_path: None,
                 },
             ],
             interfaces: vec![Interface {
                 name: "Operator-Controller".to_string(),
+                doc: None,
                 connects: vec![reference("Operator"), reference("Controller")],
                 shared_phenomena: vec![Phenomenon {
                     name: "Command".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Command,
                     from: reference("Operator"),
                     to: reference("Controller"),
@@ -788,6 +998,11 @@ mod tests {
             }],
             requirements: vec![crate::ast::Requirement {
                 name: "SafeOperation".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::CommandedBehavior,
                 phenomena: vec![],
                 marks: vec![],
@@ -800,6 +1015,7 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![AssertionSet {
                 name: "S_main".to_string(),
+                doc: None,
                 scope: AssertionScope::Specification,
                 assertions: vec![Assertion {
                     text: "command implies action".to_string(),
@@ -820,6 +1036,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let first = generate_lean_model(&problem);
@@ -842,11 +1060,16 @@ mod tests {
             name: "Projection".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "Machine".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: span(),
                 source_path: None,
             }],
@@ -856,6 +1079,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![
                         Assertion {
@@ -874,6 +1098,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![
                         Assertion {
@@ -892,6 +1117,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![
                         Assertion {
@@ -920,6 +1146,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let lean_model = generate_lean_model(&problem);
@@ -936,11 +1164,16 @@ mod tests {
             name: "SubsetProjection".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "Machine".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: span(),
                 source_path: None,
             }],
@@ -950,6 +1183,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "WorldFact".to_string(),
@@ -961,6 +1195,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "SpecFact".to_string(),
@@ -972,6 +1207,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![
                         Assertion {
@@ -1000,6 +1236,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let lean_model = generate_lean_model(&problem);
@@ -1010,4 +1248,56 @@ mod tests {
         assert!(coverage_json.contains("\"formalized_count\": 1"));
         assert!(coverage_json.contains("\"mode\": \"lean_atom_projection_subset_entailment\""));
     }
+
+    #[test]
+    fn lean_model_carries_data_types_and_phenomenon_parameters() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: TypedLean
+            type Percent = range 0..100 unit "%"
+            type Mode = enum { Auto, Manual }
+            domain Tool kind causal role machine
+            domain Operator kind biddable role given
+            interface "Operator-Tool" connects Operator, Tool {
+                shared: {
+                    phenomenon SetLevel(level: Percent, mode: Mode) : command [Operator -> Tool] controlledBy Operator
+                }
+            }
+            "#,
+        )
+        .expect("parse failed");
+
+        let lean = generate_lean_model(&problem);
+        assert!(lean.contains("structure Parameter where"));
+        assert!(lean.contains(
+            "{ name := \"Mode\", form := DataTypeForm.enumeration, literals := [\"Auto\", \"Manual\"], fields := [], bounds := none, unit := none }"
+        ));
+        assert!(lean.contains("bounds := some (\"0\", \"100\"), unit := some \"%\""));
+        assert!(lean.contains(
+            "parameters := [{ name := \"level\", typeName := \"Percent\" }, { name := \"mode\", typeName := \"Mode\" }]"
+        ));
+        assert!(lean.contains("theorem parameterTypesDeclared"));
+    }
+
+    #[test]
+    fn lean_model_carries_domain_behaviors() {
+        let problem = crate::parser::parse(
+            r#"
+            problem: GateStates
+            domain Controller kind causal role machine
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                on PulseOpen from Closed to Open emits GateOpened
+            }
+            "#,
+        )
+        .expect("parse failed");
+
+        let lean = generate_lean_model(&problem);
+        assert!(lean.contains("structure Behavior where"));
+        assert!(lean.contains(
+            "  { domain := \"Gate\", states := [\"Closed\", \"Open\"], transitions := [{ trigger := \"PulseOpen\", source := \"Closed\", target := \"Open\", emits := [\"GateOpened\"] }] },"
+        ));
+        assert!(lean.contains("theorem behaviorsConsistent"));
+    }
 }
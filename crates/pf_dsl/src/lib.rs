@@ -1,13 +1,21 @@
 pub mod ast;
 pub mod codegen;
+pub mod composition;
 pub mod concern_coverage;
 pub mod decomposition_closure;
 pub mod dot_export;
+pub mod fixes;
 pub mod formal_alloy;
 pub mod formal_closure;
+pub mod formatter;
+pub mod frames;
+pub mod import_graph;
 pub mod language;
 pub mod lean_export;
+pub mod lints;
+pub mod manifest;
 mod metamodel_contract_tests;
+pub mod module_graph;
 pub mod obligations;
 pub mod parser;
 mod parser_tests;
@@ -15,8 +23,12 @@ pub mod pim;
 pub mod report_gen;
 pub mod resolver;
 mod resolver_tests;
+pub mod rule_catalog;
+pub mod source_map;
 pub mod trace_map;
 pub mod traceability;
+pub mod unused;
 pub mod validator;
 mod validator_tests;
+pub mod vfs;
 pub mod wrspm;
@@ -0,0 +1,422 @@
+use crate::ast::*;
+use crate::composition::check_composition;
+use crate::formatter::scan_comments;
+use crate::rule_catalog::{rule_by_id, Severity};
+use crate::unused::check_unused;
+use crate::validator::{
+    issues_with_sources, validate_with_sources, validation_error_span, ValidationError,
+    ValidationIssue, UNRESOLVED_REFERENCE_VARIANTS,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const ALLOW_MARKER: &str = "pf-allow(";
+
+// Composition concerns between subproblems (`CompositionConcern`).
+pub const COMPOSITION_CONCERNS: &str = "PF-VAL-048";
+
+// Rules that only run once `pf.toml` or the command line sets them to warn or deny.
+pub const OPT_IN_RULES: [&str; 1] = [COMPOSITION_CONCERNS];
+
+// Project-wide level of a rule, set in the `[lints]` table of `pf.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// `// pf-allow(PF-VAL-015): reason` silences one rule inside the declaration the
+// comment sits in, or else the declaration that follows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    pub rule_id: String,
+    pub reason: String,
+    pub span: Span,
+    pub scope: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressedBy {
+    // index into `LintOutcome::suppressions`
+    Inline(usize),
+    Config,
+}
+
+#[derive(Debug, Default)]
+pub struct LintOutcome {
+    // findings that are still reported, re-levelled by the project configuration
+    pub issues: Vec<ValidationIssue>,
+    pub suppressed: Vec<(ValidationIssue, SuppressedBy)>,
+    pub suppressions: Vec<Suppression>,
+    // rules turned off for the whole project
+    pub allowed_rules: Vec<String>,
+}
+
+impl LintOutcome {
+    pub fn is_fatal(&self, deny_warnings: bool) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.is_fatal(deny_warnings))
+    }
+}
+
+// Validates the model and looks for unused declarations, and for composition
+// concerns between subproblems when they are enabled, then applies inline
+// suppressions and the project levels. Unresolved references stay errors
+// whatever the suppressions and levels say.
+pub fn lint(problem: &Problem, levels: &BTreeMap<String, LintLevel>) -> LintOutcome {
+    let mut outcome = LintOutcome {
+        suppressions: inline_suppressions(problem),
+        allowed_rules: levels
+            .iter()
+            .filter(|(_, level)| **level == LintLevel::Allow)
+            .map(|(rule_id, _)| rule_id.clone())
+            .collect(),
+        ..LintOutcome::default()
+    };
+
+    let unused = issues_with_sources(problem, check_unused(problem).err().unwrap_or_default());
+    let composition = if is_enabled(levels, COMPOSITION_CONCERNS) {
+        issues_with_sources(
+            problem,
+            check_composition(problem).err().unwrap_or_default(),
+        )
+    } else {
+        Vec::new()
+    };
+    let suppressions = issues_with_sources(
+        problem,
+        check_suppressions(&outcome.suppressions)
+            .err()
+            .unwrap_or_default(),
+    );
+    let findings = validate_with_sources(problem).err().unwrap_or_default();
+    for mut issue in findings
+        .into_iter()
+        .chain(unused)
+        .chain(composition)
+        .chain(suppressions)
+    {
+        if issue.error.leaves_reference_unresolved() {
+            outcome.issues.push(issue);
+            continue;
+        }
+        let span = validation_error_span(&issue.error);
+        let inline = outcome.suppressions.iter().position(|suppression| {
+            suppression.rule_id == issue.rule_id
+                && suppression.scope.file == span.file
+                && (suppression.scope.start..suppression.scope.end).contains(&span.start)
+        });
+        if let Some(index) = inline {
+            outcome
+                .suppressed
+                .push((issue, SuppressedBy::Inline(index)));
+            continue;
+        }
+        match levels.get(issue.rule_id) {
+            Some(LintLevel::Allow) => {
+                outcome.suppressed.push((issue, SuppressedBy::Config));
+                continue;
+            }
+            Some(LintLevel::Warn) => issue.severity = Severity::Warning,
+            Some(LintLevel::Deny) => issue.severity = Severity::Error,
+            None => {}
+        }
+        outcome.issues.push(issue);
+    }
+    outcome
+}
+
+fn is_enabled(levels: &BTreeMap<String, LintLevel>, rule_id: &str) -> bool {
+    !OPT_IN_RULES.contains(&rule_id)
+        || matches!(levels.get(rule_id), Some(LintLevel::Warn | LintLevel::Deny))
+}
+
+// Turns an opt-in rule on as a warning unless the project already enables it.
+pub fn enable(levels: &mut BTreeMap<String, LintLevel>, rule_id: &str) {
+    if !is_enabled(levels, rule_id) {
+        levels.insert(rule_id.to_string(), LintLevel::Warn);
+    }
+}
+
+// Suppressions are read from the sources of a resolved model; a model parsed from
+// a string has none.
+pub fn inline_suppressions(problem: &Problem) -> Vec<Suppression> {
+    let mut suppressions = Vec::new();
+    for (file, source) in problem.sources.files() {
+        let mut scopes: Vec<Span> = declaration_spans(problem, file)
+            .into_iter()
+            .map(|span| Span {
+                end: content_end(source.text(), span),
+                ..span
+            })
+            .collect();
+        scopes.sort_by_key(|span| (span.start, std::cmp::Reverse(span.end)));
+
+        for comment in scan_comments(source.text()) {
+            let Some((rule_id, reason)) = parse_allow(&comment.text) else {
+                continue;
+            };
+            let enclosing = scopes
+                .iter()
+                .rfind(|scope| (scope.start..scope.end).contains(&comment.start));
+            let following = scopes.iter().find(|scope| scope.start > comment.start);
+            let Some(&scope) = enclosing.or(following) else {
+                continue;
+            };
+            suppressions.push(Suppression {
+                rule_id,
+                reason,
+                span: Span {
+                    start: comment.start,
+                    end: comment.start + comment.text.len(),
+                    file,
+                },
+                scope,
+            });
+        }
+    }
+    suppressions
+}
+
+// An inline allow must name a catalog rule that may be suppressed at all.
+pub fn check_suppressions(suppressions: &[Suppression]) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    for suppression in suppressions {
+        let problem = match rule_by_id(&suppression.rule_id) {
+            None => "unknown rule".to_string(),
+            Some(rule) if UNRESOLVED_REFERENCE_VARIANTS.contains(&rule.error_variant.as_str()) => {
+                format!(
+                    "{} leaves a reference unresolved and cannot be suppressed",
+                    rule.error_variant
+                )
+            }
+            Some(_) => continue,
+        };
+        errors.push(ValidationError::InvalidSuppression(
+            suppression.rule_id.clone(),
+            problem,
+            suppression.span,
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_allow(comment: &str) -> Option<(String, String)> {
+    let body = comment.trim_start_matches('/').trim_start();
+    let rest = body.strip_prefix(ALLOW_MARKER)?;
+    let (rule_id, rest) = rest.split_once(')')?;
+    let reason = rest.trim_start().strip_prefix(':').unwrap_or(rest);
+    Some((rule_id.trim().to_string(), reason.trim().to_string()))
+}
+
+// Declaration spans run on over the comments that follow them; a comment on the
+// lines after a declaration belongs to the next one.
+fn content_end(text: &str, span: Span) -> usize {
+    let mut end = span.end.min(text.len());
+    loop {
+        end = span.start + text[span.start..end].trim_end().len();
+        let line_start = text[..end].rfind('\n').map_or(0, |index| index + 1);
+        if line_start > span.start && text[line_start..end].trim_start().starts_with("//") {
+            end = line_start;
+        } else {
+            return end;
+        }
+    }
+}
+
+fn declaration_spans(problem: &Problem, file: FileId) -> Vec<Span> {
+    let spans = problem
+        .frames
+        .iter()
+        .map(|frame| frame.span)
+        .chain(problem.types.iter().map(|data_type| data_type.span))
+        .chain(problem.domains.iter().map(|domain| domain.span))
+        .chain(problem.interfaces.iter().map(|interface| interface.span))
+        .chain(
+            problem
+                .requirements
+                .iter()
+                .map(|requirement| requirement.span),
+        )
+        .chain(problem.subproblems.iter().map(|subproblem| subproblem.span))
+        .chain(problem.assertion_sets.iter().map(|set| set.span))
+        .chain(
+            problem
+                .correctness_arguments
+                .iter()
+                .map(|argument| argument.span),
+        )
+        .chain(problem.import_graph.edges.iter().map(|edge| edge.span));
+    spans.filter(|span| span.file == file).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::resolve_with_files;
+    use crate::vfs::MemoryFiles;
+    use std::path::Path;
+
+    const MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+domain Valve kind causal role given
+// pf-allow(PF-VAL-007): the gate is wired by another team
+requirement "Open" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+requirement "Close" {
+  frame: RequiredBehavior
+  constrains: Valve
+}
+"#;
+
+    fn resolved(text: &str) -> Problem {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/plant.pf", text);
+        resolve_with_files(Path::new("/work/plant.pf"), &files).expect("model resolves")
+    }
+
+    #[test]
+    fn inline_allow_is_scoped_to_the_following_declaration() {
+        let problem = resolved(MODEL);
+        let outcome = lint(&problem, &BTreeMap::new());
+
+        assert_eq!(outcome.suppressions.len(), 1);
+        let suppression = &outcome.suppressions[0];
+        assert_eq!(suppression.rule_id, "PF-VAL-007");
+        assert_eq!(suppression.reason, "the gate is wired by another team");
+        assert_eq!(
+            suppression.scope.start,
+            MODEL.find("requirement \"Open\"").unwrap()
+        );
+
+        let suppressed: Vec<String> = outcome
+            .suppressed
+            .iter()
+            .map(|(issue, _)| issue.error.to_string())
+            .collect();
+        assert!(!suppressed.is_empty());
+        assert!(suppressed.iter().all(|message| message.contains("Gate")));
+        let missing: Vec<String> = outcome
+            .issues
+            .iter()
+            .filter(|issue| issue.rule_id == "PF-VAL-007")
+            .map(|issue| issue.error.to_string())
+            .collect();
+        assert!(missing.iter().any(|message| message.contains("Valve")));
+        assert!(!missing.iter().any(|message| message.contains("Gate")));
+    }
+
+    #[test]
+    fn inline_allow_of_an_unknown_rule_is_reported() {
+        let problem = resolved(&MODEL.replace("PF-VAL-007", "PF-VAL-07"));
+        let outcome = lint(&problem, &BTreeMap::new());
+        assert!(outcome.suppressed.is_empty());
+        let invalid: Vec<&ValidationIssue> = outcome
+            .issues
+            .iter()
+            .filter(|issue| issue.rule_id == "PF-VAL-050")
+            .collect();
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].severity, Severity::Warning);
+        assert_eq!(
+            invalid[0].error.to_string(),
+            "Suppression 'pf-allow(PF-VAL-07)' has no effect: unknown rule"
+        );
+    }
+
+    #[test]
+    fn unresolved_references_cannot_be_suppressed() {
+        let text = MODEL
+            .replace("PF-VAL-007", "PF-VAL-002")
+            .replace("constrains: Gate", "constrains: Operator");
+        let problem = resolved(&text);
+        let levels = BTreeMap::from([("PF-VAL-002".to_string(), LintLevel::Allow)]);
+        let outcome = lint(&problem, &levels);
+
+        assert!(outcome
+            .suppressed
+            .iter()
+            .all(|(issue, _)| issue.rule_id != "PF-VAL-002"));
+        assert!(outcome
+            .issues
+            .iter()
+            .any(|issue| issue.rule_id == "PF-VAL-002"
+                && issue.severity == Severity::Error
+                && issue.error.to_string().contains("Operator")));
+        assert!(outcome
+            .issues
+            .iter()
+            .any(|issue| issue.rule_id == "PF-VAL-050"
+                && issue.error.to_string().contains("cannot be suppressed")));
+        assert!(outcome.is_fatal(false));
+    }
+
+    #[test]
+    fn project_levels_allow_and_relevel_rules() {
+        let problem = resolved(&MODEL.replace("// pf-allow", "// note"));
+        let levels = BTreeMap::from([("PF-VAL-007".to_string(), LintLevel::Warn)]);
+        let outcome = lint(&problem, &levels);
+        assert!(outcome
+            .issues
+            .iter()
+            .filter(|issue| issue.rule_id == "PF-VAL-007")
+            .all(|issue| issue.severity == Severity::Warning));
+
+        let levels = BTreeMap::from([("PF-VAL-007".to_string(), LintLevel::Allow)]);
+        let outcome = lint(&problem, &levels);
+        assert!(outcome
+            .issues
+            .iter()
+            .all(|issue| issue.rule_id != "PF-VAL-007"));
+        assert!(outcome
+            .suppressed
+            .iter()
+            .all(|(_, by)| *by == SuppressedBy::Config));
+        assert_eq!(outcome.allowed_rules, ["PF-VAL-007"]);
+    }
+
+    #[test]
+    fn composition_concerns_are_reported_only_when_enabled() {
+        let example = &rule_by_id(COMPOSITION_CONCERNS)
+            .expect("rule is catalogued")
+            .examples
+            .invalid;
+        let problem = resolved(&example.join("\n"));
+        let reports = |levels: &BTreeMap<String, LintLevel>| {
+            lint(&problem, levels)
+                .issues
+                .iter()
+                .any(|issue| issue.rule_id == COMPOSITION_CONCERNS)
+        };
+
+        assert!(!reports(&BTreeMap::new()));
+        let mut levels = BTreeMap::new();
+        enable(&mut levels, COMPOSITION_CONCERNS);
+        assert!(reports(&levels));
+        let levels = BTreeMap::from([(COMPOSITION_CONCERNS.to_string(), LintLevel::Deny)]);
+        assert!(reports(&levels));
+    }
+
+    #[test]
+    fn parses_allow_comments() {
+        assert_eq!(
+            parse_allow("// pf-allow(PF-VAL-015): shared display"),
+            Some(("PF-VAL-015".to_string(), "shared display".to_string()))
+        );
+        assert_eq!(
+            parse_allow("//pf-allow( PF-VAL-001 )"),
+            Some(("PF-VAL-001".to_string(), String::new()))
+        );
+        assert_eq!(parse_allow("// allow PF-VAL-001"), None);
+    }
+}
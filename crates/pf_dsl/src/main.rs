@@ -1,13 +1,23 @@
 use anyhow::{anyhow, Result};
+use pf_dsl::ast::Problem;
+use pf_dsl::fixes::{apply_fixes, Fix};
+use pf_dsl::lints::{enable, lint, LintLevel, COMPOSITION_CONCERNS};
+use pf_dsl::manifest::{Manifest, MANIFEST_FILE};
+use pf_dsl::module_graph::declared_in;
+use pf_dsl::report_gen::RequirementFilter;
+use pf_dsl::rule_catalog::{find_rule, Severity};
 use pf_dsl::traceability::TraceEntity;
-use pf_dsl::validator::validate;
-use std::collections::BTreeSet;
+use pf_dsl::validator::ValidationIssue;
+use pf_dsl::vfs::DiskFiles;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::fs;
+use std::path::Path;
 
 const DEFAULT_IMPACT_HOPS: usize = 2;
 
 fn usage() -> &'static str {
-    "Usage: pf_dsl <file.pf> [--dot | --dot-context | --dot-problem | --dot-decomposition | --report | --gen-rust | --obligations | --alloy | --lean-model | --lean-coverage-json | --formal-closure-map-tsv | --requirements-tsv | --correctness-arguments-tsv | --traceability-md | --traceability-csv | --decomposition-closure | --concern-coverage | --wrspm-report | --wrspm-json | --ddd-pim | --sysml2-text | --sysml2-json | --trace-map-json] [--impact=requirement:<name>,domain:<name>] [--impact-hops=<n>]"
+    "Usage: pf_dsl fmt [--check] <file.pf>...\n       pf_dsl check [--deny-warnings] [--composition-concerns] [<pf.toml or directory>]\n       pf_dsl explain <PF-VAL-id or rule name>\n       pf_dsl <file.pf> [--dot | --dot-context | --dot-problem | --dot-decomposition | --dot-behavior | --report | --gen-rust | --obligations | --alloy | --lean-model | --lean-coverage-json | --formal-closure-map-tsv | --requirements-tsv | --correctness-arguments-tsv | --traceability-md | --traceability-csv | --decomposition-closure | --concern-coverage | --wrspm-report | --wrspm-json | --ddd-pim | --sysml2-text | --sysml2-json | --trace-map-json | --imports-dot | --fix] [--impact=requirement:<name>,domain:<name>] [--impact-hops=<n>] [--status=<status>] [--owner=<owner>] [--module=<file.pf>] [--deny-warnings] [--composition-concerns]"
 }
 
 fn parse_impact_seeds(raw: &str) -> Result<Vec<TraceEntity>> {
@@ -40,14 +50,29 @@ fn parse_impact_seeds(raw: &str) -> Result<Vec<TraceEntity>> {
     Ok(seeds.into_iter().collect())
 }
 
-fn validate_traceability_seeds(
+// Requirement seeds may name a requirement by title or ID; the graph keys on the ID.
+fn resolve_traceability_seeds(
     problem: &pf_dsl::ast::Problem,
     seeds: &[TraceEntity],
-) -> Result<()> {
+) -> Result<Vec<TraceEntity>> {
+    let seeds = seeds
+        .iter()
+        .map(|seed| match seed {
+            TraceEntity::Requirement(name) => TraceEntity::Requirement(
+                problem
+                    .requirement(name)
+                    .map(|requirement| requirement.key())
+                    .unwrap_or(name)
+                    .to_string(),
+            ),
+            seed => seed.clone(),
+        })
+        .collect::<Vec<_>>();
+
     let known_requirements: BTreeSet<&str> = problem
         .requirements
         .iter()
-        .map(|requirement| requirement.name.as_str())
+        .map(|requirement| requirement.key())
         .collect();
     let known_domains: BTreeSet<&str> = problem
         .domains
@@ -55,7 +80,7 @@ fn validate_traceability_seeds(
         .map(|domain| domain.name.as_str())
         .collect();
 
-    for seed in seeds {
+    for seed in &seeds {
         match seed {
             TraceEntity::Requirement(name) => {
                 if !known_requirements.contains(name.as_str()) {
@@ -79,12 +104,25 @@ fn validate_traceability_seeds(
         }
     }
 
-    Ok(())
+    Ok(seeds)
+}
+
+struct CliOptions {
+    impact_seeds: Vec<TraceEntity>,
+    impact_hops: usize,
+    requirement_filter: RequirementFilter,
+    module: Option<String>,
+    deny_warnings: bool,
+    composition_concerns: bool,
 }
 
-fn parse_cli_options(args: &[String]) -> Result<(Vec<TraceEntity>, usize)> {
+fn parse_cli_options(args: &[String]) -> Result<CliOptions> {
     let mut impact_seeds = Vec::new();
     let mut impact_hops = DEFAULT_IMPACT_HOPS;
+    let mut requirement_filter = RequirementFilter::default();
+    let mut module = None;
+    let mut deny_warnings = false;
+    let mut composition_concerns = false;
     let mut index = 3;
 
     while index < args.len() {
@@ -125,10 +163,235 @@ fn parse_cli_options(args: &[String]) -> Result<(Vec<TraceEntity>, usize)> {
             continue;
         }
 
+        if let Some(raw) = arg.strip_prefix("--status=") {
+            requirement_filter.status = Some(raw.parse().map_err(|message: String| {
+                anyhow!("{message}, expected draft, approved, implemented or deprecated")
+            })?);
+            index += 1;
+            continue;
+        }
+
+        if let Some(raw) = arg.strip_prefix("--owner=") {
+            if raw.trim().is_empty() {
+                return Err(anyhow!("empty value for --owner"));
+            }
+            requirement_filter.owner = Some(raw.trim().to_string());
+            index += 1;
+            continue;
+        }
+
+        if let Some(raw) = arg.strip_prefix("--module=") {
+            if raw.trim().is_empty() {
+                return Err(anyhow!("empty value for --module"));
+            }
+            module = Some(raw.trim().to_string());
+            index += 1;
+            continue;
+        }
+
+        if arg == "--module" {
+            if index + 1 >= args.len() {
+                return Err(anyhow!("missing value for --module"));
+            }
+            module = Some(args[index + 1].clone());
+            index += 2;
+            continue;
+        }
+
+        if arg == "--deny-warnings" {
+            deny_warnings = true;
+            index += 1;
+            continue;
+        }
+
+        if arg == "--composition-concerns" {
+            composition_concerns = true;
+            index += 1;
+            continue;
+        }
+
         return Err(anyhow!("unknown CLI option '{arg}'. {}", usage()));
     }
 
-    Ok((impact_seeds, impact_hops))
+    Ok(CliOptions {
+        impact_seeds,
+        impact_hops,
+        requirement_filter,
+        module,
+        deny_warnings,
+        composition_concerns,
+    })
+}
+
+fn run_fmt(args: &[String]) -> Result<()> {
+    let mut check = false;
+    let mut files = Vec::new();
+    for arg in args {
+        if arg == "--check" {
+            check = true;
+        } else if arg.starts_with("--") {
+            return Err(anyhow!("unknown fmt option '{arg}'. {}", usage()));
+        } else {
+            files.push(arg);
+        }
+    }
+    if files.is_empty() {
+        return Err(anyhow!("missing input file for fmt. {}", usage()));
+    }
+
+    let mut unformatted = Vec::new();
+    for file in files {
+        let source = fs::read_to_string(file)
+            .map_err(|error| anyhow!("failed to read '{file}': {error}"))?;
+        let formatted = pf_dsl::formatter::format_source(&source)
+            .map_err(|error| anyhow!("failed to format '{file}': {error}"))?;
+        if formatted == source {
+            continue;
+        }
+        if check {
+            unformatted.push(file);
+        } else {
+            fs::write(file, formatted)
+                .map_err(|error| anyhow!("failed to write '{file}': {error}"))?;
+        }
+    }
+
+    if !unformatted.is_empty() {
+        for file in unformatted {
+            eprintln!("Not formatted: {file}");
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// Prints every finding and returns whether any of them fails the model.
+fn report_validation_issues(
+    problem: &Problem,
+    issues: &[ValidationIssue],
+    deny_warnings: bool,
+) -> bool {
+    let locate = |issue: &ValidationIssue| {
+        let span = pf_dsl::validator::validation_error_span(&issue.error);
+        let line = match problem.sources.location(span) {
+            Some(location) => format!("{location}: {} [{}]", issue.error, issue.rule_id),
+            None => format!("{} [{}]", issue.error, issue.rule_id),
+        };
+        match issue.help() {
+            Some(help) => format!("{line}\n  help: {help}"),
+            None => line,
+        }
+    };
+    let (fatal, advisory): (Vec<_>, Vec<_>) = issues
+        .iter()
+        .partition(|issue| issue.is_fatal(deny_warnings));
+
+    for issue in &advisory {
+        eprintln!("{}: {}", issue.severity, locate(issue));
+    }
+    if fatal.is_empty() {
+        return false;
+    }
+    eprintln!("Validation Errors:");
+    for issue in &fatal {
+        match issue.severity {
+            Severity::Error => eprintln!("- {}", locate(issue)),
+            severity => eprintln!("- {severity}: {}", locate(issue)),
+        }
+    }
+    true
+}
+
+fn run_explain(args: &[String]) -> Result<()> {
+    let [name] = args else {
+        return Err(anyhow!("explain takes one rule. {}", usage()));
+    };
+    let rule = find_rule(name).ok_or_else(|| anyhow!("unknown rule '{name}'"))?;
+    print!("{}", rule.explain());
+    Ok(())
+}
+
+// Rewrites each model file with the fixes of its findings, then validates the
+// rewritten model and reports what is left.
+fn apply_fixes_in_place(
+    path: &Path,
+    problem: &Problem,
+    issues: &[ValidationIssue],
+    lints: &BTreeMap<String, LintLevel>,
+    deny_warnings: bool,
+) -> Result<()> {
+    let fixes: Vec<&Fix> = issues.iter().flat_map(|issue| &issue.fixes).collect();
+    for (file, source) in problem.sources.files() {
+        let in_file = fixes.iter().filter(|fix| fix.span.file == file).count();
+        if in_file == 0 || !source.path().is_file() {
+            continue;
+        }
+        let fixed = apply_fixes(source.text(), file, fixes.iter().copied());
+        fs::write(source.path(), fixed)
+            .map_err(|error| anyhow!("failed to write {:?}: {error}", source.path()))?;
+        println!("Fixed {in_file} finding(s) in {}", source.path().display());
+    }
+
+    let problem = pf_dsl::resolver::resolve(path, None)?;
+    let outcome = lint(&problem, lints);
+    if report_validation_issues(&problem, &outcome.issues, deny_warnings) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// Validates every entry model declared by a `pf.toml`.
+fn run_check(args: &[String]) -> Result<()> {
+    let deny_warnings = args.iter().any(|arg| arg == "--deny-warnings");
+    let composition_concerns = args.iter().any(|arg| arg == "--composition-concerns");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--deny-warnings" && *arg != "--composition-concerns")
+        .cloned()
+        .collect();
+    let manifest = match args.as_slice() {
+        [] => Manifest::discover(Path::new("."), &DiskFiles)?,
+        [target] if Path::new(target).is_dir() => {
+            Manifest::discover(Path::new(target), &DiskFiles)?
+        }
+        [target] => Some(Manifest::load(Path::new(target), &DiskFiles)?),
+        _ => return Err(anyhow!("check takes at most one argument. {}", usage())),
+    };
+    let mut manifest = manifest.ok_or_else(|| anyhow!("no {MANIFEST_FILE} found"))?;
+    if composition_concerns {
+        enable(&mut manifest.lints, COMPOSITION_CONCERNS);
+    }
+    if manifest.entries.is_empty() {
+        return Err(anyhow!("{:?} declares no entries", manifest.path));
+    }
+
+    let mut failed = false;
+    for entry in &manifest.entries {
+        let problem = match pf_dsl::resolver::resolve(entry, None) {
+            Ok(problem) => problem,
+            Err(error) => {
+                eprintln!("{}: Error parsing file: {}", entry.display(), error);
+                failed = true;
+                continue;
+            }
+        };
+        let outcome = lint(&problem, &manifest.lints);
+        if outcome.issues.is_empty() {
+            println!("ok: {}", entry.display());
+            continue;
+        }
+        eprintln!("{}:", entry.display());
+        if report_validation_issues(&problem, &outcome.issues, deny_warnings) {
+            failed = true;
+        } else {
+            println!("ok: {}", entry.display());
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -138,152 +401,197 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args[1] == "fmt" {
+        return run_fmt(&args[2..]);
+    }
+    if args[1] == "check" {
+        return run_check(&args[2..]);
+    }
+    if args[1] == "explain" {
+        return run_explain(&args[2..]);
+    }
+
     let mode = if args.len() > 2 { &args[2] } else { "--dot" };
-    let (impact_seeds, impact_hops) = parse_cli_options(&args)?;
+    let CliOptions {
+        impact_seeds,
+        impact_hops,
+        requirement_filter,
+        module,
+        deny_warnings,
+        composition_concerns,
+    } = parse_cli_options(&args)?;
 
     let filename = &args[1];
     let path = std::path::Path::new(filename);
 
-    match pf_dsl::resolver::resolve(path, None) {
-        Ok(problem) => match validate(&problem) {
-            Ok(_) => match mode {
-                "--report" => {
-                    println!("{}", pf_dsl::report_gen::generate_report(&problem));
-                }
-                "--alloy" => {
-                    println!("{}", pf_dsl::formal_alloy::generate_alloy(&problem));
-                }
-                "--lean-model" => {
-                    println!("{}", pf_dsl::lean_export::generate_lean_model(&problem));
-                }
-                "--lean-coverage-json" => {
-                    match pf_dsl::lean_export::generate_lean_coverage_json(&problem) {
-                        Ok(json) => println!("{}", json),
-                        Err(error) => {
-                            eprintln!("Error generating Lean coverage JSON: {}", error);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                "--formal-closure-map-tsv" => {
-                    println!(
-                        "{}",
-                        pf_dsl::formal_closure::generate_formal_closure_map_tsv(&problem)
-                    );
-                }
-                "--requirements-tsv" => {
-                    println!(
-                        "{}",
-                        pf_dsl::formal_closure::generate_requirements_tsv(&problem)
-                    );
-                }
-                "--correctness-arguments-tsv" => {
-                    println!(
-                        "{}",
-                        pf_dsl::formal_closure::generate_correctness_arguments_tsv(&problem)
-                    );
-                }
-                "--obligations" => {
-                    println!(
-                        "{}",
-                        pf_dsl::obligations::generate_obligations_markdown(&problem)
-                    );
-                }
-                "--traceability-md" => {
-                    validate_traceability_seeds(&problem, &impact_seeds)?;
-                    println!(
-                        "{}",
-                        pf_dsl::traceability::generate_traceability_markdown(
-                            &problem,
-                            &impact_seeds,
-                            impact_hops,
-                        )
-                    );
-                }
-                "--traceability-csv" => {
-                    validate_traceability_seeds(&problem, &impact_seeds)?;
-                    println!(
-                        "{}",
-                        pf_dsl::traceability::generate_traceability_csv(
-                            &problem,
-                            &impact_seeds,
-                            impact_hops,
-                        )
-                    );
-                }
-                "--dot-context" => {
-                    println!("{}", pf_dsl::dot_export::to_context_dot(&problem));
-                }
-                "--dot-problem" => {
-                    println!("{}", pf_dsl::dot_export::to_problem_dot(&problem));
-                }
-                "--dot-decomposition" => {
-                    println!("{}", pf_dsl::dot_export::to_decomposition_dot(&problem));
-                }
-                "--decomposition-closure" => {
-                    println!(
-                        "{}",
-                        pf_dsl::decomposition_closure::generate_markdown(&problem)
-                    );
-                }
-                "--concern-coverage" => {
-                    println!("{}", pf_dsl::concern_coverage::generate_markdown(&problem));
-                }
-                "--wrspm-report" => {
-                    println!("{}", pf_dsl::wrspm::generate_markdown(&problem));
-                }
-                "--wrspm-json" => match pf_dsl::wrspm::generate_json(&problem) {
-                    Ok(json) => println!("{}", json),
-                    Err(error) => {
-                        eprintln!("Error generating WRSPM JSON: {}", error);
-                        std::process::exit(1);
-                    }
-                },
-                "--ddd-pim" => {
-                    println!("{}", pf_dsl::pim::generate_ddd_pim_markdown(&problem));
-                }
-                "--sysml2-text" => {
-                    println!("{}", pf_dsl::pim::generate_sysml2_text(&problem));
-                }
-                "--sysml2-json" => match pf_dsl::pim::generate_sysml2_json(&problem) {
-                    Ok(json) => println!("{}", json),
-                    Err(error) => {
-                        eprintln!("Error generating SysML v2 JSON: {}", error);
-                        std::process::exit(1);
-                    }
-                },
-                "--trace-map-json" => match pf_dsl::trace_map::generate_trace_map_json(&problem) {
-                    Ok(json) => println!("{}", json),
-                    Err(error) => {
-                        eprintln!("Error generating trace map JSON: {}", error);
-                        std::process::exit(1);
-                    }
-                },
-                "--gen-rust" => match pf_dsl::codegen::generate_rust(&problem) {
-                    Ok(code) => println!("{}", code),
-                    Err(error) => {
-                        eprintln!("Error generating code: {}", error);
-                        std::process::exit(1);
-                    }
-                },
-                "--dot" => {
-                    println!("{}", pf_dsl::dot_export::to_dot(&problem));
-                }
-                _ => {
-                    return Err(anyhow!("unknown mode '{mode}'. {}", usage()));
-                }
-            },
-            Err(errors) => {
-                eprintln!("Validation Errors:");
-                for err in errors {
-                    eprintln!("- {}", err);
+    let resolved = pf_dsl::resolver::resolve_modules(path, &DiskFiles)
+        .and_then(|modules| Ok((modules.flatten()?, modules)));
+    let (problem, modules) = match resolved {
+        Ok(resolved) => resolved,
+        Err(error) => {
+            eprintln!("Error parsing file: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    if mode == "--imports-dot" {
+        // Drawn before validation so that import cycles can be inspected.
+        println!("{}", pf_dsl::dot_export::to_imports_dot(&problem));
+        return Ok(());
+    }
+
+    let mut lints = Manifest::discover(path, &DiskFiles)?
+        .map(|manifest| manifest.lints)
+        .unwrap_or_default();
+    if composition_concerns {
+        enable(&mut lints, COMPOSITION_CONCERNS);
+    }
+    let outcome = lint(&problem, &lints);
+    if mode == "--fix" {
+        return apply_fixes_in_place(path, &problem, &outcome.issues, &lints, deny_warnings);
+    }
+    if report_validation_issues(&problem, &outcome.issues, deny_warnings) {
+        std::process::exit(1);
+    }
+
+    // The whole model is validated; `--module` narrows what is exported.
+    let problem = match module {
+        Some(module) => declared_in(&problem, modules.find_module(Path::new(&module))?.file),
+        None => problem,
+    };
+
+    match mode {
+        "--report" => {
+            println!(
+                "{}{}",
+                pf_dsl::report_gen::generate_filtered_report(&problem, &requirement_filter),
+                pf_dsl::report_gen::suppressions_section(&outcome, &problem.sources)
+            );
+        }
+        "--alloy" => {
+            println!("{}", pf_dsl::formal_alloy::generate_alloy(&problem));
+        }
+        "--lean-model" => {
+            println!("{}", pf_dsl::lean_export::generate_lean_model(&problem));
+        }
+        "--lean-coverage-json" => {
+            match pf_dsl::lean_export::generate_lean_coverage_json(&problem) {
+                Ok(json) => println!("{}", json),
+                Err(error) => {
+                    eprintln!("Error generating Lean coverage JSON: {}", error);
+                    std::process::exit(1);
                 }
+            }
+        }
+        "--formal-closure-map-tsv" => {
+            println!(
+                "{}",
+                pf_dsl::formal_closure::generate_formal_closure_map_tsv(&problem)
+            );
+        }
+        "--requirements-tsv" => {
+            println!(
+                "{}",
+                pf_dsl::formal_closure::generate_requirements_tsv(&problem)
+            );
+        }
+        "--correctness-arguments-tsv" => {
+            println!(
+                "{}",
+                pf_dsl::formal_closure::generate_correctness_arguments_tsv(&problem)
+            );
+        }
+        "--obligations" => {
+            println!(
+                "{}",
+                pf_dsl::obligations::generate_obligations_markdown(&problem)
+            );
+        }
+        "--traceability-md" => {
+            let impact_seeds = resolve_traceability_seeds(&problem, &impact_seeds)?;
+            println!(
+                "{}",
+                pf_dsl::traceability::generate_traceability_markdown(
+                    &problem,
+                    &impact_seeds,
+                    impact_hops,
+                )
+            );
+        }
+        "--traceability-csv" => {
+            let impact_seeds = resolve_traceability_seeds(&problem, &impact_seeds)?;
+            println!(
+                "{}",
+                pf_dsl::traceability::generate_traceability_csv(
+                    &problem,
+                    &impact_seeds,
+                    impact_hops,
+                )
+            );
+        }
+        "--dot-context" => {
+            println!("{}", pf_dsl::dot_export::to_context_dot(&problem));
+        }
+        "--dot-problem" => {
+            println!("{}", pf_dsl::dot_export::to_problem_dot(&problem));
+        }
+        "--dot-decomposition" => {
+            println!("{}", pf_dsl::dot_export::to_decomposition_dot(&problem));
+        }
+        "--dot-behavior" => {
+            println!("{}", pf_dsl::dot_export::to_behavior_dot(&problem));
+        }
+        "--decomposition-closure" => {
+            println!(
+                "{}",
+                pf_dsl::decomposition_closure::generate_markdown(&problem)
+            );
+        }
+        "--concern-coverage" => {
+            println!("{}", pf_dsl::concern_coverage::generate_markdown(&problem));
+        }
+        "--wrspm-report" => {
+            println!("{}", pf_dsl::wrspm::generate_markdown(&problem));
+        }
+        "--wrspm-json" => match pf_dsl::wrspm::generate_json(&problem) {
+            Ok(json) => println!("{}", json),
+            Err(error) => {
+                eprintln!("Error generating WRSPM JSON: {}", error);
                 std::process::exit(1);
             }
         },
-        Err(error) => {
-            eprintln!("Error parsing file: {}", error);
-            std::process::exit(1);
+        "--ddd-pim" => {
+            println!("{}", pf_dsl::pim::generate_ddd_pim_markdown(&problem));
+        }
+        "--sysml2-text" => {
+            println!("{}", pf_dsl::pim::generate_sysml2_text(&problem));
+        }
+        "--sysml2-json" => match pf_dsl::pim::generate_sysml2_json(&problem) {
+            Ok(json) => println!("{}", json),
+            Err(error) => {
+                eprintln!("Error generating SysML v2 JSON: {}", error);
+                std::process::exit(1);
+            }
+        },
+        "--trace-map-json" => match pf_dsl::trace_map::generate_trace_map_json(&problem) {
+            Ok(json) => println!("{}", json),
+            Err(error) => {
+                eprintln!("Error generating trace map JSON: {}", error);
+                std::process::exit(1);
+            }
+        },
+        "--gen-rust" => match pf_dsl::codegen::generate_rust(&problem) {
+            Ok(code) => println!("{}", code),
+            Err(error) => {
+                eprintln!("Error generating code: {}", error);
+                std::process::exit(1);
+            }
+        },
+        "--dot" => {
+            println!("{}", pf_dsl::dot_export::to_dot(&problem));
+        }
+        _ => {
+            return Err(anyhow!("unknown mode '{mode}'. {}", usage()));
         }
     }
 
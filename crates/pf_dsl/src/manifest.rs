@@ -0,0 +1,279 @@
+use crate::lints::LintLevel;
+use crate::rule_catalog::rule_by_id;
+use crate::validator::UNRESOLVED_REFERENCE_VARIANTS;
+use crate::vfs::FileProvider;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "pf.toml";
+pub const PF_PATH_VAR: &str = "PF_PATH";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    #[serde(default)]
+    workspace: WorkspaceSection,
+    #[serde(default)]
+    libraries: BTreeMap<String, PathBuf>,
+    #[serde(default)]
+    lints: BTreeMap<String, LintLevel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct WorkspaceSection {
+    root: Option<PathBuf>,
+    #[serde(default)]
+    entries: Vec<PathBuf>,
+    #[serde(default)]
+    search_paths: Vec<PathBuf>,
+}
+
+// A `pf.toml` with its paths resolved: `root` and `search-paths` against the
+// manifest directory, `entries` against the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub path: PathBuf,
+    pub root: PathBuf,
+    pub entries: Vec<PathBuf>,
+    pub search_paths: Vec<PathBuf>,
+    pub libraries: BTreeMap<String, PathBuf>,
+    // level per `PF-VAL-...` rule ID
+    pub lints: BTreeMap<String, LintLevel>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path, files: &dyn FileProvider) -> Result<Self> {
+        let text = files
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read manifest: {:?}", path))?;
+        Self::parse(path, &text)
+    }
+
+    pub fn parse(path: &Path, text: &str) -> Result<Self> {
+        let file: ManifestFile =
+            toml::from_str(text).with_context(|| format!("Invalid manifest: {:?}", path))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let root = dir.join(file.workspace.root.unwrap_or_default());
+
+        for name in file.libraries.keys() {
+            let valid = name.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                && name
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+            if !valid {
+                return Err(anyhow!(
+                    "Invalid manifest: {:?}: library name '{}' must be an identifier",
+                    path,
+                    name
+                ));
+            }
+        }
+
+        if let Some(rule_id) = file.lints.keys().find(|id| rule_by_id(id).is_none()) {
+            return Err(anyhow!(
+                "Invalid manifest: {:?}: unknown rule '{}' in [lints]",
+                path,
+                rule_id
+            ));
+        }
+        if let Some(rule_id) = file.lints.iter().find_map(|(id, level)| {
+            let variant = rule_by_id(id)?.error_variant.as_str();
+            (*level != LintLevel::Deny && UNRESOLVED_REFERENCE_VARIANTS.contains(&variant))
+                .then_some(id)
+        }) {
+            return Err(anyhow!(
+                "Invalid manifest: {:?}: rule '{}' leaves references unresolved and stays an error",
+                path,
+                rule_id
+            ));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries: file
+                .workspace
+                .entries
+                .iter()
+                .map(|entry| root.join(entry))
+                .collect(),
+            search_paths: file
+                .workspace
+                .search_paths
+                .iter()
+                .map(|search_path| dir.join(search_path))
+                .collect(),
+            libraries: file
+                .libraries
+                .into_iter()
+                .map(|(name, library)| (name, dir.join(library)))
+                .collect(),
+            lints: file.lints,
+            root,
+        })
+    }
+
+    // Nearest `pf.toml` in `start` (a file or directory) or one of its ancestors.
+    pub fn discover(start: &Path, files: &dyn FileProvider) -> Result<Option<Self>> {
+        let start = files
+            .canonicalize(start)
+            .unwrap_or_else(|_| start.to_path_buf());
+        for dir in start.ancestors() {
+            let candidate = dir.join(MANIFEST_FILE);
+            if files.is_file(&candidate) {
+                return Self::load(&candidate, files).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+// Where imports that are not found next to the importing file are looked up:
+// `<library>/...` in a named library, then the model root and search paths in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchPaths {
+    pub roots: Vec<PathBuf>,
+    pub libraries: BTreeMap<String, PathBuf>,
+}
+
+impl SearchPaths {
+    // `PF_PATH`, when set, replaces the manifest's `search-paths`.
+    pub fn new(manifest: Option<&Manifest>, pf_path: Option<&OsStr>) -> Self {
+        let mut roots = Vec::new();
+        if let Some(manifest) = manifest {
+            roots.push(manifest.root.clone());
+        }
+        match pf_path {
+            Some(pf_path) => roots
+                .extend(std::env::split_paths(pf_path).filter(|path| !path.as_os_str().is_empty())),
+            None => roots.extend(manifest.into_iter().flat_map(|m| m.search_paths.clone())),
+        }
+        Self {
+            roots,
+            libraries: manifest.map(|m| m.libraries.clone()).unwrap_or_default(),
+        }
+    }
+
+    pub fn for_file(file: &Path, files: &dyn FileProvider) -> Result<Self> {
+        let start = match file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let manifest = Manifest::discover(start, files)?;
+        let pf_path = std::env::var_os(PF_PATH_VAR);
+        Ok(Self::new(manifest.as_ref(), pf_path.as_deref()))
+    }
+
+    pub fn locate(&self, import: &str, files: &dyn FileProvider) -> Option<PathBuf> {
+        let library = import
+            .split_once('/')
+            .and_then(|(name, rest)| self.libraries.get(name).map(|library| library.join(rest)));
+        library
+            .into_iter()
+            .chain(self.roots.iter().map(|root| root.join(import)))
+            .find(|candidate| files.is_file(candidate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFiles;
+
+    #[test]
+    fn parses_manifest_paths_relative_to_manifest_and_root() {
+        let manifest = Manifest::parse(
+            Path::new("/work/plant/pf.toml"),
+            r#"
+[workspace]
+root = "models"
+entries = ["sluice.pf"]
+search-paths = ["../catalogs"]
+
+[libraries]
+catalog = "shared/catalog"
+
+[lints]
+PF-VAL-015 = "warn"
+PF-VAL-019 = "allow"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.root, Path::new("/work/plant/models"));
+        assert_eq!(
+            manifest.entries,
+            [PathBuf::from("/work/plant/models/sluice.pf")]
+        );
+        assert_eq!(
+            manifest.search_paths,
+            [PathBuf::from("/work/plant/../catalogs")]
+        );
+        assert_eq!(
+            manifest.libraries["catalog"],
+            Path::new("/work/plant/shared/catalog")
+        );
+
+        assert_eq!(manifest.lints["PF-VAL-015"], LintLevel::Warn);
+        assert_eq!(manifest.lints["PF-VAL-019"], LintLevel::Allow);
+
+        assert!(Manifest::parse(Path::new("pf.toml"), "[workspace]\nentry = []\n").is_err());
+        assert!(
+            Manifest::parse(Path::new("pf.toml"), "[lints]\nPF-VAL-999 = \"allow\"\n").is_err()
+        );
+        assert!(Manifest::parse(Path::new("pf.toml"), "[lints]\nPF-VAL-001 = \"off\"\n").is_err());
+        assert!(
+            Manifest::parse(Path::new("pf.toml"), "[lints]\nPF-VAL-002 = \"allow\"\n").is_err()
+        );
+        assert!(Manifest::parse(Path::new("pf.toml"), "[lints]\nPF-VAL-002 = \"deny\"\n").is_ok());
+        assert!(Manifest::parse(Path::new("pf.toml"), "[libraries]\n\"../up\" = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn search_paths_prefer_libraries_and_honour_pf_path_override() {
+        let mut files = MemoryFiles::new();
+        for path in [
+            "/work/lib/gate.pf",
+            "/work/root/pump.pf",
+            "/work/extra/valve.pf",
+            "/work/env/valve.pf",
+        ] {
+            files.insert(path, "");
+        }
+        files.insert(
+            "/work/pf.toml",
+            "[workspace]\nroot = \"root\"\nsearch-paths = [\"extra\"]\n\n[libraries]\ncatalog = \"lib\"\n",
+        );
+
+        let manifest = Manifest::discover(Path::new("/work/root"), &files)
+            .unwrap()
+            .expect("manifest above the start directory");
+        assert_eq!(manifest.path, Path::new("/work/pf.toml"));
+
+        let search = SearchPaths::new(Some(&manifest), None);
+        let locate = |search: &SearchPaths, import: &str| search.locate(import, &files);
+        assert_eq!(
+            locate(&search, "catalog/gate.pf"),
+            Some(PathBuf::from("/work/lib/gate.pf"))
+        );
+        assert_eq!(
+            locate(&search, "pump.pf"),
+            Some(PathBuf::from("/work/root/pump.pf"))
+        );
+        assert_eq!(
+            locate(&search, "valve.pf"),
+            Some(PathBuf::from("/work/extra/valve.pf"))
+        );
+        assert_eq!(locate(&search, "missing.pf"), None);
+
+        let env_path = std::env::join_paths(["/work/env"]).unwrap();
+        let search = SearchPaths::new(Some(&manifest), Some(&env_path));
+        assert_eq!(
+            locate(&search, "valve.pf"),
+            Some(PathBuf::from("/work/env/valve.pf"))
+        );
+    }
+}
@@ -1,5 +1,9 @@
 #[cfg(test)]
 mod tests {
+    use crate::lints::{enable, lint, OPT_IN_RULES};
+    use crate::resolver::resolve_with_files;
+    use crate::rule_catalog::{example_files, rules};
+    use crate::vfs::MemoryFiles;
     use serde::Deserialize;
     use std::collections::{BTreeMap, BTreeSet};
     use std::fs;
@@ -175,7 +179,7 @@ mod tests {
 
         let validator_source = read_file("crates/pf_dsl/src/validator.rs");
         let validator_variants = parse_validation_error_variants(&validator_source);
-        let allowed_severities = ["error", "warn", "info"];
+        let allowed_severities = ["error", "warn", "info", "hint"];
 
         let mut rule_ids = BTreeSet::new();
         let mut catalog_variants = BTreeSet::new();
@@ -322,4 +326,72 @@ mod tests {
             );
         }
     }
+
+    // The grammar already rejects these shapes; the validator checks them for models
+    // built in code.
+    const PARSER_ENFORCED: [&str; 2] = ["PF-VAL-012", "PF-VAL-016"];
+
+    // `pf_dsl explain` shows these examples, so they have to behave as documented.
+    #[test]
+    fn test_metamodel_catalog_examples_exercise_their_rules() {
+        let mut failures = Vec::new();
+        for rule in rules() {
+            for (label, lines) in [
+                ("valid", &rule.examples.valid),
+                ("invalid", &rule.examples.invalid),
+            ] {
+                let files = example_files(lines);
+                let mut provider = MemoryFiles::new();
+                for (name, text) in &files {
+                    provider.insert(Path::new("/examples").join(name), text.as_str());
+                }
+                let Some((entry, _)) = files.first() else {
+                    failures.push(format!("{}: {label} example is empty", rule.rule_id));
+                    continue;
+                };
+                let problem =
+                    match resolve_with_files(&Path::new("/examples").join(entry), &provider) {
+                        Ok(problem) => problem,
+                        Err(_)
+                            if label == "invalid"
+                                && PARSER_ENFORCED.contains(&rule.rule_id.as_str()) =>
+                        {
+                            continue;
+                        }
+                        Err(error) => {
+                            failures.push(format!(
+                                "{}: {label} example does not resolve: {error:#}",
+                                rule.rule_id
+                            ));
+                            continue;
+                        }
+                    };
+                let mut levels = BTreeMap::new();
+                if OPT_IN_RULES.contains(&rule.rule_id.as_str()) {
+                    enable(&mut levels, &rule.rule_id);
+                }
+                let findings: Vec<String> = lint(&problem, &levels)
+                    .issues
+                    .iter()
+                    .map(|issue| format!("{} {}", issue.rule_id, issue.error))
+                    .collect();
+                let reported = findings
+                    .iter()
+                    .any(|finding| finding.starts_with(&rule.rule_id));
+                if label == "valid" && !findings.is_empty() {
+                    failures.push(format!(
+                        "{}: valid example reports {findings:?}",
+                        rule.rule_id
+                    ));
+                }
+                if label == "invalid" && !reported {
+                    failures.push(format!(
+                        "{}: invalid example reports {findings:?}",
+                        rule.rule_id
+                    ));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
 }
@@ -0,0 +1,487 @@
+use crate::ast::*;
+use crate::composition::COMPOSITION_DECISION_MARK;
+use crate::formal_closure::FORMAL_ARGUMENT_MARK;
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// One parsed file with only its own declarations. `edges[i]` is the import graph
+// edge of `problem.imports[i]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    // canonical path, as used by the import graph; the embedded path for `std/` files
+    pub path: PathBuf,
+    pub file: FileId,
+    pub problem: Problem,
+    pub edges: Vec<usize>,
+}
+
+// Every file of a model loaded once, with the imports between them. The merged
+// model that validation and the exporters work on is derived with `flatten`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleGraph {
+    // entry module first
+    pub modules: Vec<Module>,
+    pub imports: ImportGraph,
+    pub sources: SourceMap,
+}
+
+impl ModuleGraph {
+    pub fn entry(&self) -> &Module {
+        &self.modules[0]
+    }
+
+    pub fn module(&self, path: &Path) -> Option<&Module> {
+        self.modules.iter().find(|module| module.path == path)
+    }
+
+    // A module named by its path or a trailing part of it, such as `domains.pf`
+    // or `lib/domains.pf`.
+    pub fn find_module(&self, name: &Path) -> Result<&Module> {
+        let matches: Vec<&Module> = self
+            .modules
+            .iter()
+            .filter(|module| module.path == name || module.path.ends_with(name))
+            .collect();
+        match matches.as_slice() {
+            [module] => Ok(module),
+            [] => Err(anyhow!("no module of the model matches {:?}", name)),
+            _ => Err(anyhow!(
+                "{:?} matches several modules: {}",
+                name,
+                matches
+                    .iter()
+                    .map(|module| module.path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    // The entry module with its imports merged in, qualified and selected as they
+    // were declared. A file imported again under the same alias and selection
+    // (a diamond) is merged once per importer scope; imports that close a cycle or
+    // repeat an earlier import are not followed.
+    pub fn flatten(&self) -> Result<Problem> {
+        let mut loaded = HashSet::from([(0, ImportScope::default())]);
+        let mut problem = self.merged(0, &mut loaded)?;
+        problem.sources = self.sources.clone();
+        problem.import_graph = self.imports.clone();
+        Ok(problem)
+    }
+
+    fn merged(&self, index: usize, loaded: &mut HashSet<(usize, ImportScope)>) -> Result<Problem> {
+        let module = &self.modules[index];
+        // An unknown alias in the entry model is an undefined reference for the
+        // validator; in an imported file it could bind to an alias of the importer.
+        if index != 0 {
+            check_own_aliases(module)?;
+        }
+        let mut problem = module.problem.clone();
+        for (import, &edge) in module.problem.imports.iter().zip(&module.edges) {
+            if !self.imports.is_followed(edge) {
+                continue;
+            }
+            let Some(target) = self
+                .modules
+                .iter()
+                .position(|module| module.path == self.imports.edges[edge].to)
+            else {
+                continue;
+            };
+            let scope = ImportScope::of(import);
+            if !loaded.insert((target, scope.clone())) {
+                continue;
+            }
+
+            // Files merged under an alias or a selection are renamed or filtered
+            // on the way in, so they do not count as loaded for this importer.
+            let mut imported = if scope == ImportScope::default() {
+                self.merged(target, loaded)?
+            } else {
+                self.merged(
+                    target,
+                    &mut HashSet::from([(target, ImportScope::default())]),
+                )?
+            };
+            if let Some(alias) = &import.alias {
+                qualify_names(&mut imported, &alias.name);
+            }
+            if !import.items.is_empty() {
+                select_items(&mut imported, import, &self.modules[target].path)?;
+            }
+
+            // Name collisions are not checked here; the validator reports them.
+            problem.frames.extend(imported.frames);
+            problem.types.extend(imported.types);
+            problem.domains.extend(imported.domains);
+            problem.interfaces.extend(imported.interfaces);
+            problem.requirements.extend(imported.requirements);
+            problem.subproblems.extend(imported.subproblems);
+            problem.assertion_sets.extend(imported.assertion_sets);
+            problem
+                .correctness_arguments
+                .extend(imported.correctness_arguments);
+        }
+        Ok(problem)
+    }
+}
+
+// The declarations of a merged model that come from one file.
+pub fn declared_in(problem: &Problem, file: FileId) -> Problem {
+    let mut scoped = problem.clone();
+    scoped.frames.retain(|frame| frame.span.file == file);
+    scoped.types.retain(|data_type| data_type.span.file == file);
+    scoped.domains.retain(|domain| domain.span.file == file);
+    scoped
+        .interfaces
+        .retain(|interface| interface.span.file == file);
+    scoped
+        .requirements
+        .retain(|requirement| requirement.span.file == file);
+    scoped
+        .subproblems
+        .retain(|subproblem| subproblem.span.file == file);
+    scoped.assertion_sets.retain(|set| set.span.file == file);
+    scoped
+        .correctness_arguments
+        .retain(|argument| argument.span.file == file);
+    scoped
+}
+
+// A file is merged once per alias/selection it is imported under.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct ImportScope {
+    pub(crate) alias: Option<String>,
+    pub(crate) items: Vec<String>,
+}
+
+impl ImportScope {
+    pub(crate) fn of(import: &Import) -> Self {
+        let mut items: Vec<String> = import.items.iter().map(|item| item.name.clone()).collect();
+        items.sort();
+        Self {
+            alias: import.alias.as_ref().map(|alias| alias.name.clone()),
+            items,
+        }
+    }
+}
+
+// Renames every declaration of an aliased import to `Alias.Name` and rewrites the
+// references between them, so the module stays consistent after the merge.
+// References to names the module does not declare are left for the importer.
+fn qualify_names(problem: &mut Problem, alias: &str) {
+    fn names<'a>(names: impl Iterator<Item = &'a String>) -> HashSet<String> {
+        names.cloned().collect()
+    }
+    let qualified = |name: &str| format!("{alias}.{name}");
+    let requalify = |reference: &mut Reference, declared: &HashSet<String>| {
+        if declared.contains(&reference.name) {
+            reference.name = qualified(&reference.name);
+        }
+    };
+
+    let frames = names(problem.frames.iter().map(|frame| &frame.name));
+    let types = names(problem.types.iter().map(|data_type| &data_type.name));
+    let domains = names(problem.domains.iter().map(|domain| &domain.name));
+    let interfaces = names(problem.interfaces.iter().map(|interface| &interface.name));
+    let requirements = names(problem.requirements.iter().flat_map(|requirement| {
+        std::iter::once(&requirement.name).chain(requirement.id.as_ref().map(|id| &id.name))
+    }));
+    let sets = names(problem.assertion_sets.iter().map(|set| &set.name));
+    let arguments = names(
+        problem
+            .correctness_arguments
+            .iter()
+            .map(|argument| &argument.name),
+    );
+    // `[[Interface.Phenomenon]]` and `[[Domain.State]]` assertion tokens
+    let vocabulary_owners: HashSet<String> = interfaces.union(&domains).cloned().collect();
+
+    for frame in &mut problem.frames {
+        frame.name = qualified(&frame.name);
+    }
+    for data_type in &mut problem.types {
+        data_type.name = qualified(&data_type.name);
+        if let TypeDefinition::Record(fields) = &mut data_type.definition {
+            for field in fields {
+                requalify(&mut field.type_ref, &types);
+            }
+        }
+    }
+    for domain in &mut problem.domains {
+        domain.name = qualified(&domain.name);
+        for entity in domain
+            .schema
+            .iter_mut()
+            .flat_map(|schema| &mut schema.entities)
+        {
+            for field in &mut entity.fields {
+                requalify(&mut field.type_ref, &types);
+            }
+        }
+    }
+    for interface in &mut problem.interfaces {
+        interface.name = qualified(&interface.name);
+        for domain in &mut interface.connects {
+            requalify(domain, &domains);
+        }
+        for phenomenon in &mut interface.shared_phenomena {
+            requalify(&mut phenomenon.from, &domains);
+            requalify(&mut phenomenon.to, &domains);
+            requalify(&mut phenomenon.controlled_by, &domains);
+            for parameter in &mut phenomenon.parameters {
+                requalify(&mut parameter.type_ref, &types);
+            }
+        }
+    }
+    for requirement in &mut problem.requirements {
+        requirement.name = qualified(&requirement.name);
+        if let Some(id) = &mut requirement.id {
+            id.name = qualified(&id.name);
+        }
+        if let FrameType::Custom(frame) = &mut requirement.frame {
+            if frames.contains(frame.as_str()) {
+                *frame = qualified(frame);
+            }
+        }
+        for domain in [&mut requirement.constrains, &mut requirement.reference]
+            .into_iter()
+            .flatten()
+        {
+            requalify(domain, &domains);
+        }
+        for phenomenon in &mut requirement.phenomena {
+            if let Some((interface, _)) = phenomenon.name.rsplit_once('.') {
+                if interfaces.contains(interface) {
+                    phenomenon.name = qualified(&phenomenon.name);
+                }
+            }
+        }
+        for mark in &mut requirement.marks {
+            let declared = match mark.name.as_str() {
+                FORMAL_ARGUMENT_MARK => &arguments,
+                COMPOSITION_DECISION_MARK => &requirements,
+                _ => continue,
+            };
+            if let Some(value) = &mut mark.value {
+                *value = value
+                    .split(',')
+                    .map(|name| match name.trim() {
+                        trimmed if declared.contains(trimmed) => qualified(trimmed),
+                        trimmed => trimmed.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+            }
+        }
+    }
+    for subproblem in &mut problem.subproblems {
+        subproblem.name = qualified(&subproblem.name);
+        for domain in subproblem
+            .machine
+            .iter_mut()
+            .chain(subproblem.participants.iter_mut())
+        {
+            requalify(domain, &domains);
+        }
+        for requirement in &mut subproblem.requirements {
+            requalify(requirement, &requirements);
+        }
+    }
+    for set in &mut problem.assertion_sets {
+        set.name = qualified(&set.name);
+        for assertion in &mut set.assertions {
+            assertion.text = qualify_vocabulary(&assertion.text, alias, &vocabulary_owners);
+        }
+    }
+    for argument in &mut problem.correctness_arguments {
+        argument.name = qualified(&argument.name);
+        for set_ref in [
+            &mut argument.specification_ref,
+            &mut argument.world_ref,
+            &mut argument.requirement_ref,
+        ] {
+            requalify(set_ref, &sets);
+        }
+        argument.specification_set = argument.specification_ref.name.clone();
+        argument.world_set = argument.world_ref.name.clone();
+        argument.requirement_set = argument.requirement_ref.name.clone();
+    }
+}
+
+// Qualified references in a module must go through the aliases it imports itself.
+fn check_own_aliases(module: &Module) -> Result<()> {
+    let problem = &module.problem;
+    let aliases: HashSet<&str> = problem
+        .imports
+        .iter()
+        .filter_map(|import| import.alias.as_ref())
+        .map(|alias| alias.name.as_str())
+        .collect();
+
+    let mut references: Vec<&Reference> = Vec::new();
+    let typed_fields = problem
+        .types
+        .iter()
+        .flat_map(|data_type| match &data_type.definition {
+            TypeDefinition::Record(fields) => fields.as_slice(),
+            _ => &[],
+        })
+        .chain(
+            problem
+                .domains
+                .iter()
+                .flat_map(|domain| &domain.schema)
+                .flat_map(|schema| &schema.entities)
+                .flat_map(|entity| &entity.fields),
+        )
+        .chain(
+            problem
+                .interfaces
+                .iter()
+                .flat_map(|interface| &interface.shared_phenomena)
+                .flat_map(|phenomenon| &phenomenon.parameters),
+        );
+    references.extend(typed_fields.map(|field| &field.type_ref));
+    for interface in &problem.interfaces {
+        references.extend(&interface.connects);
+        for phenomenon in &interface.shared_phenomena {
+            references.extend([&phenomenon.from, &phenomenon.to, &phenomenon.controlled_by]);
+        }
+    }
+    for requirement in &problem.requirements {
+        references.extend(requirement.constrains.iter().chain(&requirement.reference));
+    }
+    for subproblem in &problem.subproblems {
+        references.extend(subproblem.machine.iter().chain(&subproblem.participants));
+    }
+    for argument in &problem.correctness_arguments {
+        references.extend([
+            &argument.specification_ref,
+            &argument.world_ref,
+            &argument.requirement_ref,
+        ]);
+    }
+
+    let frames = problem
+        .requirements
+        .iter()
+        .filter_map(|requirement| match &requirement.frame {
+            FrameType::Custom(frame) => Some(frame.as_str()),
+            _ => None,
+        });
+    let names = references
+        .into_iter()
+        .map(|reference| reference.name.as_str())
+        .chain(frames);
+    for name in names {
+        if let Some((alias, _)) = name.split_once('.') {
+            if !aliases.contains(alias) {
+                anyhow::bail!(
+                    "'{}' in {:?} uses alias '{}', which that file does not import",
+                    name,
+                    module.path,
+                    alias
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Prefixes the `[[...]]` tokens that start with a name the module declares.
+fn qualify_vocabulary(text: &str, alias: &str, owners: &HashSet<String>) -> String {
+    let mut qualified = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let (before, token) = rest.split_at(start + 2);
+        qualified.push_str(before);
+        let Some(end) = token.find("]]") else {
+            rest = token;
+            break;
+        };
+        let body = &token[..end];
+        let name = body.trim_start();
+        let owned = owners.iter().any(|owner| {
+            name.strip_prefix(owner.as_str())
+                .is_some_and(|after| after.starts_with('.'))
+        });
+        if owned {
+            qualified.push_str(&body[..body.len() - name.len()]);
+            qualified.push_str(alias);
+            qualified.push('.');
+            qualified.push_str(name);
+        } else {
+            qualified.push_str(body);
+        }
+        rest = &token[end..];
+    }
+    qualified.push_str(rest);
+    qualified
+}
+
+// Keeps only the declarations listed in `import { ... } from`. Names are matched
+// after qualification, so `import { Gate } from "lib.pf" as Lib` keeps `Lib.Gate`.
+fn select_items(problem: &mut Problem, import: &Import, source_path: &Path) -> Result<()> {
+    let qualified = |item: &Reference| match &import.alias {
+        Some(alias) => format!("{}.{}", alias.name, item.name),
+        None => item.name.clone(),
+    };
+    let selected: HashSet<String> = import.items.iter().map(qualified).collect();
+    let keep = |name: &str| selected.contains(name);
+
+    let mut declared: HashSet<&str> = HashSet::new();
+    declared.extend(problem.frames.iter().map(|frame| frame.name.as_str()));
+    declared.extend(
+        problem
+            .types
+            .iter()
+            .map(|data_type| data_type.name.as_str()),
+    );
+    declared.extend(problem.domains.iter().map(|domain| domain.name.as_str()));
+    declared.extend(
+        problem
+            .interfaces
+            .iter()
+            .map(|interface| interface.name.as_str()),
+    );
+    declared.extend(problem.requirements.iter().flat_map(|requirement| {
+        std::iter::once(requirement.name.as_str())
+            .chain(requirement.id.as_ref().map(|id| id.name.as_str()))
+    }));
+    declared.extend(
+        problem
+            .subproblems
+            .iter()
+            .map(|subproblem| subproblem.name.as_str()),
+    );
+    declared.extend(problem.assertion_sets.iter().map(|set| set.name.as_str()));
+    declared.extend(
+        problem
+            .correctness_arguments
+            .iter()
+            .map(|argument| argument.name.as_str()),
+    );
+    for item in &import.items {
+        if !declared.contains(qualified(item).as_str()) {
+            anyhow::bail!("'{}' is not declared in {:?}", item.name, source_path);
+        }
+    }
+
+    problem.frames.retain(|frame| keep(&frame.name));
+    problem.types.retain(|data_type| keep(&data_type.name));
+    problem.domains.retain(|domain| keep(&domain.name));
+    problem.interfaces.retain(|interface| keep(&interface.name));
+    problem.requirements.retain(|requirement| {
+        keep(&requirement.name) || requirement.id.as_ref().is_some_and(|id| keep(&id.name))
+    });
+    problem
+        .subproblems
+        .retain(|subproblem| keep(&subproblem.name));
+    problem.assertion_sets.retain(|set| keep(&set.name));
+    problem
+        .correctness_arguments
+        .retain(|argument| keep(&argument.name));
+    Ok(())
+}
@@ -99,7 +99,11 @@ mod tests {
     use crate::ast::*;
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn reference(name: &str) -> Reference {
@@ -115,6 +119,8 @@ mod tests {
             name: "P".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![],
             interfaces: vec![],
             requirements: vec![],
@@ -122,6 +128,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S_control".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "controller updates target".to_string(),
@@ -133,6 +140,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W_base".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "heater responds".to_string(),
@@ -144,6 +152,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R_goal".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "target achieved".to_string(),
@@ -165,6 +174,8 @@ mod tests {
                 span: span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let markdown = generate_obligations_markdown(&problem);
@@ -13,11 +13,27 @@ use std::collections::HashSet;
 #[grammar = "problem_frames.pest"]
 pub struct PFParser;
 
+const TOP_LEVEL_KEYWORDS: &[&str] = &[
+    "import",
+    "problem:",
+    "frame",
+    "type",
+    "domain",
+    "interface",
+    "requirement",
+    "subproblem",
+    "worldProperties",
+    "specification",
+    "requirementAssertions",
+    "correctnessArgument",
+];
+
 fn pair_to_span(pair: &Pair<Rule>) -> Span {
     let span = pair.as_span();
     Span {
         start: span.start(),
         end: span.end(),
+        file: FileId::default(),
     }
 }
 
@@ -41,10 +57,12 @@ fn span_from_pest_error(err: &pest::error::Error<Rule>, input: &str) -> Span {
         InputLocation::Pos(pos) => Span {
             start: pos.min(input.len()),
             end: (pos + 1).min(input.len()),
+            file: FileId::default(),
         },
         InputLocation::Span((start, end)) => Span {
             start: start.min(input.len()),
             end: end.min(input.len()),
+            file: FileId::default(),
         },
     }
 }
@@ -59,6 +77,182 @@ fn next_inner<'a>(
         .ok_or_else(|| ParseDiagnostic::new(span, format!("missing {expected}")))
 }
 
+// Decoded value of a `string_literal`; identifiers accepted in the same position
+// are returned as written.
+fn string_value(pair: &Pair<'_, Rule>) -> std::result::Result<String, ParseDiagnostic> {
+    let raw = pair.as_str();
+    if pair.as_rule() != Rule::string_literal {
+        return Ok(raw.to_string());
+    }
+    let span = pair_to_span(pair);
+    match raw
+        .strip_prefix("\"\"\"")
+        .and_then(|rest| rest.strip_suffix("\"\"\""))
+    {
+        Some(body) => unescape(&strip_block_indent(body), span),
+        None => unescape(&raw[1..raw.len() - 1], span),
+    }
+}
+
+// `Interface.Name` with quoted interface qualifiers decoded, so the reference
+// reads the same as the interface's declared name.
+fn phenomenon_ref_name(pair: Pair<'_, Rule>) -> std::result::Result<String, ParseDiagnostic> {
+    let mut segments = Vec::new();
+    for segment in pair.into_inner() {
+        match segment.clone().into_inner().next() {
+            Some(quoted) if quoted.as_rule() == Rule::quoted_string => {
+                let raw = quoted.as_str();
+                segments.push(unescape(&raw[1..raw.len() - 1], pair_to_span(&quoted))?);
+            }
+            _ => segments.push(segment.as_str().to_string()),
+        }
+    }
+    Ok(segments.join("."))
+}
+
+// Triple-quoted strings drop the line break after the opening quotes and the
+// whitespace-only line before the closing ones, then remove the indentation
+// shared by the remaining lines and the closing quotes.
+fn strip_block_indent(body: &str) -> String {
+    let body = body
+        .strip_prefix("\r\n")
+        .or_else(|| body.strip_prefix('\n'))
+        .unwrap_or(body);
+    let mut lines: Vec<&str> = body
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let closing_indent = match lines.last() {
+        Some(last) if lines.len() > 1 && last.trim().is_empty() => {
+            let indent = indentation(last);
+            lines.pop();
+            Some(indent)
+        }
+        _ => None,
+    };
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .chain(closing_indent)
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[indent..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+fn unescape(body: &str, span: Span) -> std::result::Result<String, ParseDiagnostic> {
+    let mut value = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+        let decoded = match chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => unicode_escape(&mut chars).ok_or_else(|| {
+                ParseDiagnostic::new(
+                    span,
+                    "invalid unicode escape in string literal; expected \\u{XXXX}",
+                )
+            })?,
+            Some(other) => {
+                return Err(ParseDiagnostic::new(
+                    span,
+                    format!("unknown escape sequence '\\{other}' in string literal"),
+                ))
+            }
+            None => {
+                return Err(ParseDiagnostic::new(
+                    span,
+                    "unterminated escape sequence in string literal",
+                ))
+            }
+        };
+        value.push(decoded);
+    }
+    Ok(value)
+}
+
+fn unicode_escape(chars: &mut std::str::Chars<'_>) -> Option<char> {
+    if chars.next() != Some('{') {
+        return None;
+    }
+    let mut digits = String::new();
+    for ch in chars.by_ref() {
+        if ch == '}' {
+            if !(1..=6).contains(&digits.len()) {
+                return None;
+            }
+            return u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32);
+        }
+        if !ch.is_ascii_hexdigit() {
+            return None;
+        }
+        digits.push(ch);
+    }
+    None
+}
+
+fn parse_import(pair: Pair<'_, Rule>, span: Span) -> std::result::Result<Import, ParseDiagnostic> {
+    let mut import = Import {
+        path: String::new(),
+        alias: None,
+        items: Vec::new(),
+        span,
+    };
+    for part in pair.into_inner() {
+        match part.as_rule() {
+            Rule::import_items => {
+                for item in part.into_inner() {
+                    let name = string_value(&item)?;
+                    let item_span = pair_to_span(&item);
+                    if import.items.iter().any(|existing| existing.name == name) {
+                        return Err(ParseDiagnostic::new(
+                            item_span,
+                            format!("import lists '{name}' more than once"),
+                        ));
+                    }
+                    import.items.push(Reference {
+                        name,
+                        span: item_span,
+                    });
+                }
+            }
+            Rule::string_literal => import.path = string_value(&part)?,
+            Rule::import_alias => {
+                let alias = next_inner(part, "import alias", span)?;
+                import.alias = Some(Reference {
+                    name: alias.as_str().to_string(),
+                    span: pair_to_span(&alias),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(import)
+}
+
 fn parse_assertion_stmt(
     assertion_pair: Pair<'_, Rule>,
 ) -> std::result::Result<Assertion, ParseDiagnostic> {
@@ -72,7 +266,7 @@ fn parse_assertion_stmt(
         .map(|pair| pair.as_str().trim_start_matches('@').to_string());
 
     Ok(Assertion {
-        text: text_pair.as_str().trim_matches('"').to_string(),
+        text: string_value(&text_pair)?,
         language,
         span,
     })
@@ -81,6 +275,7 @@ fn parse_assertion_stmt(
 fn parse_assertion_set(
     pair: Pair<'_, Rule>,
     scope: AssertionScope,
+    doc: Option<String>,
 ) -> std::result::Result<AssertionSet, ParseDiagnostic> {
     let span = pair_to_span(&pair);
     let mut inner = pair.into_inner();
@@ -96,6 +291,7 @@ fn parse_assertion_set(
 
     Ok(AssertionSet {
         name: name_pair.as_str().to_string(),
+        doc,
         scope,
         assertions,
         span,
@@ -103,19 +299,282 @@ fn parse_assertion_set(
     })
 }
 
+fn doc_line(pair: &Pair<'_, Rule>) -> String {
+    let text = pair.as_str().trim_start_matches("///").trim_end();
+    text.strip_prefix(' ').unwrap_or(text).to_string()
+}
+
+// Consecutive `///` lines collected so far, handed to the next documented node.
+fn take_doc(pending: &mut Vec<String>) -> Option<String> {
+    if pending.is_empty() {
+        None
+    } else {
+        Some(std::mem::take(pending).join("\n"))
+    }
+}
+
+// Parses `name: Type` pairs, rejecting a name that appears twice in the same list.
+fn parse_typed_fields(
+    pair: Pair<'_, Rule>,
+    owner: &str,
+    what: &str,
+) -> std::result::Result<Vec<TypedField>, ParseDiagnostic> {
+    let mut fields = Vec::new();
+    let mut seen = HashSet::new();
+    for field_pair in pair.into_inner() {
+        // Schema fields carry a `field` keyword; their span covers the whole line.
+        let span = pair_to_span(&field_pair);
+        let field_pair = match field_pair.as_rule() {
+            Rule::typed_field => field_pair,
+            Rule::schema_field => match field_pair.into_inner().next() {
+                Some(inner) => inner,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let mut inner = field_pair.into_inner();
+        let name = inner
+            .next()
+            .ok_or_else(|| ParseDiagnostic::new(span, format!("missing {what} name")))?
+            .as_str()
+            .to_string();
+        let type_pair = inner
+            .next()
+            .ok_or_else(|| ParseDiagnostic::new(span, format!("missing {what} type")))?;
+        if !seen.insert(name.clone()) {
+            return Err(ParseDiagnostic::new(
+                span,
+                format!("{owner} has duplicate {what} '{name}'"),
+            ));
+        }
+        fields.push(TypedField {
+            name,
+            type_ref: Reference {
+                name: type_pair.as_str().to_string(),
+                span: pair_to_span(&type_pair),
+            },
+            span,
+        });
+    }
+    Ok(fields)
+}
+
+fn parse_number(pair: &Pair<'_, Rule>) -> std::result::Result<f64, ParseDiagnostic> {
+    pair.as_str().parse().map_err(|_| {
+        ParseDiagnostic::new(
+            pair_to_span(pair),
+            format!("invalid number '{}'", pair.as_str()),
+        )
+    })
+}
+
+fn parse_type_decl(
+    pair: Pair<'_, Rule>,
+    doc: Option<String>,
+) -> std::result::Result<DataType, ParseDiagnostic> {
+    let span = pair_to_span(&pair);
+    let mut inner = pair.into_inner();
+    let name = inner
+        .next()
+        .ok_or_else(|| ParseDiagnostic::new(span, "missing type name"))?
+        .as_str()
+        .to_string();
+    let definition_pair = inner
+        .next()
+        .ok_or_else(|| ParseDiagnostic::new(span, "missing type definition"))?;
+    let definition_span = pair_to_span(&definition_pair);
+
+    let definition = match definition_pair.as_rule() {
+        Rule::enum_type => {
+            let mut literals: Vec<Reference> = Vec::new();
+            for literal_pair in definition_pair.into_inner() {
+                let literal = Reference {
+                    name: literal_pair.as_str().to_string(),
+                    span: pair_to_span(&literal_pair),
+                };
+                if literals.iter().any(|seen| seen.name == literal.name) {
+                    return Err(ParseDiagnostic::new(
+                        literal.span,
+                        format!("type '{}' has duplicate literal '{}'", name, literal.name),
+                    ));
+                }
+                literals.push(literal);
+            }
+            TypeDefinition::Enum(literals)
+        }
+        Rule::record_type => {
+            let owner = format!("type '{name}'");
+            TypeDefinition::Record(parse_typed_fields(definition_pair, &owner, "field")?)
+        }
+        Rule::range_type => {
+            let mut parts = definition_pair.into_inner();
+            let min_pair = parts
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(definition_span, "missing range minimum"))?;
+            let max_pair = parts
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(definition_span, "missing range maximum"))?;
+            let unit = parts
+                .next()
+                .and_then(|unit_pair| unit_pair.into_inner().next())
+                .map(|literal| string_value(&literal))
+                .transpose()?;
+            TypeDefinition::Range {
+                min: parse_number(&min_pair)?,
+                max: parse_number(&max_pair)?,
+                unit,
+            }
+        }
+        _ => {
+            let literal = next_inner(definition_pair, "unit symbol", definition_span)?;
+            TypeDefinition::Unit(string_value(&literal)?)
+        }
+    };
+
+    Ok(DataType {
+        name,
+        doc,
+        definition,
+        span,
+        source_path: None,
+    })
+}
+
+fn parse_frame_slot(pair: Pair<'_, Rule>) -> FrameSlot {
+    let span = pair_to_span(&pair);
+    let mut slot = FrameSlot {
+        kinds: vec![],
+        roles: vec![],
+        span,
+    };
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::domain_kind => slot.kinds.push(parse_domain_kind(inner.as_str())),
+            Rule::domain_role => slot.roles.push(parse_domain_role(inner.as_str())),
+            _ => {}
+        }
+    }
+    slot
+}
+
+fn parse_frame_participant(pair: &Pair<'_, Rule>) -> FrameParticipant {
+    match pair.as_str() {
+        "constrains" => FrameParticipant::Constrains,
+        "reference" => FrameParticipant::Reference,
+        _ => FrameParticipant::Machine,
+    }
+}
+
+fn parse_frame_decl(
+    pair: Pair<'_, Rule>,
+    doc: Option<String>,
+) -> std::result::Result<Frame, ParseDiagnostic> {
+    let span = pair_to_span(&pair);
+    let mut inner = pair.into_inner();
+    let name = inner
+        .next()
+        .ok_or_else(|| ParseDiagnostic::new(span, "missing frame name"))?
+        .as_str()
+        .to_string();
+    let body_pair = inner
+        .next()
+        .ok_or_else(|| ParseDiagnostic::new(span, "missing frame body"))?;
+
+    let mut frame = Frame {
+        name,
+        doc,
+        constrains: None,
+        reference: None,
+        connects: vec![],
+        flows: vec![],
+        span,
+        source_path: None,
+    };
+    let mut seen_fields = HashSet::new();
+
+    for field in body_pair.into_inner() {
+        let field_span = pair_to_span(&field);
+        let field_seen = match field.as_rule() {
+            Rule::frame_constrains => "constrains",
+            Rule::frame_reference => "reference",
+            Rule::frame_connects => "connects",
+            _ => "phenomenon",
+        };
+        if field_seen != "phenomenon" && !seen_fields.insert(field_seen) {
+            return Err(ParseDiagnostic::new(
+                field_span,
+                format!(
+                    "frame '{}' has duplicate field '{}'",
+                    frame.name, field_seen
+                ),
+            ));
+        }
+
+        match field.as_rule() {
+            Rule::frame_constrains => {
+                let slot_pair = next_inner(field, "constrains slot", field_span)?;
+                frame.constrains = Some(parse_frame_slot(slot_pair));
+            }
+            Rule::frame_reference => {
+                let slot_pair = next_inner(field, "reference slot", field_span)?;
+                frame.reference = Some(parse_frame_slot(slot_pair));
+            }
+            Rule::frame_connects => {
+                frame.connects = field
+                    .into_inner()
+                    .map(|participant| parse_frame_participant(&participant))
+                    .collect();
+            }
+            Rule::frame_flow => {
+                let mut parts = field.into_inner();
+                let types_pair = parts
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(field_span, "missing phenomenon type"))?;
+                let types = types_pair
+                    .into_inner()
+                    .map(|type_pair| {
+                        parse_phenomenon_type(type_pair.as_str()).ok_or_else(|| {
+                            ParseDiagnostic::new(
+                                pair_to_span(&type_pair),
+                                format!("invalid phenomenon type '{}'", type_pair.as_str()),
+                            )
+                        })
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                let from = parts
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(field_span, "missing flow source"))?;
+                let to = parts
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(field_span, "missing flow target"))?;
+                frame.flows.push(FrameFlow {
+                    types,
+                    from: parse_frame_participant(&from),
+                    to: parse_frame_participant(&to),
+                    controlled_by: parts.next().map(|pair| parse_frame_participant(&pair)),
+                    span: field_span,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(frame)
+}
+
 fn parse_mark_decl(mark_pair: Pair<'_, Rule>) -> std::result::Result<Mark, ParseDiagnostic> {
     let span = pair_to_span(&mark_pair);
     let mut inner = mark_pair.into_inner();
     let name_pair = inner
         .next()
         .ok_or_else(|| ParseDiagnostic::new(span, "missing mark name"))?;
-    let value = inner.next().map(|value_pair| {
-        value_pair
-            .into_inner()
-            .next()
-            .map(|token| token.as_str().trim_matches('"').to_string())
-            .unwrap_or_default()
-    });
+    let value = match inner
+        .next()
+        .and_then(|value_pair| value_pair.into_inner().next())
+    {
+        Some(token) => Some(string_value(&token)?),
+        None => None,
+    };
 
     Ok(Mark {
         name: name_pair.as_str().to_string(),
@@ -137,6 +596,114 @@ fn parse_marks_block(
     Ok(marks)
 }
 
+fn identifier_references(pair: Pair<'_, Rule>) -> Vec<Reference> {
+    pair.into_inner()
+        .filter(|inner| inner.as_rule() == Rule::identifier)
+        .map(|inner| Reference {
+            name: inner.as_str().to_string(),
+            span: pair_to_span(&inner),
+        })
+        .collect()
+}
+
+fn parse_behavior_block(
+    pair: Pair<'_, Rule>,
+    domain: &str,
+) -> std::result::Result<Behavior, ParseDiagnostic> {
+    let span = pair_to_span(&pair);
+    let mut states: Vec<Reference> = Vec::new();
+    let mut transitions = Vec::new();
+    for item in pair.into_inner() {
+        match item.as_rule() {
+            Rule::state_decl => {
+                for state in identifier_references(item) {
+                    if states.iter().any(|existing| existing.name == state.name) {
+                        return Err(ParseDiagnostic::new(
+                            state.span,
+                            format!("domain '{}' has duplicate state '{}'", domain, state.name),
+                        ));
+                    }
+                    states.push(state);
+                }
+            }
+            Rule::transition => {
+                let transition_span = pair_to_span(&item);
+                let mut inner = item.into_inner();
+                let mut next_reference = |what: &str| {
+                    inner
+                        .next()
+                        .map(|part| Reference {
+                            name: part.as_str().to_string(),
+                            span: pair_to_span(&part),
+                        })
+                        .ok_or_else(|| {
+                            ParseDiagnostic::new(
+                                transition_span,
+                                format!("missing transition {what}"),
+                            )
+                        })
+                };
+                let trigger = next_reference("trigger")?;
+                let from = next_reference("source state")?;
+                let to = next_reference("target state")?;
+                let emits = inner.next().map(identifier_references).unwrap_or_default();
+                transitions.push(Transition {
+                    trigger,
+                    from,
+                    to,
+                    emits,
+                    span: transition_span,
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(Behavior {
+        states,
+        transitions,
+        span,
+    })
+}
+
+fn parse_schema_block(
+    pair: Pair<'_, Rule>,
+    domain: &str,
+) -> std::result::Result<Schema, ParseDiagnostic> {
+    let span = pair_to_span(&pair);
+    let mut entities: Vec<SchemaEntity> = Vec::new();
+    for entity_pair in pair.into_inner() {
+        if entity_pair.as_rule() != Rule::schema_entity {
+            continue;
+        }
+        let entity_span = pair_to_span(&entity_pair);
+        let mut inner = entity_pair.clone().into_inner();
+        let kind = match inner.next().map(|kind| kind.as_str()) {
+            Some("value") => SchemaEntityKind::Value,
+            _ => SchemaEntityKind::Entity,
+        };
+        let name = inner
+            .next()
+            .ok_or_else(|| ParseDiagnostic::new(entity_span, "missing schema entity name"))?
+            .as_str()
+            .to_string();
+        if entities.iter().any(|entity| entity.name == name) {
+            return Err(ParseDiagnostic::new(
+                entity_span,
+                format!("domain '{}' has duplicate schema entity '{}'", domain, name),
+            ));
+        }
+        let owner = format!("schema entity '{name}'");
+        let fields = parse_typed_fields(entity_pair, &owner, "field")?;
+        entities.push(SchemaEntity {
+            name,
+            kind,
+            fields,
+            span: entity_span,
+        });
+    }
+    Ok(Schema { entities, span })
+}
+
 pub fn parse_error_diagnostic(input: &str) -> Option<(Span, String)> {
     if input.trim().is_empty() {
         return None;
@@ -155,6 +722,51 @@ pub fn parse_module(input: &str) -> Result<Problem> {
     parse_internal(input, false).map_err(|err| anyhow!("{}", err.message))
 }
 
+// Error-tolerant counterpart of `parse`: broken declarations are skipped up to the
+// next top-level keyword and reported, everything else is kept in the returned model.
+pub fn parse_recovering(input: &str) -> (Problem, Vec<(Span, String)>) {
+    parse_recovering_internal(input, true)
+}
+
+pub fn parse_module_recovering(input: &str) -> (Problem, Vec<(Span, String)>) {
+    parse_recovering_internal(input, false)
+}
+
+fn empty_problem(span: Span) -> Problem {
+    Problem {
+        name: String::new(),
+        span,
+        imports: vec![],
+        frames: vec![],
+        types: vec![],
+        domains: vec![],
+        interfaces: vec![],
+        requirements: vec![],
+        subproblems: vec![],
+        assertion_sets: vec![],
+        correctness_arguments: vec![],
+        sources: SourceMap::default(),
+        import_graph: ImportGraph::default(),
+    }
+}
+
+fn missing_problem_decl_diagnostic(input: &str) -> ParseDiagnostic {
+    let first_non_ws = input
+        .char_indices()
+        .find(|(_, ch)| !ch.is_whitespace())
+        .map(|(idx, _)| idx)
+        .unwrap_or(0);
+    let end = input.len().min(first_non_ws.saturating_add(1));
+    ParseDiagnostic::new(
+        Span {
+            start: first_non_ws,
+            end,
+            file: FileId::default(),
+        },
+        "missing required top-level 'problem:' declaration",
+    )
+}
+
 fn parse_internal(
     input: &str,
     require_problem_decl: bool,
@@ -163,466 +775,778 @@ fn parse_internal(
         .map_err(|err| ParseDiagnostic::new(span_from_pest_error(&err, input), err.to_string()))?;
     let program_pair = pairs.next().ok_or_else(|| {
         ParseDiagnostic::new(
-            Span { start: 0, end: 0 },
+            Span {
+                start: 0,
+                end: 0,
+                file: FileId::default(),
+            },
             "program did not produce a parse tree",
         )
     })?;
-    let problem_span = pair_to_span(&program_pair);
 
-    let mut problem = Problem {
-        name: String::new(),
-        span: problem_span,
-        imports: vec![],
-        domains: vec![],
-        interfaces: vec![],
-        requirements: vec![],
-        subproblems: vec![],
-        assertion_sets: vec![],
-        correctness_arguments: vec![],
-    };
+    let mut problem = empty_problem(pair_to_span(&program_pair));
     let mut has_problem_decl = false;
+    let mut docs = Vec::new();
 
     for pair in program_pair.into_inner() {
-        let span = pair_to_span(&pair);
-        match pair.as_rule() {
-            Rule::import_decl => {
-                let path_literal = next_inner(pair, "import path literal", span)?.as_str();
-                problem
-                    .imports
-                    .push(path_literal.trim_matches('"').to_string());
+        parse_declaration(pair, &mut problem, &mut has_problem_decl, &mut docs)?;
+    }
+
+    if require_problem_decl && !has_problem_decl {
+        return Err(missing_problem_decl_diagnostic(input));
+    }
+
+    Ok(problem)
+}
+
+fn parse_recovering_internal(
+    input: &str,
+    require_problem_decl: bool,
+) -> (Problem, Vec<(Span, String)>) {
+    let mut problem = empty_problem(Span {
+        start: 0,
+        end: input.len(),
+        file: FileId::default(),
+    });
+    let mut has_problem_decl = false;
+    let mut docs = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    match PFParser::parse(Rule::program, input) {
+        Ok(pairs) => {
+            for program_pair in pairs {
+                problem.span = pair_to_span(&program_pair);
+                for pair in program_pair.into_inner() {
+                    if let Err(err) =
+                        parse_declaration(pair, &mut problem, &mut has_problem_decl, &mut docs)
+                    {
+                        diagnostics.push((err.span, err.message));
+                    }
+                }
             }
-            Rule::problem_decl => {
-                if has_problem_decl {
-                    return Err(ParseDiagnostic::new(
-                        span,
-                        "multiple problem declarations are not allowed",
-                    ));
+        }
+        Err(_) => {
+            for (start, end) in declaration_chunks(input) {
+                // Blank out everything outside the chunk so pest spans stay absolute.
+                let masked = mask_outside(input, start, end);
+                match PFParser::parse(Rule::declaration_chunk, &masked) {
+                    Ok(pairs) => {
+                        for chunk_pair in pairs {
+                            for pair in chunk_pair.into_inner() {
+                                if let Err(err) = parse_declaration(
+                                    pair,
+                                    &mut problem,
+                                    &mut has_problem_decl,
+                                    &mut docs,
+                                ) {
+                                    diagnostics.push((err.span, err.message));
+                                }
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        let span =
+                            clamp_to_chunk(span_from_pest_error(&err, input), input, start, end);
+                        diagnostics.push((span, chunk_error_message(err, input, span)));
+                    }
                 }
-                let name_pair = next_inner(pair, "problem name", span)?;
-                problem.name = name_pair.as_str().trim().to_string();
-                has_problem_decl = true;
             }
-            Rule::domain_decl => {
-                let mut inner = pair.into_inner();
-                let name = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing domain name"))?
-                    .as_str()
-                    .to_string();
-                let kind_pair = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing domain kind"))?;
-                let role_pair = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing domain role"))?;
-                let marks = match inner.next() {
-                    Some(marks_pair) => parse_marks_block(marks_pair)?,
-                    None => vec![],
+        }
+    }
+
+    if require_problem_decl && !has_problem_decl {
+        let err = missing_problem_decl_diagnostic(input);
+        diagnostics.push((err.span, err.message));
+    }
+
+    (problem, diagnostics)
+}
+
+// Offset just past the string literal opening at `start`, honouring escapes and
+// triple-quoted blocks the same way the grammar does.
+pub(crate) fn string_end(bytes: &[u8], start: usize) -> usize {
+    let delimiter: &[u8] = if bytes[start..].starts_with(b"\"\"\"") {
+        b"\"\"\""
+    } else {
+        b"\""
+    };
+    let mut index = start + delimiter.len();
+    while index < bytes.len() {
+        if bytes[index] == b'\\' {
+            index += 2;
+        } else if bytes[index..].starts_with(delimiter) {
+            return index + delimiter.len();
+        } else {
+            index += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+// Splits `input` into byte ranges that each start at a top-level keyword. A keyword
+// opens a new chunk when it appears outside any block, or as the first token on its
+// line, so an unclosed `{` does not swallow the declarations that follow it.
+fn declaration_chunks(input: &str) -> Vec<(usize, usize)> {
+    let bytes = input.as_bytes();
+    let mut starts = Vec::new();
+    let mut depth = 0_usize;
+    let mut at_line_start = true;
+    let mut has_leading_content = false;
+    let mut doc_start = None;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+
+        if byte == b'\n' {
+            at_line_start = true;
+            index += 1;
+            continue;
+        }
+        if byte.is_ascii_whitespace() {
+            index += 1;
+            continue;
+        }
+        if bytes[index..].starts_with(b"//") {
+            // Doc comments travel with the declaration that follows them.
+            let is_doc = bytes[index..].starts_with(b"///") && bytes.get(index + 3) != Some(&b'/');
+            if is_doc && doc_start.is_none() {
+                doc_start = Some(index);
+            }
+            while index < bytes.len() && bytes[index] != b'\n' {
+                index += 1;
+            }
+            continue;
+        }
+
+        let word_boundary = index == 0 || !is_identifier_byte(bytes[index - 1]);
+        let keyword = TOP_LEVEL_KEYWORDS.iter().find(|keyword| {
+            let end = index + keyword.len();
+            bytes[index..].starts_with(keyword.as_bytes())
+                && (keyword.ends_with(':')
+                    || end >= bytes.len()
+                    // `frame:` inside a requirement is a field, not a `frame` declaration
+                    || !(is_identifier_byte(bytes[end]) || bytes[end] == b':'))
+        });
+        if let Some(keyword) = keyword.filter(|_| word_boundary && (depth == 0 || at_line_start)) {
+            starts.push(doc_start.take().unwrap_or(index));
+            depth = 0;
+            at_line_start = false;
+            index += keyword.len();
+            continue;
+        }
+
+        doc_start = None;
+        if starts.is_empty() {
+            has_leading_content = true;
+        }
+        match byte {
+            b'"' => {
+                let end = string_end(bytes, index);
+                // An unterminated `"` while typing must not swallow the
+                // declarations after it; only `"""` blocks run over lines.
+                index = match input[index..end].find('\n') {
+                    Some(offset) if !bytes[index..].starts_with(b"\"\"\"") => index + offset,
+                    _ => end,
                 };
+                at_line_start = false;
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        at_line_start = false;
+        index += 1;
+    }
+
+    if has_leading_content || starts.is_empty() {
+        starts.insert(0, 0);
+    }
 
-                problem.domains.push(Domain {
-                    name,
-                    kind: parse_domain_kind(kind_pair.as_str()),
-                    role: parse_domain_role(role_pair.as_str()),
-                    marks,
+    let mut chunks = Vec::with_capacity(starts.len());
+    for (position, start) in starts.iter().enumerate() {
+        let end = starts.get(position + 1).copied().unwrap_or(input.len());
+        chunks.push((*start, end));
+    }
+    chunks
+}
+
+// Renders a chunk's parse error at `span` in the real source, so its line, column
+// and excerpt match the span rather than the blanked-out chunk input.
+fn chunk_error_message(err: pest::error::Error<Rule>, input: &str, span: Span) -> String {
+    match pest::Position::new(input, span.start) {
+        Some(position) => pest::error::Error::new_from_pos(err.variant, position).to_string(),
+        None => err.variant.message().into_owned(),
+    }
+}
+
+// Errors at the end of a truncated chunk would otherwise point past the blanked-out
+// tail of the input, so pin them to the last token of the chunk instead.
+fn clamp_to_chunk(span: Span, input: &str, start: usize, end: usize) -> Span {
+    let content_end = start + input[start..end].trim_end().len();
+    if span.start < content_end {
+        return Span {
+            start: span.start,
+            end: span.end.min(content_end).max(span.start),
+            file: span.file,
+        };
+    }
+    let last_char_start = input[start..content_end]
+        .char_indices()
+        .last()
+        .map(|(offset, _)| start + offset)
+        .unwrap_or(start);
+    Span {
+        start: last_char_start,
+        end: content_end,
+        file: span.file,
+    }
+}
+
+fn mask_outside(input: &str, start: usize, end: usize) -> String {
+    let mut masked = String::with_capacity(input.len());
+    for (index, ch) in input.char_indices() {
+        if (start..end).contains(&index) || ch == '\n' {
+            masked.push(ch);
+        } else {
+            masked.extend(std::iter::repeat_n(' ', ch.len_utf8()));
+        }
+    }
+    masked
+}
+
+fn parse_declaration(
+    pair: Pair<'_, Rule>,
+    problem: &mut Problem,
+    has_problem_decl: &mut bool,
+    docs: &mut Vec<String>,
+) -> std::result::Result<(), ParseDiagnostic> {
+    if pair.as_rule() == Rule::doc_comment {
+        docs.push(doc_line(&pair));
+        return Ok(());
+    }
+
+    let span = pair_to_span(&pair);
+    let doc = take_doc(docs);
+    match pair.as_rule() {
+        Rule::import_decl => {
+            problem.imports.push(parse_import(pair, span)?);
+        }
+        Rule::problem_decl => {
+            if *has_problem_decl {
+                return Err(ParseDiagnostic::new(
                     span,
-                    source_path: None,
-                });
+                    "multiple problem declarations are not allowed",
+                ));
             }
-            Rule::interface_decl => {
-                let mut inner = pair.into_inner();
-                let name = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing interface name"))?
-                    .as_str()
-                    .trim_matches('"')
-                    .to_string();
-                let connects_pair = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing interface connects list"))?;
-                let shared_pair = inner
+            let name_pair = next_inner(pair, "problem name", span)?;
+            problem.name = name_pair.as_str().trim().to_string();
+            *has_problem_decl = true;
+        }
+        Rule::frame_decl => {
+            problem.frames.push(parse_frame_decl(pair, doc)?);
+        }
+        Rule::type_decl => {
+            problem.types.push(parse_type_decl(pair, doc)?);
+        }
+        Rule::domain_decl => {
+            let mut inner = pair.into_inner();
+            let name = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing domain name"))?
+                .as_str()
+                .to_string();
+            let kind_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing domain kind"))?;
+            let role_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing domain role"))?;
+            let mut marks = vec![];
+            let mut behavior = None;
+            let mut schema = None;
+            for block in inner {
+                match block.as_rule() {
+                    Rule::marks_block => marks = parse_marks_block(block)?,
+                    Rule::behavior_block => behavior = Some(parse_behavior_block(block, &name)?),
+                    Rule::schema_block => schema = Some(parse_schema_block(block, &name)?),
+                    _ => {}
+                }
+            }
+
+            problem.domains.push(Domain {
+                name,
+                doc,
+                kind: parse_domain_kind(kind_pair.as_str()),
+                role: parse_domain_role(role_pair.as_str()),
+                marks,
+                behavior,
+                schema,
+                span,
+                source_path: None,
+            });
+        }
+        Rule::interface_decl => {
+            let mut inner = pair.into_inner();
+            let name = string_value(
+                &inner
                     .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing shared phenomena block"))?;
-
-                let mut connects = Vec::new();
-                for domain_ident in connects_pair.into_inner() {
-                    if domain_ident.as_rule() == Rule::identifier {
-                        connects.push(Reference {
-                            name: domain_ident.as_str().to_string(),
-                            span: pair_to_span(&domain_ident),
-                        });
-                    }
+                    .ok_or_else(|| ParseDiagnostic::new(span, "missing interface name"))?,
+            )?;
+            let connects_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing interface connects list"))?;
+            let shared_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing shared phenomena block"))?;
+
+            let mut connects = Vec::new();
+            for domain_ident in connects_pair.into_inner() {
+                if domain_ident.as_rule() == Rule::qualified_identifier {
+                    connects.push(Reference {
+                        name: domain_ident.as_str().to_string(),
+                        span: pair_to_span(&domain_ident),
+                    });
                 }
+            }
 
-                let mut phenomena = Vec::new();
-                for phen_pair in shared_pair.into_inner() {
-                    if phen_pair.as_rule() != Rule::phenomenon {
-                        continue;
-                    }
+            let mut phenomena = Vec::new();
+            let mut phenomenon_docs = Vec::new();
+            for phen_pair in shared_pair.into_inner() {
+                if phen_pair.as_rule() == Rule::doc_comment {
+                    phenomenon_docs.push(doc_line(&phen_pair));
+                    continue;
+                }
+                if phen_pair.as_rule() != Rule::phenomenon {
+                    continue;
+                }
 
-                    let p_span = pair_to_span(&phen_pair);
-                    let mut p_inner = phen_pair.into_inner();
-                    let name_pair = p_inner
-                        .next()
-                        .ok_or_else(|| ParseDiagnostic::new(p_span, "missing phenomenon name"))?;
-                    let type_pair = p_inner
+                let p_span = pair_to_span(&phen_pair);
+                let mut p_inner = phen_pair.into_inner();
+                let name_pair = p_inner
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(p_span, "missing phenomenon name"))?;
+                let mut type_pair = p_inner
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(p_span, "missing phenomenon type"))?;
+                let mut parameters = vec![];
+                if type_pair.as_rule() == Rule::phenomenon_parameters {
+                    let owner = format!("phenomenon '{}'", name_pair.as_str());
+                    parameters = parse_typed_fields(type_pair, &owner, "parameter")?;
+                    type_pair = p_inner
                         .next()
                         .ok_or_else(|| ParseDiagnostic::new(p_span, "missing phenomenon type"))?;
-                    let from_pair = p_inner.next().ok_or_else(|| {
-                        ParseDiagnostic::new(p_span, "missing phenomenon source domain")
-                    })?;
-                    let to_pair = p_inner.next().ok_or_else(|| {
-                        ParseDiagnostic::new(p_span, "missing phenomenon target domain")
-                    })?;
-                    let controlled_by_pair = p_inner.next().ok_or_else(|| {
-                        ParseDiagnostic::new(p_span, "missing controlledBy domain")
-                    })?;
-
-                    phenomena.push(Phenomenon {
-                        name: name_pair.as_str().to_string(),
-                        type_: parse_phenomenon_type(type_pair.as_str())
-                            .unwrap_or(PhenomenonType::Event),
-                        from: Reference {
-                            name: from_pair.as_str().to_string(),
-                            span: pair_to_span(&from_pair),
-                        },
-                        to: Reference {
-                            name: to_pair.as_str().to_string(),
-                            span: pair_to_span(&to_pair),
-                        },
-                        controlled_by: Reference {
-                            name: controlled_by_pair.as_str().to_string(),
-                            span: pair_to_span(&controlled_by_pair),
-                        },
-                        span: p_span,
-                    });
                 }
+                let from_pair = p_inner.next().ok_or_else(|| {
+                    ParseDiagnostic::new(p_span, "missing phenomenon source domain")
+                })?;
+                let to_pair = p_inner.next().ok_or_else(|| {
+                    ParseDiagnostic::new(p_span, "missing phenomenon target domain")
+                })?;
+                let controlled_by_pair = p_inner
+                    .next()
+                    .ok_or_else(|| ParseDiagnostic::new(p_span, "missing controlledBy domain"))?;
 
-                problem.interfaces.push(Interface {
-                    name,
-                    connects,
-                    shared_phenomena: phenomena,
-                    span,
-                    source_path: None,
+                phenomena.push(Phenomenon {
+                    name: name_pair.as_str().to_string(),
+                    doc: take_doc(&mut phenomenon_docs),
+                    parameters,
+                    type_: parse_phenomenon_type(type_pair.as_str())
+                        .unwrap_or(PhenomenonType::Event),
+                    from: Reference {
+                        name: from_pair.as_str().to_string(),
+                        span: pair_to_span(&from_pair),
+                    },
+                    to: Reference {
+                        name: to_pair.as_str().to_string(),
+                        span: pair_to_span(&to_pair),
+                    },
+                    controlled_by: Reference {
+                        name: controlled_by_pair.as_str().to_string(),
+                        span: pair_to_span(&controlled_by_pair),
+                    },
+                    span: p_span,
                 });
             }
-            Rule::requirement_decl => {
-                let mut inner = pair.into_inner();
-                let name = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing requirement name"))?
-                    .as_str()
-                    .trim_matches('"')
-                    .to_string();
-                let req_body_pair = inner
+
+            problem.interfaces.push(Interface {
+                name,
+                doc,
+                connects,
+                shared_phenomena: phenomena,
+                span,
+                source_path: None,
+            });
+        }
+        Rule::requirement_decl => {
+            let mut inner = pair.into_inner();
+            let name = string_value(
+                &inner
                     .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing requirement body"))?;
-                let req_body_span = pair_to_span(&req_body_pair);
-
-                let mut required_fields = HashSet::new();
-                let mut has_frame = false;
-
-                let mut req = Requirement {
-                    name,
-                    frame: FrameType::Custom(String::new()),
-                    phenomena: vec![],
-                    marks: vec![],
-                    constraint: String::new(),
-                    constrains: None,
-                    reference: None,
-                    span,
-                    source_path: None,
+                    .ok_or_else(|| ParseDiagnostic::new(span, "missing requirement name"))?,
+            )?;
+            let req_body_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing requirement body"))?;
+            let req_body_span = pair_to_span(&req_body_pair);
+
+            let mut required_fields = HashSet::new();
+            let mut has_frame = false;
+
+            let mut req = Requirement {
+                name,
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc,
+                frame: FrameType::Custom(String::new()),
+                phenomena: vec![],
+                marks: vec![],
+                constraint: String::new(),
+                constrains: None,
+                reference: None,
+                span,
+                source_path: None,
+            };
+
+            for field in req_body_pair.into_inner() {
+                let rule = field.as_rule();
+                let field_span = pair_to_span(&field);
+                let field_seen = match rule {
+                    Rule::requirement_id => "id",
+                    Rule::requirement_priority => "priority",
+                    Rule::requirement_status => "status",
+                    Rule::requirement_owner => "owner",
+                    Rule::frame_type => "frame",
+                    Rule::constraint => "constraint",
+                    Rule::constrains => "constrains",
+                    Rule::reference => "reference",
+                    Rule::requirement_phenomena => "phenomena",
+                    Rule::marks_block => "marks",
+                    _ => "unknown",
                 };
 
-                for field in req_body_pair.into_inner() {
-                    let rule = field.as_rule();
-                    let field_span = pair_to_span(&field);
-                    let field_seen = match rule {
-                        Rule::frame_type => "frame",
-                        Rule::constraint => "constraint",
-                        Rule::constrains => "constrains",
-                        Rule::reference => "reference",
-                        Rule::marks_block => "marks",
-                        _ => "unknown",
-                    };
-
-                    if field_seen != "unknown" && !required_fields.insert(field_seen) {
-                        return Err(ParseDiagnostic::new(
-                            field_span,
-                            format!(
-                                "requirement '{}' has duplicate field '{}'",
-                                req.name, field_seen
-                            ),
-                        ));
-                    }
+                if field_seen != "unknown" && !required_fields.insert(field_seen) {
+                    return Err(ParseDiagnostic::new(
+                        field_span,
+                        format!(
+                            "requirement '{}' has duplicate field '{}'",
+                            req.name, field_seen
+                        ),
+                    ));
+                }
 
-                    match field.as_rule() {
-                        Rule::frame_type => {
-                            let frame_value_pair = next_inner(field, "frame type", field_span)?;
-                            let type_str = match frame_value_pair.as_rule() {
-                                Rule::string_literal => {
-                                    frame_value_pair.as_str().trim_matches('"').to_string()
-                                }
-                                _ => frame_value_pair.as_str().to_string(),
-                            };
-                            if type_str.trim().is_empty() {
+                match field.as_rule() {
+                    Rule::requirement_id => {
+                        let id_pair = next_inner(field, "requirement id", field_span)?;
+                        req.id = Some(Reference {
+                            name: id_pair.as_str().to_string(),
+                            span: pair_to_span(&id_pair),
+                        });
+                    }
+                    Rule::requirement_priority => {
+                        let priority = next_inner(field, "requirement priority", field_span)?;
+                        req.priority = Some(
+                            priority
+                                .as_str()
+                                .parse()
+                                .map_err(|message| ParseDiagnostic::new(field_span, message))?,
+                        );
+                    }
+                    Rule::requirement_status => {
+                        let status = next_inner(field, "requirement status", field_span)?;
+                        req.status = Some(
+                            status
+                                .as_str()
+                                .parse()
+                                .map_err(|message| ParseDiagnostic::new(field_span, message))?,
+                        );
+                    }
+                    Rule::requirement_owner => {
+                        let owner_pair = next_inner(field, "requirement owner", field_span)?;
+                        let owner = string_value(&owner_pair)?;
+                        if owner.trim().is_empty() {
+                            return Err(ParseDiagnostic::new(
+                                pair_to_span(&owner_pair),
+                                format!("requirement '{}' has invalid empty owner", req.name),
+                            ));
+                        }
+                        req.owner = Some(owner);
+                    }
+                    Rule::frame_type => {
+                        let frame_value_pair = next_inner(field, "frame type", field_span)?;
+                        let type_str = string_value(&frame_value_pair)?;
+                        if type_str.trim().is_empty() {
+                            return Err(ParseDiagnostic::new(
+                                pair_to_span(&frame_value_pair),
+                                format!("requirement '{}' has invalid empty frame value", req.name),
+                            ));
+                        }
+                        has_frame = true;
+                        req.frame = parse_frame_type(type_str.as_str());
+                    }
+                    Rule::constraint => {
+                        let literal = next_inner(field, "constraint literal", field_span)?;
+                        req.constraint = string_value(&literal)?;
+                    }
+                    Rule::constrains => {
+                        let ident_pair = next_inner(field, "constrains domain", field_span)?;
+                        req.constrains = Some(Reference {
+                            name: ident_pair.as_str().to_string(),
+                            span: pair_to_span(&ident_pair),
+                        });
+                    }
+                    Rule::reference => {
+                        let ident_pair = next_inner(field, "reference domain", field_span)?;
+                        req.reference = Some(Reference {
+                            name: ident_pair.as_str().to_string(),
+                            span: pair_to_span(&ident_pair),
+                        });
+                    }
+                    Rule::requirement_phenomena => {
+                        let list_pair =
+                            next_inner(field, "requirement phenomena list", field_span)?;
+                        let mut seen_phenomena = HashSet::new();
+                        for phenomenon_pair in list_pair.into_inner() {
+                            if phenomenon_pair.as_rule() != Rule::phenomenon_ref {
+                                continue;
+                            }
+                            let span = pair_to_span(&phenomenon_pair);
+                            let name = phenomenon_ref_name(phenomenon_pair)?;
+                            if !seen_phenomena.insert(name.clone()) {
                                 return Err(ParseDiagnostic::new(
-                                    pair_to_span(&frame_value_pair),
+                                    span,
                                     format!(
-                                        "requirement '{}' has invalid empty frame value",
-                                        req.name
+                                        "requirement '{}' has duplicate phenomenon reference '{}'",
+                                        req.name, name
                                     ),
                                 ));
                             }
-                            has_frame = true;
-                            req.frame = parse_frame_type(type_str.as_str());
-                        }
-                        Rule::constraint => {
-                            let s = next_inner(field, "constraint literal", field_span)?.as_str();
-                            req.constraint = s.trim_matches('"').to_string();
-                        }
-                        Rule::constrains => {
-                            let ident_pair = next_inner(field, "constrains domain", field_span)?;
-                            req.constrains = Some(Reference {
-                                name: ident_pair.as_str().to_string(),
-                                span: pair_to_span(&ident_pair),
-                            });
+                            req.phenomena.push(Reference { name, span });
                         }
-                        Rule::reference => {
-                            let ident_pair = next_inner(field, "reference domain", field_span)?;
-                            req.reference = Some(Reference {
-                                name: ident_pair.as_str().to_string(),
-                                span: pair_to_span(&ident_pair),
-                            });
-                        }
-                        Rule::marks_block => {
-                            req.marks = parse_marks_block(field)?;
-                        }
-                        _ => {}
                     }
+                    Rule::marks_block => {
+                        req.marks = parse_marks_block(field)?;
+                    }
+                    _ => {}
                 }
+            }
+
+            if !has_frame {
+                return Err(ParseDiagnostic::new(
+                    req_body_span,
+                    format!(
+                        "requirement '{}' is missing required field 'frame:'",
+                        req.name
+                    ),
+                ));
+            }
+
+            problem.requirements.push(req);
+        }
+        Rule::subproblem_decl => {
+            let mut inner = pair.into_inner();
+            let name = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing subproblem name"))?
+                .as_str()
+                .to_string();
+            let body = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing subproblem body"))?;
+            let body_span = pair_to_span(&body);
+
+            let mut required_fields = HashSet::new();
+            let mut has_machine = false;
+            let mut has_participants = false;
+            let mut has_requirements = false;
+            let mut seen_requirements = HashSet::new();
+            let mut seen_participants = HashSet::new();
 
-                if !has_frame {
+            let mut subproblem = Subproblem {
+                name,
+                doc,
+                machine: None,
+                participants: vec![],
+                requirements: vec![],
+                span,
+                source_path: None,
+            };
+
+            for field in body.into_inner() {
+                let field_seen = match field.as_rule() {
+                    Rule::subproblem_machine => "machine",
+                    Rule::subproblem_participants => "participants",
+                    Rule::subproblem_requirements => "requirements",
+                    _ => "unknown",
+                };
+                let field_span = pair_to_span(&field);
+
+                if field_seen != "unknown" && !required_fields.insert(field_seen) {
                     return Err(ParseDiagnostic::new(
-                        req_body_span,
+                        field_span,
                         format!(
-                            "requirement '{}' is missing required field 'frame:'",
-                            req.name
+                            "subproblem '{}' has duplicate field '{}'",
+                            subproblem.name, field_seen
                         ),
                     ));
                 }
 
-                problem.requirements.push(req);
-            }
-            Rule::subproblem_decl => {
-                let mut inner = pair.into_inner();
-                let name = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing subproblem name"))?
-                    .as_str()
-                    .to_string();
-                let body = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing subproblem body"))?;
-                let body_span = pair_to_span(&body);
-
-                let mut required_fields = HashSet::new();
-                let mut has_machine = false;
-                let mut has_participants = false;
-                let mut has_requirements = false;
-                let mut seen_requirements = HashSet::new();
-                let mut seen_participants = HashSet::new();
-
-                let mut subproblem = Subproblem {
-                    name,
-                    machine: None,
-                    participants: vec![],
-                    requirements: vec![],
-                    span,
-                    source_path: None,
-                };
-
-                for field in body.into_inner() {
-                    let field_seen = match field.as_rule() {
-                        Rule::subproblem_machine => "machine",
-                        Rule::subproblem_participants => "participants",
-                        Rule::subproblem_requirements => "requirements",
-                        _ => "unknown",
-                    };
-                    let field_span = pair_to_span(&field);
-
-                    if field_seen != "unknown" && !required_fields.insert(field_seen) {
-                        return Err(ParseDiagnostic::new(
-                            field_span,
-                            format!(
-                                "subproblem '{}' has duplicate field '{}'",
-                                subproblem.name, field_seen
-                            ),
-                        ));
+                match field.as_rule() {
+                    Rule::subproblem_machine => {
+                        let machine_pair =
+                            next_inner(field, "subproblem machine domain", field_span)?;
+                        has_machine = true;
+                        subproblem.machine = Some(Reference {
+                            name: machine_pair.as_str().to_string(),
+                            span: pair_to_span(&machine_pair),
+                        });
                     }
-
-                    match field.as_rule() {
-                        Rule::subproblem_machine => {
-                            let machine_pair =
-                                next_inner(field, "subproblem machine domain", field_span)?;
-                            has_machine = true;
-                            subproblem.machine = Some(Reference {
-                                name: machine_pair.as_str().to_string(),
-                                span: pair_to_span(&machine_pair),
-                            });
-                        }
-                        Rule::subproblem_participants => {
-                            has_participants = true;
-                            let list_pair =
-                                next_inner(field, "subproblem participants list", field_span)?;
-                            for participant_pair in list_pair.into_inner() {
-                                if participant_pair.as_rule() == Rule::identifier {
-                                    let name = participant_pair.as_str().to_string();
-                                    let span = pair_to_span(&participant_pair);
-                                    if !seen_participants.insert(name.clone()) {
-                                        return Err(ParseDiagnostic::new(
-                                            span,
-                                            format!(
-                                                "subproblem '{}' has duplicate participant '{}'",
-                                                subproblem.name, name
-                                            ),
-                                        ));
-                                    }
-                                    subproblem.participants.push(Reference { name, span });
+                    Rule::subproblem_participants => {
+                        has_participants = true;
+                        let list_pair =
+                            next_inner(field, "subproblem participants list", field_span)?;
+                        for participant_pair in list_pair.into_inner() {
+                            if participant_pair.as_rule() == Rule::qualified_identifier {
+                                let name = participant_pair.as_str().to_string();
+                                let span = pair_to_span(&participant_pair);
+                                if !seen_participants.insert(name.clone()) {
+                                    return Err(ParseDiagnostic::new(
+                                        span,
+                                        format!(
+                                            "subproblem '{}' has duplicate participant '{}'",
+                                            subproblem.name, name
+                                        ),
+                                    ));
                                 }
+                                subproblem.participants.push(Reference { name, span });
                             }
                         }
-                        Rule::subproblem_requirements => {
-                            has_requirements = true;
-                            let list_pair =
-                                next_inner(field, "subproblem requirements list", field_span)?;
-                            for requirement_pair in list_pair.into_inner() {
-                                if requirement_pair.as_rule() == Rule::string_literal {
-                                    let name =
-                                        requirement_pair.as_str().trim_matches('"').to_string();
-                                    let span = pair_to_span(&requirement_pair);
-                                    if !seen_requirements.insert(name.clone()) {
-                                        return Err(ParseDiagnostic::new(
-                                            span,
-                                            format!(
-                                                "subproblem '{}' has duplicate requirement reference '{}'",
-                                                subproblem.name, name
-                                            ),
-                                        ));
-                                    }
-                                    subproblem.requirements.push(Reference { name, span });
+                    }
+                    Rule::subproblem_requirements => {
+                        has_requirements = true;
+                        let list_pair =
+                            next_inner(field, "subproblem requirements list", field_span)?;
+                        for requirement_pair in list_pair.into_inner() {
+                            if requirement_pair.as_rule() == Rule::string_literal {
+                                let name = string_value(&requirement_pair)?;
+                                let span = pair_to_span(&requirement_pair);
+                                if !seen_requirements.insert(name.clone()) {
+                                    return Err(ParseDiagnostic::new(
+                                        span,
+                                        format!(
+                                            "subproblem '{}' has duplicate requirement reference '{}'",
+                                            subproblem.name, name
+                                        ),
+                                    ));
                                 }
+                                subproblem.requirements.push(Reference { name, span });
                             }
                         }
-                        _ => {}
                     }
+                    _ => {}
                 }
-
-                if !has_machine {
-                    return Err(ParseDiagnostic::new(
-                        body_span,
-                        format!(
-                            "subproblem '{}' is missing required field 'machine:'",
-                            subproblem.name
-                        ),
-                    ));
-                }
-                if !has_participants {
-                    return Err(ParseDiagnostic::new(
-                        body_span,
-                        format!(
-                            "subproblem '{}' is missing required field 'participants:'",
-                            subproblem.name
-                        ),
-                    ));
-                }
-                if !has_requirements {
-                    return Err(ParseDiagnostic::new(
-                        body_span,
-                        format!(
-                            "subproblem '{}' is missing required field 'requirements:'",
-                            subproblem.name
-                        ),
-                    ));
-                }
-
-                problem.subproblems.push(subproblem);
             }
-            Rule::world_properties_decl => {
-                problem
-                    .assertion_sets
-                    .push(parse_assertion_set(pair, AssertionScope::WorldProperties)?);
+
+            if !has_machine {
+                return Err(ParseDiagnostic::new(
+                    body_span,
+                    format!(
+                        "subproblem '{}' is missing required field 'machine:'",
+                        subproblem.name
+                    ),
+                ));
             }
-            Rule::specification_decl => {
-                problem
-                    .assertion_sets
-                    .push(parse_assertion_set(pair, AssertionScope::Specification)?);
+            if !has_participants {
+                return Err(ParseDiagnostic::new(
+                    body_span,
+                    format!(
+                        "subproblem '{}' is missing required field 'participants:'",
+                        subproblem.name
+                    ),
+                ));
             }
-            Rule::requirement_assertions_decl => {
-                problem.assertion_sets.push(parse_assertion_set(
-                    pair,
-                    AssertionScope::RequirementAssertions,
-                )?);
+            if !has_requirements {
+                return Err(ParseDiagnostic::new(
+                    body_span,
+                    format!(
+                        "subproblem '{}' is missing required field 'requirements:'",
+                        subproblem.name
+                    ),
+                ));
             }
-            Rule::correctness_argument_decl => {
-                let mut inner = pair.into_inner();
-                let name_pair = inner.next().ok_or_else(|| {
-                    ParseDiagnostic::new(span, "missing correctness argument name")
-                })?;
-                let prove_pair = inner
-                    .next()
-                    .ok_or_else(|| ParseDiagnostic::new(span, "missing prove statement"))?;
-                let mut prove_inner = prove_pair.into_inner();
-                let specification_set = prove_inner.next().ok_or_else(|| {
-                    ParseDiagnostic::new(span, "missing specification set reference")
-                })?;
-                let world_set = prove_inner.next().ok_or_else(|| {
-                    ParseDiagnostic::new(span, "missing world properties set reference")
-                })?;
-                let requirement_set = prove_inner.next().ok_or_else(|| {
-                    ParseDiagnostic::new(span, "missing requirement set reference")
-                })?;
 
-                problem.correctness_arguments.push(CorrectnessArgument {
-                    name: name_pair.as_str().to_string(),
-                    specification_set: specification_set.as_str().to_string(),
-                    world_set: world_set.as_str().to_string(),
-                    requirement_set: requirement_set.as_str().to_string(),
-                    specification_ref: Reference {
-                        name: specification_set.as_str().to_string(),
-                        span: pair_to_span(&specification_set),
-                    },
-                    world_ref: Reference {
-                        name: world_set.as_str().to_string(),
-                        span: pair_to_span(&world_set),
-                    },
-                    requirement_ref: Reference {
-                        name: requirement_set.as_str().to_string(),
-                        span: pair_to_span(&requirement_set),
-                    },
-                    span,
-                    source_path: None,
-                });
-            }
-            _ => {}
+            problem.subproblems.push(subproblem);
         }
-    }
+        Rule::world_properties_decl => {
+            problem.assertion_sets.push(parse_assertion_set(
+                pair,
+                AssertionScope::WorldProperties,
+                doc,
+            )?);
+        }
+        Rule::specification_decl => {
+            problem.assertion_sets.push(parse_assertion_set(
+                pair,
+                AssertionScope::Specification,
+                doc,
+            )?);
+        }
+        Rule::requirement_assertions_decl => {
+            problem.assertion_sets.push(parse_assertion_set(
+                pair,
+                AssertionScope::RequirementAssertions,
+                doc,
+            )?);
+        }
+        Rule::correctness_argument_decl => {
+            let mut inner = pair.into_inner();
+            let name_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing correctness argument name"))?;
+            let prove_pair = inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing prove statement"))?;
+            let mut prove_inner = prove_pair.into_inner();
+            let specification_set = prove_inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing specification set reference"))?;
+            let world_set = prove_inner.next().ok_or_else(|| {
+                ParseDiagnostic::new(span, "missing world properties set reference")
+            })?;
+            let requirement_set = prove_inner
+                .next()
+                .ok_or_else(|| ParseDiagnostic::new(span, "missing requirement set reference"))?;
 
-    if require_problem_decl && !has_problem_decl {
-        let first_non_ws = input
-            .char_indices()
-            .find(|(_, ch)| !ch.is_whitespace())
-            .map(|(idx, _)| idx)
-            .unwrap_or(0);
-        let end = input.len().min(first_non_ws.saturating_add(1));
-        return Err(ParseDiagnostic::new(
-            Span {
-                start: first_non_ws,
-                end,
-            },
-            "missing required top-level 'problem:' declaration",
-        ));
+            problem.correctness_arguments.push(CorrectnessArgument {
+                name: name_pair.as_str().to_string(),
+                specification_set: specification_set.as_str().to_string(),
+                world_set: world_set.as_str().to_string(),
+                requirement_set: requirement_set.as_str().to_string(),
+                specification_ref: Reference {
+                    name: specification_set.as_str().to_string(),
+                    span: pair_to_span(&specification_set),
+                },
+                world_ref: Reference {
+                    name: world_set.as_str().to_string(),
+                    span: pair_to_span(&world_set),
+                },
+                requirement_ref: Reference {
+                    name: requirement_set.as_str().to_string(),
+                    span: pair_to_span(&requirement_set),
+                },
+                span,
+                source_path: None,
+            });
+        }
+        _ => {}
     }
 
-    Ok(problem)
+    Ok(())
 }
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod tests {
     use crate::ast::*;
-    use crate::parser::{parse, parse_error_diagnostic, parse_module};
+    use crate::parser::{
+        parse, parse_error_diagnostic, parse_module, parse_module_recovering, parse_recovering,
+    };
 
     fn token_span(input: &str, token: &str) -> Span {
         let start = input
@@ -10,6 +12,7 @@ mod tests {
         Span {
             start,
             end: start + token.len(),
+            file: FileId::default(),
         }
     }
 
@@ -19,6 +22,7 @@ mod tests {
         Span {
             start: abs,
             end: abs + token.len(),
+            file: FileId::default(),
         }
     }
 
@@ -235,6 +239,173 @@ mod tests {
         assert!(parse(input).is_err());
     }
 
+    #[test]
+    fn test_parse_requirement_phenomena_field() {
+        let input = r#"
+            problem: RequirementPhenomena
+            domain Gate kind causal role given
+
+            requirement "R1" {
+                frame: RequiredBehavior
+                phenomena: Controller-Gate.PulseOpen, TopPos
+                constrains: Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse requirement phenomena");
+        let phenomena = &problem.requirements[0].phenomena;
+        assert_eq!(phenomena.len(), 2);
+        assert_eq!(phenomena[0].name, "Controller-Gate.PulseOpen");
+        assert_eq!(
+            phenomena[0].span,
+            token_span(input, "Controller-Gate.PulseOpen")
+        );
+        assert_eq!(phenomena[1].name, "TopPos");
+        assert_eq!(phenomena[1].span, token_span(input, "TopPos"));
+    }
+
+    #[test]
+    fn test_parse_quoted_interface_qualifier_in_requirement_phenomena() {
+        let input = r#"
+            problem: RequirementPhenomena
+            domain Gate kind causal role given
+
+            requirement "R1" {
+                frame: RequiredBehavior
+                phenomena: "Gate panel".Open, "Say \"hi\"".Greet
+                constrains: Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse quoted interface qualifier");
+        let phenomena = &problem.requirements[0].phenomena;
+        assert_eq!(phenomena[0].name, "Gate panel.Open");
+        assert_eq!(phenomena[0].span, token_span(input, "\"Gate panel\".Open"));
+        assert_eq!(phenomena[1].name, "Say \"hi\".Greet");
+    }
+
+    #[test]
+    fn test_parse_doc_comments_attach_to_following_nodes() {
+        let input = r#"
+            problem: Documented
+            /// Runs the gate.
+            /// Owned by the controls team.
+            domain Controller kind causal role machine
+            // plain comment, not documentation
+            domain Gate kind causal role given
+            //// banner, not documentation
+            /// Motor wiring.
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    /// Opens the gate.
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                    phenomenon Top : state [Gate -> Controller] controlledBy Gate
+                }
+            }
+            /// Gate opens on request.
+            requirement "R1" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            /// Gate control slice.
+            subproblem S1 {
+                machine: Controller
+                participants: Controller, Gate
+                requirements: "R1"
+            }
+            /// Physical assumptions.
+            worldProperties W {
+                assert "motor works"
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse doc comments");
+        assert_eq!(
+            problem.domains[0].doc.as_deref(),
+            Some("Runs the gate.\nOwned by the controls team.")
+        );
+        assert_eq!(problem.domains[1].doc, None);
+        assert_eq!(problem.interfaces[0].doc.as_deref(), Some("Motor wiring."));
+        let phenomena = &problem.interfaces[0].shared_phenomena;
+        assert_eq!(phenomena[0].doc.as_deref(), Some("Opens the gate."));
+        assert_eq!(phenomena[1].doc, None);
+        assert_eq!(
+            problem.requirements[0].doc.as_deref(),
+            Some("Gate opens on request.")
+        );
+        assert_eq!(
+            problem.subproblems[0].doc.as_deref(),
+            Some("Gate control slice.")
+        );
+        assert_eq!(
+            problem.assertion_sets[0].doc.as_deref(),
+            Some("Physical assumptions.")
+        );
+        assert_eq!(
+            problem.domains[0].span.start,
+            input.find("domain Controller").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_doc_comment_inside_body_is_a_plain_comment() {
+        let input = r#"
+            problem: P
+            domain Gate kind causal role given
+            requirement "R" {
+                /// why the gate must open
+                frame: RequiredBehavior
+                constrains: Gate
+                /// trailing note
+            }
+            subproblem S {
+                /// the controller owns this slice
+                machine: Controller
+                participants: Controller, Gate
+                requirements: "R"
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse /// inside bodies");
+        assert_eq!(problem.requirements[0].doc, None);
+        assert_eq!(problem.subproblems[0].doc, None);
+    }
+
+    #[test]
+    fn test_parse_doc_comment_after_last_declaration_is_a_plain_comment() {
+        let input =
+            "problem: P\ndomain Gate kind causal role given /// trailing\n/// nothing follows\n";
+        let problem = parse(input).expect("Failed to parse trailing ///");
+        assert_eq!(problem.domains[0].doc, None);
+
+        // Recovery parses each declaration on its own, trailing comment included.
+        let broken = format!("domain A kind causal role\n{input}");
+        let (problem, diagnostics) = parse_recovering(&broken);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(problem.domains.len(), 1);
+        assert_eq!(problem.domains[0].name, "Gate");
+    }
+
+    #[test]
+    fn test_parse_error_diagnostic_has_span_for_duplicate_requirement_phenomena() {
+        let input = r#"
+            problem: DuplicatePhenomena
+            domain Gate kind causal role given
+
+            requirement "R1" {
+                frame: RequiredBehavior
+                phenomena: TopPos, TopPos
+                constrains: Gate
+            }
+        "#;
+
+        let (span, message) =
+            parse_error_diagnostic(input).expect("Expected duplicate phenomenon diagnostic");
+        let first = token_span(input, "TopPos");
+        assert_eq!(span, token_span_from(input, first.end, "TopPos"));
+        assert!(message.contains("duplicate phenomenon reference 'TopPos'"));
+    }
+
     #[test]
     fn test_parse_requirement_custom_frame_as_string() {
         let input = r#"
@@ -605,6 +776,7 @@ mod tests {
         let expected = Span {
             start: duplicate,
             end: duplicate + 4,
+            file: FileId::default(),
         };
 
         assert!(message.contains("has duplicate requirement reference"));
@@ -799,4 +971,615 @@ mod tests {
 
         assert!(parse(input).is_err());
     }
+
+    #[test]
+    fn test_parse_recovering_matches_parse_for_valid_input() {
+        let input = r#"
+            problem: Recovering
+            domain M kind causal role machine
+            domain G kind causal role given
+            interface "M-G" connects M, G {
+                shared: {
+                    phenomenon Pulse : event [M -> G] controlledBy M
+                }
+            }
+            requirement "R1" {
+                frame: RequiredBehavior
+                constrains: G
+            }
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        assert!(diagnostics.is_empty());
+        assert_eq!(problem, parse(input).expect("valid input must parse"));
+    }
+
+    #[test]
+    fn test_parse_recovering_keeps_doc_comments_after_broken_declaration() {
+        let input = r#"
+            problem: RecoveringDocs
+            domain M kind causal role
+            /// The gate.
+            domain G kind causal role given
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        assert_eq!(diagnostics.len(), 1);
+        // The message locates the error where the span does, not at the end of
+        // the blanked-out chunk input.
+        let (span, message) = &diagnostics[0];
+        let broken = input.find("domain M kind causal role").unwrap();
+        assert!(span.start > broken && span.start < input.find("///").unwrap());
+        assert!(message.contains(" --> 3:"), "{message}");
+        assert!(message.contains("domain M kind causal role"), "{message}");
+        assert!(message.contains("expected domain_role"), "{message}");
+        assert_eq!(problem.domains.len(), 1);
+        assert_eq!(problem.domains[0].name, "G");
+        assert_eq!(problem.domains[0].doc.as_deref(), Some("The gate."));
+    }
+
+    #[test]
+    fn test_parse_recovering_skips_broken_declarations_and_reports_each() {
+        let input = r#"
+            problem: Recovering
+            domain M kind causal role machine
+            interface "M-G" connects M, G {
+                shared: {
+                    phenomenon Pulse : event [M -> G] controlledBy
+                }
+            }
+            requirement "R1" {
+                constrains: G
+            }
+            domain G kind causal role given
+            requirement "R2" {
+                frame: RequiredBehavior
+                constrains: G
+            }
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        assert_eq!(problem.name, "Recovering");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, vec!["M", "G"]);
+        assert!(problem.interfaces.is_empty());
+        assert_eq!(problem.requirements.len(), 1);
+        assert_eq!(problem.requirements[0].name, "R2");
+        assert_eq!(
+            problem.domains[1].span.start,
+            input.find("domain G").unwrap()
+        );
+
+        assert_eq!(diagnostics.len(), 2);
+        let interface_decl = token_span(input, "interface \"M-G\"");
+        let requirement_decl = token_span(input, "requirement \"R1\"");
+        assert!(diagnostics[0].0.start > interface_decl.start);
+        assert!(diagnostics[0].0.start < requirement_decl.start);
+        assert!(diagnostics[1]
+            .1
+            .contains("requirement 'R1' is missing required field 'frame:'"));
+    }
+
+    #[test]
+    fn test_parse_recovering_unclosed_block_does_not_swallow_following_declarations() {
+        let input = r#"
+            problem: Recovering
+            domain M kind causal role machine
+            requirement "R1" {
+                frame: RequiredBehavior
+
+            domain G kind causal role given
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, vec!["M", "G"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].0.start >= input.find("requirement").unwrap());
+        assert!(diagnostics[0].0.start < input.find("domain G").unwrap());
+    }
+
+    #[test]
+    fn test_parse_recovering_skips_keywords_inside_string_literals() {
+        let input = r#"
+            problem: Recovering
+            domain M kind causal role machine
+            domain G kind causal role given
+            requirement "R1 \"quoted\" { name" {
+                frame: RequiredBehavior
+                constraint: """
+domain knowledge: the gate "closes"
+requirement text { stays } here
+"""
+                constrains: G
+            }
+            requirement "R2
+            domain H kind causal role given
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, vec!["M", "G", "H"]);
+        assert_eq!(problem.requirements.len(), 1);
+        assert_eq!(problem.requirements[0].name, "R1 \"quoted\" { name");
+        assert!(problem.requirements[0]
+            .constraint
+            .contains("domain knowledge: the gate \"closes\""));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].0.start >= input.find("requirement \"R2").unwrap());
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_missing_problem_declaration_only_for_entry_files() {
+        let input = r#"
+            domain M kind causal role machine
+            domain G kind causal role
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        assert_eq!(problem.domains.len(), 1);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[1]
+            .1
+            .contains("missing required top-level 'problem:' declaration"));
+
+        let (module, diagnostics) = parse_module_recovering(input);
+        assert_eq!(module.domains.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_frame_declaration() {
+        let input = r#"
+            problem: HouseFrames
+            /// Clerks approve ledger entries.
+            frame Approval {
+                constrains: lexical | causal role given | designed
+                reference: any
+                connects: constrains, reference
+                phenomenon: command [reference -> machine]
+                phenomenon: state | value [machine -> constrains] controlledBy machine
+                phenomenon: any [constrains -> machine]
+            }
+            requirement "R1" {
+                frame: Approval
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse frame declaration");
+        assert_eq!(problem.frames.len(), 1);
+        let frame = &problem.frames[0];
+        assert_eq!(frame.name, "Approval");
+        assert_eq!(frame.doc.as_deref(), Some("Clerks approve ledger entries."));
+        let constrains = frame.constrains.as_ref().expect("constrains slot");
+        assert_eq!(
+            constrains.kinds,
+            vec![DomainKind::Lexical, DomainKind::Causal]
+        );
+        assert_eq!(
+            constrains.roles,
+            vec![DomainRole::Given, DomainRole::Designed]
+        );
+        let reference = frame.reference.as_ref().expect("reference slot");
+        assert!(reference.kinds.is_empty() && reference.roles.is_empty());
+        assert_eq!(
+            frame.connects,
+            vec![FrameParticipant::Constrains, FrameParticipant::Reference]
+        );
+        assert_eq!(frame.flows.len(), 3);
+        assert_eq!(frame.flows[0].types, [PhenomenonType::Command]);
+        assert_eq!(frame.flows[0].from, FrameParticipant::Reference);
+        assert_eq!(frame.flows[0].to, FrameParticipant::Machine);
+        assert_eq!(frame.flows[0].controlled_by, None);
+        assert_eq!(
+            frame.flows[1].types,
+            [PhenomenonType::State, PhenomenonType::Value]
+        );
+        assert_eq!(
+            frame.flows[1].controlled_by,
+            Some(FrameParticipant::Machine)
+        );
+        assert!(frame.flows[2].types.is_empty());
+        assert_eq!(
+            problem.requirements[0].frame,
+            FrameType::Custom("Approval".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_frame_declaration_rejects_duplicate_fields() {
+        let input = r#"
+            problem: HouseFrames
+            frame Approval {
+                constrains: lexical
+                constrains: causal
+            }
+        "#;
+
+        let err = parse(input).expect_err("duplicate frame field must fail");
+        assert!(err
+            .to_string()
+            .contains("frame 'Approval' has duplicate field 'constrains'"));
+    }
+
+    #[test]
+    fn test_parse_recovering_does_not_split_requirement_frame_field() {
+        let input = r#"
+            problem: Recovering
+            domain Broken kind causal
+            requirement "R1" {
+            frame: RequiredBehavior
+                constrains: Gate
+            }
+        "#;
+
+        let (problem, diagnostics) = parse_recovering(input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(problem.requirements.len(), 1);
+        assert_eq!(problem.requirements[0].frame, FrameType::RequiredBehavior);
+    }
+
+    #[test]
+    fn test_parse_type_declarations_and_phenomenon_parameters() {
+        let input = r#"
+            problem: Typed
+            /// Heater output level.
+            type Percent = range 0..100 unit "%"
+            type Offset = range -2.5..2.5
+            type Celsius = unit "degC"
+            type Mode = enum { Auto, Manual }
+            type Setting = record { level: Percent, mode: Mode }
+            type Nothing = record {}
+            domain Tool kind causal role machine
+            domain Heater kind causal role given
+            interface "Tool-Heater" connects Tool, Heater {
+                shared: {
+                    phenomenon SetLevel(level: Percent, mode: Mode) : command [Tool -> Heater] controlledBy Tool
+                    phenomenon Ping() : event [Tool -> Heater] controlledBy Tool
+                }
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse types");
+        assert_eq!(problem.types.len(), 6);
+        assert_eq!(
+            problem.types[0].doc.as_deref(),
+            Some("Heater output level.")
+        );
+        assert_eq!(
+            problem.types[0].definition,
+            TypeDefinition::Range {
+                min: 0.0,
+                max: 100.0,
+                unit: Some("%".to_string())
+            }
+        );
+        assert_eq!(
+            problem.types[1].definition,
+            TypeDefinition::Range {
+                min: -2.5,
+                max: 2.5,
+                unit: None
+            }
+        );
+        assert_eq!(
+            problem.types[2].definition,
+            TypeDefinition::Unit("degC".to_string())
+        );
+        match &problem.types[3].definition {
+            TypeDefinition::Enum(literals) => {
+                let names: Vec<&str> = literals.iter().map(|l| l.name.as_str()).collect();
+                assert_eq!(names, vec!["Auto", "Manual"]);
+            }
+            other => panic!("expected enum, got {other:?}"),
+        }
+        match &problem.types[4].definition {
+            TypeDefinition::Record(fields) => {
+                assert_eq!(fields.len(), 2);
+                assert_eq!(fields[1].name, "mode");
+                assert_eq!(fields[1].type_ref.name, "Mode");
+            }
+            other => panic!("expected record, got {other:?}"),
+        }
+        assert_eq!(problem.types[5].definition, TypeDefinition::Record(vec![]));
+
+        let phenomena = &problem.interfaces[0].shared_phenomena;
+        let parameters: Vec<(&str, &str)> = phenomena[0]
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_ref.name.as_str()))
+            .collect();
+        assert_eq!(parameters, vec![("level", "Percent"), ("mode", "Mode")]);
+        assert_eq!(phenomena[0].type_, PhenomenonType::Command);
+        assert!(phenomena[1].parameters.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_duplicate_parameters_fields_and_literals() {
+        let cases = [
+            (
+                "type Mode = enum { Auto, Auto }",
+                "type 'Mode' has duplicate literal 'Auto'",
+            ),
+            (
+                "type Pair = record { a: Integer, a: Decimal }",
+                "type 'Pair' has duplicate field 'a'",
+            ),
+            (
+                r#"interface "I" connects A, B {
+                    shared: {
+                        phenomenon Set(a: Integer, a: Integer) : command [A -> B] controlledBy A
+                    }
+                }"#,
+                "phenomenon 'Set' has duplicate parameter 'a'",
+            ),
+        ];
+
+        for (declaration, message) in cases {
+            let input = format!("problem: Dup\n{declaration}\n");
+            let err = parse(&input).expect_err("duplicate names must fail");
+            assert!(
+                err.to_string().contains(message),
+                "expected '{message}' in '{err}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_domain_behavior() {
+        let input = r#"
+            problem: GateStates
+            domain Gate kind causal role given marks: {
+                @ddd.entity
+            } behavior {
+                state Closed, Open
+                state Jammed
+                on PulseOpen from Closed to Open emits GateOpened, Moving
+                on PulseClose from Open to Closed
+            }
+            domain Lamp kind causal role given
+        "#;
+
+        let problem = parse(input).expect("failed to parse behavior");
+        let gate = &problem.domains[0];
+        assert_eq!(gate.marks.len(), 1);
+        let behavior = gate.behavior.as_ref().expect("behavior expected");
+        let states: Vec<&str> = behavior.states.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(states, vec!["Closed", "Open", "Jammed"]);
+        assert_eq!(behavior.transitions.len(), 2);
+        let first = &behavior.transitions[0];
+        assert_eq!(first.trigger.name, "PulseOpen");
+        assert_eq!(first.trigger.span, token_span(input, "PulseOpen"));
+        assert_eq!(first.from.name, "Closed");
+        assert_eq!(first.to.name, "Open");
+        let emits: Vec<&str> = first.emits.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(emits, vec!["GateOpened", "Moving"]);
+        assert!(behavior.transitions[1].emits.is_empty());
+        assert!(problem.domains[1].behavior.is_none());
+    }
+
+    #[test]
+    fn test_parse_domain_behavior_rejects_duplicate_states() {
+        let input = r#"
+            problem: GateStates
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                state Closed
+            }
+        "#;
+
+        let err = parse(input).expect_err("duplicate state must fail");
+        assert!(err
+            .to_string()
+            .contains("domain 'Gate' has duplicate state 'Closed'"));
+    }
+
+    #[test]
+    fn test_parse_domain_schema() {
+        let input = r#"
+            problem: Orders
+            domain Ledger kind lexical role designed schema {
+                entity Order {
+                    field id: String
+                    field total: Money
+                }
+                value Money {
+                    field amount: Decimal
+                }
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse schema");
+        let schema = problem.domains[0].schema.as_ref().expect("schema expected");
+        assert_eq!(schema.entities.len(), 2);
+        assert_eq!(schema.entities[0].name, "Order");
+        assert_eq!(schema.entities[0].kind, SchemaEntityKind::Entity);
+        assert_eq!(schema.entities[1].kind, SchemaEntityKind::Value);
+        let total = &schema.entities[0].fields[1];
+        assert_eq!(total.name, "total");
+        assert_eq!(total.type_ref.name, "Money");
+        assert_eq!(total.span.start, input.find("field total").unwrap());
+    }
+
+    #[test]
+    fn test_parse_domain_schema_rejects_duplicate_entities_and_fields() {
+        let cases = [
+            (
+                "schema { entity Order { } value Order { } }",
+                "domain 'Ledger' has duplicate schema entity 'Order'",
+            ),
+            (
+                "schema { entity Order { field id: String field id: Integer } }",
+                "schema entity 'Order' has duplicate field 'id'",
+            ),
+        ];
+
+        for (schema, message) in cases {
+            let input = format!("problem: Dup\ndomain Ledger kind lexical role given {schema}\n");
+            let err = parse(&input).expect_err("duplicate names must fail");
+            assert!(
+                err.to_string().contains(message),
+                "expected '{message}' in '{err}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_requirement_metadata() {
+        let input = r#"
+            problem: Tracked
+            domain M kind causal role machine
+            requirement "Gate opens on request" {
+                id: REQ-1.2
+                priority: medium
+                status: implemented
+                owner: "Gate Team"
+                frame: RequiredBehavior
+                constrains: M
+            }
+            requirement "R2" {
+                owner: Ops
+                frame: RequiredBehavior
+                constrains: M
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse requirement metadata");
+        let requirement = &problem.requirements[0];
+        let id = requirement.id.as_ref().expect("id should be parsed");
+        assert_eq!(id.name, "REQ-1.2");
+        assert_eq!(id.span.start, input.find("REQ-1.2").unwrap());
+        assert_eq!(requirement.key(), "REQ-1.2");
+        assert_eq!(requirement.priority, Some(RequirementPriority::Medium));
+        assert_eq!(requirement.status, Some(RequirementStatus::Implemented));
+        assert_eq!(requirement.owner.as_deref(), Some("Gate Team"));
+
+        let untracked = &problem.requirements[1];
+        assert_eq!(untracked.key(), "R2");
+        assert_eq!(untracked.status, None);
+        assert_eq!(untracked.owner.as_deref(), Some("Ops"));
+    }
+
+    #[test]
+    fn test_parse_requirement_metadata_rejects_duplicates_and_unknown_status() {
+        let duplicate = r#"
+            problem: Dup
+            requirement "R1" {
+                id: REQ-1
+                id: REQ-2
+            }
+        "#;
+        let err = parse(duplicate).expect_err("duplicate id must fail");
+        assert!(err
+            .to_string()
+            .contains("requirement 'R1' has duplicate field 'id'"));
+
+        let unknown = r#"
+            problem: Unknown
+            requirement "R1" {
+                status: retired
+            }
+        "#;
+        assert!(parse(unknown).is_err());
+    }
+
+    #[test]
+    fn test_parse_string_escapes_and_block_strings() {
+        let input = r#"
+            problem: Strings
+            domain M kind causal role machine
+            requirement "Say \"open\"" {
+                frame: RequiredBehavior
+                constrains: M
+                constraint: """
+                    Opens within 5 s
+                      when "asked".
+
+                    Path: C:\\gate \u{2192} done\n"""
+            }
+            worldProperties W {
+                assert "a\tb \"\"\" // not a comment"
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse escaped strings");
+        let requirement = &problem.requirements[0];
+        assert_eq!(requirement.name, "Say \"open\"");
+        assert_eq!(
+            requirement.constraint,
+            "Opens within 5 s\n  when \"asked\".\n\nPath: C:\\gate \u{2192} done\n"
+        );
+        assert_eq!(
+            problem.assertion_sets[0].assertions[0].text,
+            "a\tb \"\"\" // not a comment"
+        );
+    }
+
+    #[test]
+    fn test_parse_block_string_indentation_follows_closing_quotes() {
+        let input = "problem: P\nrequirement \"R\" {\n    frame: RequiredBehavior\n    constraint: \"\"\"\n        first\n          second\n    \"\"\"\n}\n";
+        let problem = parse(input).expect("Failed to parse block string");
+        assert_eq!(
+            problem.requirements[0].constraint,
+            "    first\n      second"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_escape_sequences() {
+        for literal in [r#""\q""#, r#""\u{110000}""#, r#""\u{}""#, r#""\u{41""#] {
+            let input =
+                format!("problem: P\nrequirement {literal} {{ frame: RequiredBehavior }}\n");
+            let err = parse(&input).expect_err("invalid escape must fail");
+            assert!(
+                err.to_string().contains("escape"),
+                "unexpected error for {literal}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_aliased_and_selective_imports() {
+        let input = r#"
+            problem: Imports
+            import "std/RequiredBehavior.pf"
+            import "lib.pf" as Lib
+            import { Gate, "Keep open" } from "gate.pf"
+            domain M kind causal role machine
+            requirement "R" {
+                frame: Lib.Audit
+                constrains: Lib.Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("Failed to parse imports");
+        assert_eq!(problem.imports.len(), 3);
+        assert_eq!(problem.imports[0].path, "std/RequiredBehavior.pf");
+        assert!(problem.imports[0].alias.is_none());
+
+        let alias = problem.imports[1].alias.as_ref().expect("alias");
+        assert_eq!(alias.name, "Lib");
+        assert_eq!(alias.span.start, input.find("Lib\n").unwrap());
+
+        let items: Vec<&str> = problem.imports[2]
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(items, ["Gate", "Keep open"]);
+        assert_eq!(problem.imports[2].path, "gate.pf");
+
+        let requirement = &problem.requirements[0];
+        assert_eq!(
+            requirement.frame,
+            FrameType::Custom("Lib.Audit".to_string())
+        );
+        assert_eq!(requirement.constrains.as_ref().unwrap().name, "Lib.Gate");
+
+        let duplicate = "problem: P\nimport { Gate, Gate } from \"gate.pf\"\n";
+        let err = parse(duplicate).expect_err("duplicate import item must fail");
+        assert!(err
+            .to_string()
+            .contains("import lists 'Gate' more than once"));
+    }
 }
@@ -1,4 +1,6 @@
-use crate::ast::{FrameType, Mark, PhenomenonType, Problem};
+use crate::ast::{
+    FrameType, Mark, PhenomenonType, Problem, SchemaEntityKind, TypeDefinition, TypedField,
+};
 use serde_json::json;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -43,6 +45,7 @@ pub fn generate_ddd_pim_markdown(problem: &Problem) -> String {
     let mut aggregate_candidates = Vec::new();
     let mut value_object_candidates = Vec::new();
     let mut external_systems = Vec::new();
+    let mut entities = Vec::new();
 
     for domain in &problem.domains {
         if let Some(context) = mark_value(&domain.marks, "ddd.bounded_context") {
@@ -60,6 +63,20 @@ pub fn generate_ddd_pim_markdown(problem: &Problem) -> String {
         if has_mark(&domain.marks, "ddd.external_system") {
             external_systems.push(domain.name.clone());
         }
+        // Schema entities of lexical domains are entity and value object candidates.
+        for entity in domain.schema.iter().flat_map(|schema| &schema.entities) {
+            let fields = entity
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name, field.type_ref.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let entry = format!("{}.{} ({})", domain.name, entity.name, fields);
+            match entity.kind {
+                SchemaEntityKind::Entity => entities.push(entry),
+                SchemaEntityKind::Value => value_object_candidates.push(entry),
+            }
+        }
     }
 
     output.push_str("## Bounded Context Map\n");
@@ -133,6 +150,17 @@ pub fn generate_ddd_pim_markdown(problem: &Problem) -> String {
         output.push('\n');
     }
 
+    entities.sort();
+    output.push_str("## Entities\n");
+    if entities.is_empty() {
+        output.push_str("- None.\n\n");
+    } else {
+        for entity in entities {
+            output.push_str(&format!("- {}\n", entity));
+        }
+        output.push('\n');
+    }
+
     output.push_str("## Value Object Candidates\n");
     if value_object_candidates.is_empty() {
         output.push_str("- None.\n\n");
@@ -156,7 +184,7 @@ pub fn generate_ddd_pim_markdown(problem: &Problem) -> String {
     let mut services = BTreeSet::new();
     for requirement in &problem.requirements {
         if let Some(service) = mark_value(&requirement.marks, "ddd.application_service") {
-            services.insert(format!("{} ({})", service, requirement.name));
+            services.insert(format!("{} ({})", service, requirement.key()));
         }
     }
 
@@ -172,16 +200,53 @@ pub fn generate_ddd_pim_markdown(problem: &Problem) -> String {
     output
 }
 
+// SysML v2 carries documentation as a `doc /* ... */` element in the owning body.
+fn sysml_doc(doc: &str) -> String {
+    let text = doc.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("doc /* {} */", text.replace("*/", "* /"))
+}
+
+fn sysml_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn sysml_doc_body(doc: &Option<String>) -> String {
+    match doc {
+        Some(doc) => format!(" {{ {} }}", sysml_doc(doc)),
+        None => ";".to_string(),
+    }
+}
+
 pub fn generate_sysml2_text(problem: &Problem) -> String {
     let mut output = String::new();
     output.push_str(&format!("package {} {{\n", problem.name));
 
     let mut requirements = problem.requirements.clone();
-    requirements.sort_by(|left, right| left.name.cmp(&right.name));
+    requirements.sort_by(|left, right| left.key().cmp(right.key()));
     for requirement in requirements {
+        // SysML v2 short names carry the stable requirement ID
+        let short_name = requirement
+            .id
+            .as_ref()
+            .map(|id| format!("<'{}'> ", id.name))
+            .unwrap_or_default();
         output.push_str(&format!(
-            "  requirement {} \"{}\";\n",
-            requirement.name, requirement.constraint
+            "  requirement {}{} \"{}\"{}\n",
+            short_name,
+            requirement.name,
+            sysml_string(&requirement.constraint),
+            sysml_doc_body(&requirement.doc)
         ));
     }
 
@@ -189,8 +254,11 @@ pub fn generate_sysml2_text(problem: &Problem) -> String {
     domains.sort_by(|left, right| left.name.cmp(&right.name));
     for domain in domains {
         output.push_str(&format!(
-            "  block {} /* kind={:?}, role={:?} */;\n",
-            domain.name, domain.kind, domain.role
+            "  block {} /* kind={:?}, role={:?} */{}\n",
+            domain.name,
+            domain.kind,
+            domain.role,
+            sysml_doc_body(&domain.doc)
         ));
     }
 
@@ -198,14 +266,18 @@ pub fn generate_sysml2_text(problem: &Problem) -> String {
     interfaces.sort_by(|left, right| left.name.cmp(&right.name));
     for interface in interfaces {
         output.push_str(&format!("  interface {} {{\n", interface.name));
+        if let Some(doc) = &interface.doc {
+            output.push_str(&format!("    {}\n", sysml_doc(doc)));
+        }
         for phenomenon in interface.shared_phenomena {
             output.push_str(&format!(
-                "    phenomenon {} : {:?} from {} to {} controlledBy {};\n",
+                "    phenomenon {} : {:?} from {} to {} controlledBy {}{}\n",
                 phenomenon.name,
                 phenomenon.type_,
                 phenomenon.from.name,
                 phenomenon.to.name,
-                phenomenon.controlled_by.name
+                phenomenon.controlled_by.name,
+                sysml_doc_body(&phenomenon.doc)
             ));
         }
         output.push_str("  }\n");
@@ -215,13 +287,36 @@ pub fn generate_sysml2_text(problem: &Problem) -> String {
     output
 }
 
+// Primitive parameter types map onto the SysML v2 `ScalarValues` library.
+fn sysml_type_name(type_name: &str) -> String {
+    match type_name {
+        "Integer" => "ScalarValues::Integer".to_string(),
+        "Decimal" => "ScalarValues::Real".to_string(),
+        "Boolean" => "ScalarValues::Boolean".to_string(),
+        "String" => "ScalarValues::String".to_string(),
+        _ => type_name.to_string(),
+    }
+}
+
+fn sysml_attributes(fields: &[TypedField]) -> Vec<serde_json::Value> {
+    fields
+        .iter()
+        .map(|field| json!({"name": field.name, "type": sysml_type_name(&field.type_ref.name)}))
+        .collect()
+}
+
 pub fn generate_sysml2_json(problem: &Problem) -> Result<String, serde_json::Error> {
     let mut requirements = problem
         .requirements
         .iter()
         .map(|requirement| {
             json!({
-                "id": requirement.name,
+                "id": requirement.key(),
+                "name": requirement.name,
+                "priority": requirement.priority.map(|priority| priority.to_string()),
+                "status": requirement.status.map(|status| status.to_string()),
+                "owner": requirement.owner,
+                "doc": requirement.doc,
                 "constraint": requirement.constraint,
                 "frame": format!("{:?}", requirement.frame),
                 "marks": requirement
@@ -240,6 +335,7 @@ pub fn generate_sysml2_json(problem: &Problem) -> Result<String, serde_json::Err
         .map(|domain| {
             json!({
                 "id": domain.name,
+                "doc": domain.doc,
                 "kind": format!("{:?}", domain.kind),
                 "role": format!("{:?}", domain.role),
                 "marks": domain
@@ -262,10 +358,12 @@ pub fn generate_sysml2_json(problem: &Problem) -> Result<String, serde_json::Err
                 .map(|phenomenon| {
                     json!({
                         "id": phenomenon.name,
+                        "doc": phenomenon.doc,
                         "type": format!("{:?}", phenomenon.type_),
                         "from": phenomenon.from.name,
                         "to": phenomenon.to.name,
                         "controlled_by": phenomenon.controlled_by.name,
+                        "parameters": sysml_attributes(&phenomenon.parameters),
                     })
                 })
                 .collect::<Vec<_>>();
@@ -273,6 +371,7 @@ pub fn generate_sysml2_json(problem: &Problem) -> Result<String, serde_json::Err
 
             json!({
                 "id": interface.name,
+                "doc": interface.doc,
                 "connects": interface.connects.iter().map(|reference| reference.name.clone()).collect::<Vec<_>>(),
                 "phenomena": phenomena,
             })
@@ -280,19 +379,56 @@ pub fn generate_sysml2_json(problem: &Problem) -> Result<String, serde_json::Err
         .collect::<Vec<_>>();
     interfaces.sort_by_key(|entry| entry["id"].as_str().unwrap_or_default().to_string());
 
+    let mut attribute_definitions = problem
+        .types
+        .iter()
+        .map(|data_type| match &data_type.definition {
+            TypeDefinition::Enum(literals) => json!({
+                "id": data_type.name,
+                "doc": data_type.doc,
+                "kind": "enumeration",
+                "literals": literals.iter().map(|literal| literal.name.clone()).collect::<Vec<_>>(),
+            }),
+            TypeDefinition::Record(fields) => json!({
+                "id": data_type.name,
+                "doc": data_type.doc,
+                "kind": "record",
+                "attributes": sysml_attributes(fields),
+            }),
+            TypeDefinition::Range { min, max, unit } => json!({
+                "id": data_type.name,
+                "doc": data_type.doc,
+                "kind": "range",
+                "base": sysml_type_name("Decimal"),
+                "min": min,
+                "max": max,
+                "unit": unit,
+            }),
+            TypeDefinition::Unit(unit) => json!({
+                "id": data_type.name,
+                "doc": data_type.doc,
+                "kind": "unit",
+                "base": sysml_type_name("Decimal"),
+                "unit": unit,
+            }),
+        })
+        .collect::<Vec<_>>();
+    attribute_definitions.sort_by_key(|entry| entry["id"].as_str().unwrap_or_default().to_string());
+
     let payload = json!({
         "model": problem.name,
         "target": "sysml-v2-json",
         "schema_version": "0.1-draft",
         "requirements": requirements,
         "blocks": blocks,
+        "attribute_definitions": attribute_definitions,
         "interfaces": interfaces,
         "frames": problem
             .requirements
             .iter()
             .map(|requirement| {
                 json!({
-                    "requirement_id": requirement.name,
+                    "requirement_id": requirement.key(),
                     "frame_type": match &requirement.frame {
                         FrameType::Custom(name) => name.clone(),
                         _ => format!("{:?}", requirement.frame),
@@ -384,4 +520,132 @@ mod tests {
         assert!(json.contains("\"target\": \"sysml-v2-json\""));
         assert!(json.contains("\"id\": \"R1\""));
     }
+
+    #[test]
+    fn sysml_outputs_key_requirements_on_stable_id() {
+        let input = r#"
+            problem: PimIds
+            domain Tool kind causal role machine
+            domain Ledger kind lexical role given
+            interface "Tool-Ledger" connects Tool, Ledger {
+                shared: {
+                    phenomenon Persist : value [Tool -> Ledger] controlledBy Tool
+                }
+            }
+            requirement "KeepRecords" {
+                id: REQ-1
+                priority: high
+                status: approved
+                owner: "Ledger Team"
+                frame: Transformation
+                constrains: Ledger
+            }
+        "#;
+        let problem = parse(input).expect("parse failed");
+        let text = generate_sysml2_text(&problem);
+        let json = generate_sysml2_json(&problem).expect("json generation failed");
+        assert!(text.contains("requirement <'REQ-1'> KeepRecords \"\";"));
+        assert!(json.contains("\"id\": \"REQ-1\""));
+        assert!(json.contains("\"name\": \"KeepRecords\""));
+        assert!(json.contains("\"status\": \"approved\""));
+        assert!(json.contains("\"owner\": \"Ledger Team\""));
+        assert!(json.contains("\"requirement_id\": \"REQ-1\""));
+    }
+
+    #[test]
+    fn sysml_outputs_carry_doc_comments() {
+        let input = r#"
+            problem: PimDocs
+            /// The tool.
+            domain Tool kind causal role machine
+            domain Ledger kind lexical role given
+            /// Persistence link.
+            interface "Tool-Ledger" connects Tool, Ledger {
+                shared: {
+                    /// Writes a */ record.
+                    phenomenon Persist : value [Tool -> Ledger] controlledBy Tool
+                }
+            }
+            /// Records are kept.
+            requirement "R1" {
+                frame: Transformation
+                constrains: Ledger
+            }
+        "#;
+        let problem = parse(input).expect("parse failed");
+        let text = generate_sysml2_text(&problem);
+        let json = generate_sysml2_json(&problem).expect("json generation failed");
+        assert!(text.contains("requirement R1 \"\" { doc /* Records are kept. */ }"));
+        assert!(text.contains("block Tool /* kind=Causal, role=Machine */ { doc /* The tool. */ }"));
+        assert!(text.contains("block Ledger /* kind=Lexical, role=Given */;"));
+        assert!(text.contains("    doc /* Persistence link. */\n"));
+        assert!(text.contains("{ doc /* Writes a * / record. */ }"));
+        assert!(json.contains("\"doc\": \"Records are kept.\""));
+        assert!(json.contains("\"doc\": null"));
+    }
+
+    #[test]
+    fn sysml_json_carries_attribute_definitions_and_parameters() {
+        let input = r#"
+            problem: PimTypes
+            type Percent = range 0..100 unit "%"
+            type Mode = enum { Auto, Manual }
+            type Setting = record { level: Percent, locked: Boolean }
+            domain Tool kind causal role machine
+            domain Operator kind biddable role given
+            interface "Operator-Tool" connects Operator, Tool {
+                shared: {
+                    phenomenon Configure(setting: Setting, mode: Mode) : command [Operator -> Tool] controlledBy Operator
+                }
+            }
+        "#;
+        let problem = parse(input).expect("parse failed");
+        let json: serde_json::Value =
+            serde_json::from_str(&generate_sysml2_json(&problem).expect("json generation failed"))
+                .expect("invalid json");
+
+        let definitions = json["attribute_definitions"].as_array().unwrap();
+        let ids: Vec<&str> = definitions
+            .iter()
+            .map(|definition| definition["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["Mode", "Percent", "Setting"]);
+        assert_eq!(
+            definitions[0]["literals"],
+            serde_json::json!(["Auto", "Manual"])
+        );
+        assert_eq!(definitions[1]["max"], serde_json::json!(100.0));
+        assert_eq!(definitions[1]["unit"], "%");
+        assert_eq!(
+            definitions[2]["attributes"][1]["type"],
+            "ScalarValues::Boolean"
+        );
+        assert_eq!(
+            json["interfaces"][0]["phenomena"][0]["parameters"],
+            serde_json::json!([
+                {"name": "setting", "type": "Setting"},
+                {"name": "mode", "type": "Mode"}
+            ])
+        );
+    }
+
+    #[test]
+    fn ddd_report_lists_schema_entities_and_value_objects() {
+        let input = r#"
+            problem: Orders
+            domain Clerk kind causal role machine
+            domain Ledger kind lexical role designed schema {
+                entity Order {
+                    field total: Money
+                }
+                value Money {
+                    field amount: Decimal
+                }
+            }
+        "#;
+        let problem = parse(input).expect("parse failed");
+        let report = generate_ddd_pim_markdown(&problem);
+        assert!(report.contains("## Entities\n- Ledger.Order (total: Money)\n"));
+        assert!(report.contains("## Value Object Candidates\n- Ledger.Money (amount: Decimal)\n"));
+    }
 }
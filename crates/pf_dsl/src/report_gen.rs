@@ -1,7 +1,49 @@
 use crate::ast::*;
 use crate::decomposition_closure;
+use crate::lints::{LintOutcome, SuppressedBy};
+use crate::source_map::SourceMap;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequirementFilter {
+    pub status: Option<RequirementStatus>,
+    pub owner: Option<String>,
+}
+
+impl RequirementFilter {
+    pub fn matches(&self, requirement: &Requirement) -> bool {
+        self.status
+            .is_none_or(|status| requirement.status == Some(status))
+            && self
+                .owner
+                .as_ref()
+                .is_none_or(|owner| requirement.owner.as_ref() == Some(owner))
+    }
+}
+
+// Doc comments continue the list item (or heading) they describe.
+fn push_doc(report: &mut String, indent: &str, doc: &Option<String>) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            report.push_str(&format!("{indent}{line}\n"));
+        }
+    }
+}
+
+fn push_grouping(report: &mut String, label: &str, groups: BTreeMap<String, Vec<&str>>) {
+    let groups = groups
+        .into_iter()
+        .map(|(group, keys)| format!("{} ({})", group, keys.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ");
+    report.push_str(&format!("- **{}**: {}\n", label, groups));
+}
 
 pub fn generate_report(problem: &Problem) -> String {
+    generate_filtered_report(problem, &RequirementFilter::default())
+}
+
+pub fn generate_filtered_report(problem: &Problem, filter: &RequirementFilter) -> String {
     let mut report = String::new();
 
     report.push_str(&format!("# Problem Report: {}\n\n", problem.name));
@@ -9,12 +51,14 @@ pub fn generate_report(problem: &Problem) -> String {
     report.push_str("## 1. Domains\n");
     for d in &problem.domains {
         report.push_str(&format!("- **{}** ({:?}/{:?})\n", d.name, d.kind, d.role));
+        push_doc(&mut report, "  ", &d.doc);
     }
     report.push('\n');
 
     report.push_str("## 2. Interfaces\n");
     for i in &problem.interfaces {
         report.push_str(&format!("- **Interface**: {}\n", i.name));
+        push_doc(&mut report, "  ", &i.doc);
         for p in &i.shared_phenomena {
             let symbol = match p.type_ {
                 PhenomenonType::Event => "Event",
@@ -26,13 +70,56 @@ pub fn generate_report(problem: &Problem) -> String {
                 "  - [{}] {} ({} -> {})\n",
                 symbol, p.name, p.from.name, p.to.name
             ));
+            push_doc(&mut report, "    ", &p.doc);
         }
     }
     report.push('\n');
 
     report.push_str("## 3. Requirements\n");
-    for r in &problem.requirements {
-        report.push_str(&format!("### {}\n", r.name));
+    let requirements = problem
+        .requirements
+        .iter()
+        .filter(|requirement| filter.matches(requirement))
+        .collect::<Vec<_>>();
+    let has_metadata = requirements
+        .iter()
+        .any(|r| r.status.is_some() || r.owner.is_some());
+    if has_metadata {
+        let mut by_status: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        let mut by_owner: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for r in &requirements {
+            let status = r.status.map(|status| status.to_string());
+            by_status
+                .entry(status.unwrap_or_else(|| "unspecified".to_string()))
+                .or_default()
+                .push(r.key());
+            by_owner
+                .entry(r.owner.clone().unwrap_or_else(|| "unassigned".to_string()))
+                .or_default()
+                .push(r.key());
+        }
+        push_grouping(&mut report, "By status", by_status);
+        push_grouping(&mut report, "By owner", by_owner);
+        report.push('\n');
+    }
+    for r in requirements {
+        match &r.id {
+            Some(id) => report.push_str(&format!("### {}: {}\n", id.name, r.name)),
+            None => report.push_str(&format!("### {}\n", r.name)),
+        }
+        if r.doc.is_some() {
+            push_doc(&mut report, "", &r.doc);
+            report.push('\n');
+        }
+        if let Some(priority) = r.priority {
+            report.push_str(&format!("- **Priority**: {}\n", priority));
+        }
+        if let Some(status) = r.status {
+            report.push_str(&format!("- **Status**: {}\n", status));
+        }
+        if let Some(ref owner) = r.owner {
+            report.push_str(&format!("- **Owner**: {}\n", owner));
+        }
         report.push_str(&format!("- **Frame**: {:?}\n", r.frame));
         report.push_str(&format!("- **Constraint**: {}\n", r.constraint));
         if let Some(ref c) = r.constrains {
@@ -48,6 +135,10 @@ pub fn generate_report(problem: &Problem) -> String {
         report.push_str("## 4. Subproblems\n");
         for subproblem in &problem.subproblems {
             report.push_str(&format!("### {}\n", subproblem.name));
+            if subproblem.doc.is_some() {
+                push_doc(&mut report, "", &subproblem.doc);
+                report.push('\n');
+            }
             if let Some(machine) = &subproblem.machine {
                 report.push_str(&format!("- **Machine**: {}\n", machine.name));
             }
@@ -64,7 +155,12 @@ pub fn generate_report(problem: &Problem) -> String {
                 let requirements = subproblem
                     .requirements
                     .iter()
-                    .map(|requirement| requirement.name.as_str())
+                    .map(|requirement| {
+                        problem
+                            .requirement(&requirement.name)
+                            .map(|requirement| requirement.key())
+                            .unwrap_or(requirement.name.as_str())
+                    })
                     .collect::<Vec<_>>()
                     .join(", ");
                 report.push_str(&format!("- **Requirements**: {}\n", requirements));
@@ -102,3 +198,134 @@ pub fn generate_report(problem: &Problem) -> String {
 
     report
 }
+
+// Findings silenced by `pf-allow` comments or `[lints]` in `pf.toml`, so that they
+// stay visible in reviews; empty when nothing was suppressed.
+pub fn suppressions_section(outcome: &LintOutcome, sources: &SourceMap) -> String {
+    if outcome.suppressions.is_empty() && outcome.allowed_rules.is_empty() {
+        return String::new();
+    }
+    let count = |by: SuppressedBy| {
+        outcome
+            .suppressed
+            .iter()
+            .filter(|(_, suppressed_by)| *suppressed_by == by)
+            .count()
+    };
+
+    let mut report = String::from("## 6. Suppressions\n");
+    for (index, suppression) in outcome.suppressions.iter().enumerate() {
+        let location = sources
+            .location(suppression.span)
+            .unwrap_or_else(|| "unknown location".to_string());
+        let reason = if suppression.reason.is_empty() {
+            "no reason given"
+        } else {
+            suppression.reason.as_str()
+        };
+        report.push_str(&format!(
+            "- `{}` at {}: {} ({} suppressed)\n",
+            suppression.rule_id,
+            location,
+            reason,
+            count(SuppressedBy::Inline(index))
+        ));
+    }
+    if !outcome.allowed_rules.is_empty() {
+        report.push_str(&format!(
+            "- allowed in pf.toml: {} ({} suppressed)\n",
+            outcome
+                .allowed_rules
+                .iter()
+                .map(|rule_id| format!("`{rule_id}`"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            count(SuppressedBy::Config)
+        ));
+    }
+    report.push('\n');
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_filtered_report, generate_report, RequirementFilter};
+    use crate::ast::RequirementStatus;
+    use crate::parser::parse;
+
+    #[test]
+    fn report_includes_doc_comments() {
+        let input = r#"
+            problem: Documented
+            /// Drives the gate.
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    /// Opens the gate.
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            /// Gate opens on request.
+            /// Within two seconds.
+            requirement "R1" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+        "#;
+        let report = generate_report(&parse(input).expect("parse failed"));
+
+        assert!(report.contains("- **Controller** (Causal/Machine)\n  Drives the gate.\n"));
+        assert!(report.contains("- **Gate** (Causal/Given)\n\n"));
+        assert!(report.contains("(Controller -> Gate)\n    Opens the gate.\n"));
+        assert!(
+            report.contains("### R1\nGate opens on request.\nWithin two seconds.\n\n- **Frame**")
+        );
+    }
+
+    #[test]
+    fn report_groups_and_filters_requirements_by_status_and_owner() {
+        let input = r#"
+            problem: Tracked
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            requirement "Gate opens on request" {
+                id: REQ-1
+                priority: high
+                status: approved
+                owner: "Gate Team"
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            requirement "Gate closes at night" {
+                id: REQ-2
+                status: draft
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+        "#;
+        let problem = parse(input).expect("parse failed");
+
+        let report = generate_report(&problem);
+        assert!(report.contains("- **By status**: approved (REQ-1); draft (REQ-2)\n"));
+        assert!(report.contains("- **By owner**: Gate Team (REQ-1); unassigned (REQ-2)\n"));
+        assert!(report.contains(
+            "### REQ-1: Gate opens on request\n- **Priority**: high\n- **Status**: approved\n- **Owner**: Gate Team\n"
+        ));
+
+        let filtered = generate_filtered_report(
+            &problem,
+            &RequirementFilter {
+                status: Some(RequirementStatus::Draft),
+                owner: None,
+            },
+        );
+        assert!(filtered.contains("### REQ-2: Gate closes at night\n"));
+        assert!(!filtered.contains("### REQ-1"));
+    }
+}
@@ -1,38 +1,113 @@
 use crate::ast::*;
-use crate::parser::{parse, parse_module};
+use crate::frames::load_standard_import;
+use crate::import_graph::ImportEdge;
+use crate::manifest::SearchPaths;
+use crate::module_graph::{ImportScope, Module, ModuleGraph};
+use crate::parser::{parse, parse_module, parse_recovering};
+use crate::vfs::{DiskFiles, FileProvider, OverlayFiles};
 use anyhow::{Context, Result};
-use std::collections::HashSet;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 pub fn resolve(entry_file: &Path, content_override: Option<&str>) -> Result<Problem> {
-    let content = match content_override {
-        Some(c) => c.to_string(),
-        None => fs::read_to_string(entry_file)
-            .with_context(|| format!("Failed to read file: {:?}", entry_file))?,
-    };
+    resolve_with_files(entry_file, &entry_overlay(entry_file, content_override))
+}
+
+// Like `resolve`, but the entry file is parsed with error recovery so tooling keeps
+// a usable model mid-edit. Imported files are still parsed strictly.
+pub fn resolve_recovering(
+    entry_file: &Path,
+    content_override: Option<&str>,
+) -> Result<(Problem, Vec<(Span, String)>)> {
+    resolve_recovering_with_files(entry_file, &entry_overlay(entry_file, content_override))
+}
+
+pub fn resolve_with_files(entry_file: &Path, files: &dyn FileProvider) -> Result<Problem> {
+    resolve_modules(entry_file, files)?.flatten()
+}
+
+pub fn resolve_recovering_with_files(
+    entry_file: &Path,
+    files: &dyn FileProvider,
+) -> Result<(Problem, Vec<(Span, String)>)> {
+    let (graph, parse_diagnostics) = resolve_modules_recovering(entry_file, files)?;
+    Ok((graph.flatten()?, parse_diagnostics))
+}
 
-    let mut problem =
+pub fn resolve_modules(entry_file: &Path, files: &dyn FileProvider) -> Result<ModuleGraph> {
+    let content = read_entry_content(entry_file, files)?;
+    let problem =
         parse(&content).with_context(|| format!("Failed to parse file: {:?}", entry_file))?;
 
-    set_problem_source_path(&mut problem, entry_file);
+    load_modules(problem, entry_file, &content, files)
+}
 
-    // Track loaded files to avoid cycles (simple check)
-    let mut loaded = HashSet::new();
-    if let Ok(canon) = fs::canonicalize(entry_file) {
-        loaded.insert(canon);
-    } else {
-        // If file doesn't exist on disk (e.g. unsaved buffer), we can't canonicalize it easily.
-        // We just use the path as is for now, or skip adding it to loaded.
-        // For imports to work relative to it, it must have a parent.
+pub fn resolve_modules_recovering(
+    entry_file: &Path,
+    files: &dyn FileProvider,
+) -> Result<(ModuleGraph, Vec<(Span, String)>)> {
+    let content = read_entry_content(entry_file, files)?;
+    let (problem, parse_diagnostics) = parse_recovering(&content);
+
+    Ok((
+        load_modules(problem, entry_file, &content, files)?,
+        parse_diagnostics,
+    ))
+}
+
+fn entry_overlay(entry_file: &Path, content_override: Option<&str>) -> OverlayFiles<'static> {
+    let mut files = OverlayFiles::new(&DiskFiles);
+    if let Some(content) = content_override {
+        files.insert(entry_file, content);
     }
+    files
+}
 
-    resolve_recursive(&mut problem, entry_file, &mut loaded)?;
+fn read_entry_content(entry_file: &Path, files: &dyn FileProvider) -> Result<String> {
+    files
+        .read_to_string(entry_file)
+        .with_context(|| format!("Failed to read file: {:?}", entry_file))
+}
 
-    Ok(problem)
+fn load_modules(
+    mut problem: Problem,
+    entry_file: &Path,
+    content: &str,
+    files: &dyn FileProvider,
+) -> Result<ModuleGraph> {
+    set_problem_source_path(&mut problem, entry_file);
+    let search = SearchPaths::for_file(entry_file, files)?;
+    let entry_key = files
+        .canonicalize(entry_file)
+        .unwrap_or_else(|_| entry_file.to_path_buf());
+
+    let mut graph = ModuleGraph::default();
+    let file = graph.sources.add(entry_file, content);
+    graph.imports.add_file(&entry_key);
+    graph.modules.push(Module {
+        path: entry_key,
+        file,
+        problem,
+        edges: Vec::new(),
+    });
+
+    let mut loader = ModuleLoader {
+        files,
+        search: &search,
+        graph,
+        active: Vec::new(),
+        path_edges: Vec::new(),
+    };
+    loader.load_imports(0, entry_file)?;
+    Ok(loader.graph)
 }
 
 fn set_problem_source_path(problem: &mut Problem, source_path: &Path) {
+    for frame in &mut problem.frames {
+        frame.source_path = Some(source_path.to_path_buf());
+    }
+    for data_type in &mut problem.types {
+        data_type.source_path = Some(source_path.to_path_buf());
+    }
     for domain in &mut problem.domains {
         domain.source_path = Some(source_path.to_path_buf());
     }
@@ -53,106 +128,101 @@ fn set_problem_source_path(problem: &mut Problem, source_path: &Path) {
     }
 }
 
-fn load_standard_import(import_path_str: &str) -> Option<(&'static str, PathBuf)> {
-    let content = match import_path_str {
-        "std/RequiredBehavior.pf" => include_str!("../../../models/std/RequiredBehavior.pf"),
-        "std/CommandedBehavior.pf" => include_str!("../../../models/std/CommandedBehavior.pf"),
-        "std/InformationDisplay.pf" => include_str!("../../../models/std/InformationDisplay.pf"),
-        "std/SimpleWorkpieces.pf" => include_str!("../../../models/std/SimpleWorkpieces.pf"),
-        "std/Transformation.pf" => include_str!("../../../models/std/Transformation.pf"),
-        _ => return None,
-    };
+struct ModuleLoader<'a> {
+    files: &'a dyn FileProvider,
+    search: &'a SearchPaths,
+    graph: ModuleGraph,
+    // modules from the entry down to the one being loaded, and the edges between them
+    active: Vec<usize>,
+    path_edges: Vec<usize>,
+}
 
-    let source_path = Path::new(env!("CARGO_MANIFEST_DIR"))
-        .join("..")
-        .join("..")
-        .join("models")
-        .join(import_path_str);
+impl ModuleLoader<'_> {
+    // Each file is parsed once, however often and under whichever alias it is
+    // imported; `ModuleGraph::flatten` applies the import scopes.
+    fn load_imports(&mut self, index: usize, current_file: &Path) -> Result<()> {
+        let base_dir = current_file.parent().unwrap_or(Path::new("."));
+        let imports = self.graph.modules[index].problem.imports.clone();
+        self.active.push(index);
 
-    Some((content, source_path))
-}
+        for import in imports {
+            let standard = load_standard_import(import.path.as_str());
+            let import_source_path = match &standard {
+                Some((_, import_path)) => import_path.clone(),
+                None => {
+                    // Next to the importing file first, then the manifest's libraries and search paths.
+                    let import_path = base_dir.join(&import.path);
+                    let import_path = if self.files.is_file(&import_path) {
+                        import_path
+                    } else {
+                        self.search
+                            .locate(&import.path, self.files)
+                            .unwrap_or(import_path)
+                    };
+                    self.files.canonicalize(&import_path).with_context(|| {
+                        format!("Failed to resolve import path: {:?}", import_path)
+                    })?
+                }
+            };
 
-fn resolve_recursive(
-    problem: &mut Problem,
-    current_file: &Path,
-    loaded: &mut HashSet<PathBuf>,
-) -> Result<()> {
-    let base_dir = current_file.parent().unwrap_or(Path::new("."));
-
-    // We iterate over a copy of imports to avoid borrow issues
-    let imports = problem.imports.clone();
-
-    for import_path_str in imports {
-        let (content, import_source_path) = if let Some((content, import_path)) =
-            load_standard_import(import_path_str.as_str())
-        {
-            if loaded.contains(&import_path) {
-                // Cycle detected or already loaded.
+            let scope = ImportScope::of(&import);
+            let edge = self.graph.imports.add_edge(ImportEdge {
+                from: self.graph.modules[index].path.clone(),
+                to: import_source_path.clone(),
+                path: import.path.clone(),
+                alias: scope.alias,
+                items: scope.items,
+                span: import.span,
+            });
+            self.graph.modules[index].edges.push(edge);
+            if let Some(first) = self.graph.imports.first_import_like(edge) {
+                self.graph.imports.add_redundant(edge, first);
                 continue;
             }
-            loaded.insert(import_path.clone());
-            (content.to_string(), import_path)
-        } else {
-            let import_path = base_dir.join(&import_path_str);
-
-            let canonical_path = fs::canonicalize(&import_path)
-                .with_context(|| format!("Failed to resolve import path: {:?}", import_path))?;
-
-            if loaded.contains(&canonical_path) {
-                // Cycle detected or already loaded. For v1 we just skip (idempotent include)
+            let on_path = self
+                .active
+                .iter()
+                .position(|&module| self.graph.modules[module].path == import_source_path);
+            if let Some(start) = on_path {
+                let mut cycle = self.path_edges[start..].to_vec();
+                cycle.push(edge);
+                self.graph.imports.add_cycle(cycle);
+                continue;
+            }
+            if self.graph.module(&import_source_path).is_some() {
                 continue;
             }
-            loaded.insert(canonical_path.clone());
-
-            let content = fs::read_to_string(&canonical_path)
-                .with_context(|| format!("Failed to read imported file: {:?}", canonical_path))?;
-            (content, canonical_path)
-        };
-
-        let mut imported_problem = parse_module(&content)
-            .with_context(|| format!("Failed to parse imported file: {:?}", import_source_path))?;
 
-        // Recursively resolve imports of the imported problem
-        resolve_recursive(&mut imported_problem, &import_source_path, loaded)?;
+            let content = match standard {
+                Some((content, _)) => content.to_string(),
+                None => self
+                    .files
+                    .read_to_string(&import_source_path)
+                    .with_context(|| {
+                        format!("Failed to read imported file: {:?}", import_source_path)
+                    })?,
+            };
+            let mut problem = parse_module(&content).with_context(|| {
+                format!("Failed to parse imported file: {:?}", import_source_path)
+            })?;
+            let file = self.graph.sources.add(&import_source_path, content);
+            problem.assign_file(file);
+            set_problem_source_path(&mut problem, &import_source_path);
+            self.graph.modules.push(Module {
+                path: import_source_path.clone(),
+                file,
+                problem,
+                edges: Vec::new(),
+            });
 
-        // MERGE LOGIC:
-        // Set source_path for all imported elements
-        for domain in &mut imported_problem.domains {
-            domain.source_path = Some(import_source_path.clone());
-        }
-        for interface in &mut imported_problem.interfaces {
-            interface.source_path = Some(import_source_path.clone());
-        }
-        for requirement in &mut imported_problem.requirements {
-            requirement.source_path = Some(import_source_path.clone());
-        }
-        for subproblem in &mut imported_problem.subproblems {
-            subproblem.source_path = Some(import_source_path.clone());
-        }
-        for assertion_set in &mut imported_problem.assertion_sets {
-            assertion_set.source_path = Some(import_source_path.clone());
-        }
-        for correctness_argument in &mut imported_problem.correctness_arguments {
-            correctness_argument.source_path = Some(import_source_path.clone());
+            self.path_edges.push(edge);
+            self.load_imports(self.graph.modules.len() - 1, &import_source_path)?;
+            self.path_edges.pop();
         }
 
-        // Append domains, interfaces, requirements, assertions to the main problem
-        // Note: This is a simple merge. Name collisions are not checked here (Validator handles that).
-        problem.domains.extend(imported_problem.domains);
-        problem.interfaces.extend(imported_problem.interfaces);
-        problem.requirements.extend(imported_problem.requirements);
-        problem.subproblems.extend(imported_problem.subproblems);
-        problem
-            .assertion_sets
-            .extend(imported_problem.assertion_sets);
-        problem
-            .correctness_arguments
-            .extend(imported_problem.correctness_arguments);
-
-        // We effectively "flatten" the user's problem into one big struct.
+        self.active.pop();
+        Ok(())
     }
-
-    Ok(())
 }
 
 pub fn find_definition(
@@ -193,7 +263,7 @@ pub fn find_definition(
         |name: &str, preferred_source: Option<&PathBuf>| -> Option<(Option<PathBuf>, Span)> {
             let mut fallback: Option<(Option<PathBuf>, Span)> = None;
             for requirement in &problem.requirements {
-                if requirement.name != name {
+                if !requirement.is_named(name) {
                     continue;
                 }
 
@@ -235,6 +305,59 @@ pub fn find_definition(
         fallback
     };
 
+    let find_type =
+        |name: &str, preferred_source: Option<&PathBuf>| -> Option<(Option<PathBuf>, Span)> {
+            let mut fallback: Option<(Option<PathBuf>, Span)> = None;
+            for data_type in &problem.types {
+                if data_type.name != name {
+                    continue;
+                }
+
+                if preferred_source
+                    .map(|source| data_type.source_path.as_ref() == Some(source))
+                    .unwrap_or(false)
+                {
+                    return Some((data_type.source_path.clone(), data_type.span));
+                }
+
+                if fallback.is_none() {
+                    fallback = Some((data_type.source_path.clone(), data_type.span));
+                }
+            }
+            fallback
+        };
+
+    let find_phenomenon =
+        |name: &str, preferred_source: Option<&PathBuf>| -> Option<(Option<PathBuf>, Span)> {
+            let (interface_name, phenomenon_name) = match name.rsplit_once('.') {
+                Some((interface_name, phenomenon_name)) => (Some(interface_name), phenomenon_name),
+                None => (None, name),
+            };
+            let mut fallback: Option<(Option<PathBuf>, Span)> = None;
+            for interface in &problem.interfaces {
+                if interface_name.is_some_and(|expected| interface.name != expected) {
+                    continue;
+                }
+                for phenomenon in &interface.shared_phenomena {
+                    if phenomenon.name != phenomenon_name {
+                        continue;
+                    }
+
+                    if preferred_source
+                        .map(|source| interface.source_path.as_ref() == Some(source))
+                        .unwrap_or(false)
+                    {
+                        return Some((interface.source_path.clone(), phenomenon.span));
+                    }
+
+                    if fallback.is_none() {
+                        fallback = Some((interface.source_path.clone(), phenomenon.span));
+                    }
+                }
+            }
+            fallback
+        };
+
     let is_offset_in_ref =
         |reference: &Reference| offset >= reference.span.start && offset < reference.span.end;
 
@@ -260,6 +383,68 @@ pub fn find_definition(
             if offset >= phen.controlled_by.span.start && offset < phen.controlled_by.span.end {
                 return find_domain(&phen.controlled_by.name, interface_source);
             }
+            for parameter in &phen.parameters {
+                if is_offset_in_ref(&parameter.type_ref) {
+                    return find_type(&parameter.type_ref.name, interface_source);
+                }
+            }
+        }
+    }
+
+    // Record fields referring to other declared types
+    for data_type in problem
+        .types
+        .iter()
+        .filter(|data_type| source_matches(data_type.source_path.as_ref()))
+    {
+        if let TypeDefinition::Record(fields) = &data_type.definition {
+            for field in fields {
+                if is_offset_in_ref(&field.type_ref) {
+                    return find_type(&field.type_ref.name, data_type.source_path.as_ref());
+                }
+            }
+        }
+    }
+
+    // Domain schemas and behaviors: field types, triggers, emissions and states
+    for domain in problem
+        .domains
+        .iter()
+        .filter(|domain| source_matches(domain.source_path.as_ref()))
+    {
+        let domain_source = domain.source_path.as_ref();
+        let entities = domain
+            .schema
+            .as_ref()
+            .map(|schema| schema.entities.as_slice())
+            .unwrap_or_default();
+        for field in entities.iter().flat_map(|entity| &entity.fields) {
+            if is_offset_in_ref(&field.type_ref) {
+                return entities
+                    .iter()
+                    .find(|entity| entity.name == field.type_ref.name)
+                    .map(|entity| (domain.source_path.clone(), entity.span))
+                    .or_else(|| find_type(&field.type_ref.name, domain_source));
+            }
+        }
+        let Some(behavior) = &domain.behavior else {
+            continue;
+        };
+        for transition in &behavior.transitions {
+            for phenomenon_ref in std::iter::once(&transition.trigger).chain(&transition.emits) {
+                if is_offset_in_ref(phenomenon_ref) {
+                    return find_phenomenon(&phenomenon_ref.name, domain_source);
+                }
+            }
+            for state_ref in [&transition.from, &transition.to] {
+                if is_offset_in_ref(state_ref) {
+                    return behavior
+                        .states
+                        .iter()
+                        .find(|state| state.name == state_ref.name)
+                        .map(|state| (domain.source_path.clone(), state.span));
+                }
+            }
         }
     }
 
@@ -280,6 +465,11 @@ pub fn find_definition(
                 return find_domain(&r.name, req_source);
             }
         }
+        for phenomenon_ref in &req.phenomena {
+            if is_offset_in_ref(phenomenon_ref) {
+                return find_phenomenon(&phenomenon_ref.name, req_source);
+            }
+        }
     }
 
     // 3. Check Subproblems
@@ -338,15 +528,102 @@ pub fn find_definition(
         }
     }
 
+    // 5. Check selective import lists of the entry file
+    for import in &problem.imports {
+        if let Some(item) = import.items.iter().find(|item| is_offset_in_ref(item)) {
+            let name = match &import.alias {
+                Some(alias) => format!("{}.{}", alias.name, item.name),
+                None => item.name.clone(),
+            };
+            return find_domain(&name, None)
+                .or_else(|| find_type(&name, None))
+                .or_else(|| find_requirement(&name, None));
+        }
+    }
+
     None
 }
 
+// Doc comment for the entity under `offset`: the definition a reference resolves to,
+// or otherwise the innermost documented declaration enclosing the offset.
+pub fn find_documentation(problem: &Problem, source_file: &Path, offset: usize) -> Option<String> {
+    let mut documented: Vec<(Option<&PathBuf>, Span, &Option<String>)> = Vec::new();
+    for data_type in &problem.types {
+        documented.push((
+            data_type.source_path.as_ref(),
+            data_type.span,
+            &data_type.doc,
+        ));
+    }
+    for domain in &problem.domains {
+        documented.push((domain.source_path.as_ref(), domain.span, &domain.doc));
+    }
+    for interface in &problem.interfaces {
+        documented.push((
+            interface.source_path.as_ref(),
+            interface.span,
+            &interface.doc,
+        ));
+        for phenomenon in &interface.shared_phenomena {
+            documented.push((
+                interface.source_path.as_ref(),
+                phenomenon.span,
+                &phenomenon.doc,
+            ));
+        }
+    }
+    for requirement in &problem.requirements {
+        documented.push((
+            requirement.source_path.as_ref(),
+            requirement.span,
+            &requirement.doc,
+        ));
+    }
+    for subproblem in &problem.subproblems {
+        documented.push((
+            subproblem.source_path.as_ref(),
+            subproblem.span,
+            &subproblem.doc,
+        ));
+    }
+    for assertion_set in &problem.assertion_sets {
+        documented.push((
+            assertion_set.source_path.as_ref(),
+            assertion_set.span,
+            &assertion_set.doc,
+        ));
+    }
+
+    if let Some((target_source, target_span)) = find_definition(problem, source_file, offset) {
+        return documented
+            .iter()
+            .find(|(source, span, _)| *span == target_span && source.cloned() == target_source)
+            .and_then(|(_, _, doc)| (*doc).clone());
+    }
+
+    documented
+        .iter()
+        .filter(|(source, span, _)| {
+            source
+                .map(|path| path.as_path() == source_file)
+                .unwrap_or(true)
+                && offset >= span.start
+                && offset < span.end
+        })
+        .min_by_key(|(_, span, _)| span.end - span.start)
+        .and_then(|(_, _, doc)| (*doc).clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn mock_span(start: usize, end: usize) -> Span {
-        Span { start, end }
+        Span {
+            start,
+            end,
+            file: FileId::default(),
+        }
     }
 
     fn mock_ref(name: &str, start: usize, end: usize) -> Reference {
@@ -364,6 +641,7 @@ mod tests {
     ) -> AssertionSet {
         AssertionSet {
             name: name.to_string(),
+            doc: None,
             scope,
             assertions: vec![Assertion {
                 text: "assertion".to_string(),
@@ -383,19 +661,27 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 100),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "D".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: mock_span(10, 20),
                 source_path: None,
             }],
             interfaces: vec![Interface {
                 name: "I".to_string(),
+                doc: None,
                 connects: vec![mock_ref("D", 32, 33), mock_ref("X", 34, 35)],
                 shared_phenomena: vec![Phenomenon {
                     name: "E".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Event,
                     from: mock_ref("D", 50, 55),
                     to: mock_ref("X", 60, 65), // X not defined
@@ -409,6 +695,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         // Click on "D" in "from D" (offset 52)
@@ -433,17 +721,27 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 100),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "C".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Given,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: mock_span(10, 20),
                 source_path: None,
             }],
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 phenomena: vec![],
                 marks: vec![],
@@ -456,6 +754,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         // Click on "C" in "constrains: C" (offset 82)
@@ -465,6 +765,81 @@ mod tests {
         assert_eq!(span.start, 10);
     }
 
+    #[test]
+    fn test_find_definition_requirement_phenomena() {
+        let problem = Problem {
+            name: "Test".to_string(),
+            span: mock_span(0, 200),
+            imports: vec![],
+            frames: vec![],
+            types: vec![],
+            domains: vec![],
+            interfaces: vec![
+                Interface {
+                    name: "A-M".to_string(),
+                    doc: None,
+                    connects: vec![mock_ref("A", 12, 13), mock_ref("M", 14, 15)],
+                    shared_phenomena: vec![Phenomenon {
+                        name: "Tick".to_string(),
+                        doc: None,
+                        parameters: vec![],
+                        type_: PhenomenonType::Event,
+                        from: mock_ref("A", 22, 23),
+                        to: mock_ref("M", 24, 25),
+                        controlled_by: mock_ref("A", 26, 27),
+                        span: mock_span(16, 30),
+                    }],
+                    span: mock_span(10, 40),
+                    source_path: None,
+                },
+                Interface {
+                    name: "B-M".to_string(),
+                    doc: None,
+                    connects: vec![mock_ref("B", 52, 53), mock_ref("M", 54, 55)],
+                    shared_phenomena: vec![Phenomenon {
+                        name: "Tick".to_string(),
+                        doc: None,
+                        parameters: vec![],
+                        type_: PhenomenonType::Event,
+                        from: mock_ref("B", 62, 63),
+                        to: mock_ref("M", 64, 65),
+                        controlled_by: mock_ref("B", 66, 67),
+                        span: mock_span(56, 70),
+                    }],
+                    span: mock_span(50, 80),
+                    source_path: None,
+                },
+            ],
+            requirements: vec![Requirement {
+                name: "R".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
+                frame: FrameType::RequiredBehavior,
+                phenomena: vec![mock_ref("B-M.Tick", 110, 118), mock_ref("Tick", 120, 124)],
+                marks: vec![],
+                constraint: String::new(),
+                constrains: None,
+                reference: None,
+                span: mock_span(100, 130),
+                source_path: None,
+            }],
+            subproblems: vec![],
+            assertion_sets: vec![],
+            correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
+        };
+
+        let qualified = find_definition(&problem, Path::new("root.pf"), 112).unwrap();
+        assert_eq!(qualified.1, mock_span(56, 70));
+
+        let unqualified = find_definition(&problem, Path::new("root.pf"), 121).unwrap();
+        assert_eq!(unqualified.1, mock_span(16, 30));
+    }
+
     #[test]
     fn test_find_definition_from_imported_source_file() {
         let imported_path = PathBuf::from("/tmp/imported.pf");
@@ -472,29 +847,40 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 200),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 Domain {
                     name: "A".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Machine,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(10, 20),
                     source_path: Some(imported_path.clone()),
                 },
                 Domain {
                     name: "B".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(21, 30),
                     source_path: Some(imported_path.clone()),
                 },
             ],
             interfaces: vec![Interface {
                 name: "Imported".to_string(),
+                doc: None,
                 connects: vec![mock_ref("A", 40, 41), mock_ref("B", 42, 43)],
                 shared_phenomena: vec![Phenomenon {
                     name: "ev".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Event,
                     from: mock_ref("A", 50, 55),
                     to: mock_ref("B", 56, 61),
@@ -508,6 +894,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let root_result = find_definition(&problem, Path::new("/tmp/root.pf"), 52);
@@ -529,20 +917,28 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 200),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 Domain {
                     name: "M".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Machine,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(10, 20),
                     source_path: None,
                 },
                 Domain {
                     name: "A".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(21, 30),
                     source_path: None,
                 },
@@ -550,6 +946,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 phenomena: vec![],
                 marks: vec![],
@@ -561,6 +962,7 @@ mod tests {
             }],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: Some(mock_ref("M", 100, 101)),
                 participants: vec![mock_ref("M", 110, 111), mock_ref("A", 112, 113)],
                 requirements: vec![mock_ref("R1", 120, 124)],
@@ -569,6 +971,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let machine_def = find_definition(&problem, Path::new("root.pf"), 100).unwrap();
@@ -587,11 +991,16 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 240),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "M".to_string(),
+                doc: None,
                 kind: DomainKind::Causal,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: mock_span(10, 20),
                 source_path: None,
             }],
@@ -614,6 +1023,8 @@ mod tests {
                 span: mock_span(90, 140),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let spec_def = find_definition(&problem, Path::new("root.pf"), 102).unwrap();
@@ -634,37 +1045,51 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(0, 240),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 Domain {
                     name: "A".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Machine,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(10, 20),
                     source_path: Some(root_path.clone()),
                 },
                 Domain {
                     name: "A".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(40, 50),
                     source_path: Some(imported_path.clone()),
                 },
                 Domain {
                     name: "B".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(51, 60),
                     source_path: Some(imported_path.clone()),
                 },
             ],
             interfaces: vec![Interface {
                 name: "Imported".to_string(),
+                doc: None,
                 connects: vec![mock_ref("A", 80, 81), mock_ref("B", 82, 83)],
                 shared_phenomena: vec![Phenomenon {
                     name: "ev".to_string(),
+                    doc: None,
+                    parameters: vec![],
                     type_: PhenomenonType::Event,
                     from: mock_ref("A", 90, 91),
                     to: mock_ref("B", 92, 93),
@@ -678,6 +1103,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let imported_result =
@@ -685,4 +1112,66 @@ mod tests {
         assert_eq!(imported_result.0.as_deref(), Some(imported_path.as_path()));
         assert_eq!(imported_result.1, mock_span(40, 50));
     }
+
+    #[test]
+    fn test_find_definition_behavior_phenomena_and_states() {
+        let input = r#"
+            problem: GateStates
+            domain Controller kind causal role machine
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                on PulseOpen from Closed to Open
+            }
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+        "#;
+        let problem = crate::parser::parse(input).expect("parse failed");
+        let offset_of = |needle: &str| input.find(needle).expect("needle must exist");
+
+        let trigger = find_definition(
+            &problem,
+            Path::new("root.pf"),
+            offset_of("on PulseOpen") + 3,
+        )
+        .expect("trigger definition expected");
+        assert_eq!(trigger.1.start, offset_of("phenomenon PulseOpen"));
+
+        let target = find_definition(&problem, Path::new("root.pf"), offset_of("to Open") + 3)
+            .expect("state definition expected");
+        assert_eq!(target.1.start, offset_of("Open\n"));
+    }
+
+    #[test]
+    fn test_find_definition_schema_field_types() {
+        let input = r#"
+            problem: Orders
+            type Money = record { amount: Decimal }
+            domain Ledger kind lexical role designed schema {
+                entity Order {
+                    field total: Money
+                    field line: Line
+                }
+                entity Line {
+                    field quantity: Integer
+                }
+            }
+        "#;
+        let problem = crate::parser::parse(input).expect("parse failed");
+        let offset_of = |needle: &str| input.find(needle).expect("needle must exist");
+
+        let money = find_definition(
+            &problem,
+            Path::new("root.pf"),
+            offset_of("total: Money") + 7,
+        )
+        .expect("type definition expected");
+        assert_eq!(money.1.start, offset_of("type Money"));
+
+        let line = find_definition(&problem, Path::new("root.pf"), offset_of("line: Line") + 6)
+            .expect("entity definition expected");
+        assert_eq!(line.1.start, offset_of("entity Line"));
+    }
 }
@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod tests {
-    use crate::resolver::resolve;
-    use crate::validator::{validate_with_sources, ValidationError};
+    use crate::module_graph::declared_in;
+    use crate::resolver::{
+        find_definition, find_documentation, resolve, resolve_modules, resolve_recovering,
+        resolve_recovering_with_files, resolve_with_files,
+    };
+    use crate::rule_catalog::Severity;
+    use crate::validator::{validate_with_sources, validation_error_span, ValidationError};
+    use crate::vfs::{MemoryFiles, OverlayFiles};
     use std::fs;
     use std::path::Path;
     use std::path::PathBuf;
@@ -64,6 +70,169 @@ mod tests {
             .any(|r| r.name == "Transformation"));
     }
 
+    #[test]
+    fn test_resolve_merges_imported_frame_declarations() {
+        let dir = make_temp_dir("pf-frame-import");
+        let frames_path = dir.join("frames.pf");
+        let main_path = dir.join("main.pf");
+        fs::write(
+            &frames_path,
+            r#"
+            frame Audit {
+                constrains: lexical
+                connects: constrains
+            }
+            "#,
+        )
+        .expect("failed to write frames file");
+        fs::write(
+            &main_path,
+            r#"
+            problem: AuditTrail
+            import "frames.pf"
+            domain Auditor kind causal role machine
+            domain Log kind lexical role given
+            requirement "KeepLog" {
+                frame: Audit
+                constrains: Log
+            }
+            "#,
+        )
+        .expect("failed to write main file");
+
+        let problem = resolve(&main_path, None).expect("failed to resolve frame import");
+        assert_eq!(problem.frames.len(), 1);
+        assert_eq!(problem.frames[0].name, "Audit");
+
+        let errors = validate_with_sources(&problem).unwrap_err();
+        assert!(errors.iter().any(|issue| matches!(
+            &issue.error,
+            ValidationError::MissingConnection(domain, _, frame, _, _)
+                if domain == "Log" && frame == "Audit"
+        )));
+        assert!(!errors
+            .iter()
+            .any(|issue| matches!(issue.error, ValidationError::UnsupportedFrame(_, _, _))));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_resolve_tags_imported_spans_with_their_file() {
+        let dir = make_temp_dir("pf-source-map");
+        let lib_path = dir.join("lib.pf");
+        let main_path = dir.join("main.pf");
+        fs::write(&lib_path, "domain Plant kind causal role given\n")
+            .expect("failed to write lib file");
+        fs::write(
+            &main_path,
+            "problem: Mapped\nimport \"lib.pf\"\ndomain Controller kind causal role machine\n",
+        )
+        .expect("failed to write main file");
+
+        let problem = resolve(&main_path, None).expect("failed to resolve import");
+        let domain = |name: &str| {
+            problem
+                .domains
+                .iter()
+                .find(|domain| domain.name == name)
+                .unwrap_or_else(|| panic!("missing domain {name}"))
+        };
+        let (controller, plant) = (domain("Controller"), domain("Plant"));
+
+        assert_eq!(
+            problem.sources.path(controller.span.file),
+            Some(main_path.as_path())
+        );
+        assert_ne!(plant.span.file, controller.span.file);
+        assert!(problem
+            .sources
+            .path(plant.span.file)
+            .is_some_and(|path| path.ends_with("lib.pf")));
+        assert!(problem
+            .sources
+            .location(plant.span)
+            .is_some_and(|location| location.ends_with("lib.pf:1:1")));
+        assert!(problem
+            .sources
+            .location(controller.span)
+            .is_some_and(|location| location.ends_with("main.pf:3:1")));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_resolve_recovering_keeps_imports_of_partially_broken_entry() {
+        let input = r#"
+            problem: RecoveringImports
+            import "std/RequiredBehavior.pf"
+            domain Broken kind causal
+            requirement "Local" {
+                frame: RequiredBehavior
+                constrains: ControlledDomain
+            }
+        "#;
+
+        let dummy_path = Path::new("dummy.pf");
+        assert!(resolve(dummy_path, Some(input)).is_err());
+
+        let (problem, diagnostics) =
+            resolve_recovering(dummy_path, Some(input)).expect("recovering resolve must succeed");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(problem.domains.iter().any(|d| d.name == "ControlledDomain"));
+        assert!(!problem.domains.iter().any(|d| d.name == "Broken"));
+        assert!(problem.requirements.iter().any(|r| r.name == "Local"));
+    }
+
+    #[test]
+    fn test_find_documentation_follows_references_and_enclosing_declarations() {
+        let input = r#"
+            problem: Docs
+            /// Drives the gate.
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    /// Opens the gate.
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            /// Gate opens on request.
+            requirement "R1" {
+                frame: RequiredBehavior
+                constrains: Gate
+                reference: Controller
+                phenomena: Pulse
+            }
+        "#;
+        let dummy_path = Path::new("dummy.pf");
+        let problem = resolve(dummy_path, Some(input)).expect("resolve must succeed");
+        let offset_of = |needle: &str| input.find(needle).expect("needle must exist");
+
+        assert_eq!(
+            find_documentation(
+                &problem,
+                dummy_path,
+                offset_of("reference: Controller") + 11
+            )
+            .as_deref(),
+            Some("Drives the gate.")
+        );
+        assert_eq!(
+            find_documentation(&problem, dummy_path, offset_of("phenomena: Pulse") + 11).as_deref(),
+            Some("Opens the gate.")
+        );
+        assert_eq!(
+            find_documentation(&problem, dummy_path, offset_of("frame: RequiredBehavior"))
+                .as_deref(),
+            Some("Gate opens on request.")
+        );
+        assert_eq!(
+            find_documentation(&problem, dummy_path, offset_of("constrains: Gate") + 12),
+            None
+        );
+    }
+
     #[test]
     fn test_resolve_unknown_standard_file() {
         let input = r#"
@@ -162,4 +331,383 @@ correctnessArgument A {
 
         let _ = fs::remove_dir_all(dir);
     }
+
+    const GATE_LIBRARY: &str = r#"
+domain Controller kind causal role machine
+domain Gate kind causal role given
+type Percent = range 0..100 unit "%"
+interface "Drive" connects Controller, Gate {
+  shared: {
+    phenomenon Open(level: Percent) : event [Controller -> Gate] controlledBy Controller
+  }
+}
+requirement "KeepOpen" {
+  id: GATE-1
+  frame: RequiredBehavior
+  constrains: Gate
+  phenomena: Drive.Open
+}
+subproblem GateControl {
+  machine: Controller
+  participants: Controller, Gate
+  requirements: "GATE-1"
+}
+"#;
+
+    #[test]
+    fn test_aliased_imports_qualify_names_and_references() {
+        let dir = make_temp_dir("pf-import-alias");
+        let root_path = dir.join("root.pf");
+        fs::write(dir.join("gate.pf"), GATE_LIBRARY).expect("failed to write gate library");
+        fs::write(
+            dir.join("pump.pf"),
+            "domain Controller kind causal role given\n",
+        )
+        .expect("failed to write pump library");
+        fs::write(
+            &root_path,
+            "problem: Plant\nimport \"gate.pf\" as Lib\nimport \"pump.pf\" as Pump\nrequirement \"Watch\" {\n  frame: RequiredBehavior\n  constrains: Lib.Gate\n  reference: Pump.Controller\n}\n",
+        )
+        .expect("failed to write root");
+
+        let problem = resolve(&root_path, None).expect("failed to resolve aliased imports");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["Lib.Controller", "Lib.Gate", "Pump.Controller"]);
+
+        let interface = &problem.interfaces[0];
+        assert_eq!(interface.name, "Lib.Drive");
+        assert_eq!(interface.connects[1].name, "Lib.Gate");
+        let open = &interface.shared_phenomena[0];
+        assert_eq!(open.controlled_by.name, "Lib.Controller");
+        assert_eq!(open.parameters[0].type_ref.name, "Lib.Percent");
+
+        let keep_open = problem.requirement("Lib.GATE-1").expect("qualified id");
+        assert_eq!(keep_open.name, "Lib.KeepOpen");
+        assert_eq!(keep_open.phenomena[0].name, "Lib.Drive.Open");
+        assert_eq!(problem.subproblems[0].requirements[0].name, "Lib.GATE-1");
+
+        let result = validate_with_sources(&problem);
+        assert!(result.is_ok(), "unexpected issues: {:?}", result.err());
+
+        let root = fs::read_to_string(&root_path).unwrap();
+        let offset = root.find("Lib.Gate").unwrap() + "Lib.".len();
+        let (target_path, span) =
+            find_definition(&problem, &root_path, offset).expect("qualified reference resolves");
+        assert!(target_path.is_some_and(|path| path.ends_with("gate.pf")));
+        assert!(GATE_LIBRARY[span.start..].starts_with("domain Gate"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    const ARGUED_LIBRARY: &str = r#"
+domain Controller kind causal role machine
+domain Gate kind causal role given behavior {
+  state Closed, Opened
+  on Open from Closed to Opened emits Opening
+}
+interface "Controller-Gate" connects Controller, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+    phenomenon Opening : state [Gate -> Controller] controlledBy Gate
+  }
+}
+requirement "Open gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+  marks: {
+    @formal.argument("A")
+  }
+}
+specification S {
+  assert "the controller issues [[Controller-Gate.Open]]"
+}
+worldProperties W {
+  assert "[[Controller-Gate.Open]] moves the gate to [[ Gate.Opened ]]"
+}
+requirementAssertions R {
+  assert "the gate reaches [[Gate.Opened]]"
+}
+correctnessArgument A {
+  prove S and W entail R
+}
+"#;
+
+    #[test]
+    fn test_aliased_imports_qualify_vocabulary_and_argument_marks() {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/lib.pf", ARGUED_LIBRARY);
+        files.insert("/work/plain.pf", "problem: Plant\nimport \"lib.pf\"\n");
+        files.insert(
+            "/work/aliased.pf",
+            "problem: Plant\nimport \"lib.pf\" as Lib\n",
+        );
+
+        let plain = resolve_with_files(Path::new("/work/plain.pf"), &files).unwrap();
+        let result = validate_with_sources(&plain);
+        assert!(result.is_ok(), "unexpected issues: {:?}", result.err());
+
+        let problem = resolve_with_files(Path::new("/work/aliased.pf"), &files).unwrap();
+        let result = validate_with_sources(&problem);
+        assert!(result.is_ok(), "unexpected issues: {:?}", result.err());
+
+        let world = &problem.assertion_sets[1];
+        assert_eq!(
+            world.assertions[0].text,
+            "[[Lib.Controller-Gate.Open]] moves the gate to [[ Lib.Gate.Opened ]]"
+        );
+        let mark = &problem.requirements[0].marks[0];
+        assert_eq!(mark.value.as_deref(), Some("Lib.A"));
+    }
+
+    #[test]
+    fn test_imported_file_cannot_use_an_alias_of_its_importer() {
+        let mut files = MemoryFiles::new();
+        files.insert(
+            "/work/lib.pf",
+            "domain Gate kind causal role given\nrequirement \"Open\" {\n  frame: RequiredBehavior\n  constrains: Lib.Gate\n}\n",
+        );
+        files.insert(
+            "/work/root.pf",
+            "problem: Plant\nimport \"lib.pf\" as Lib\n",
+        );
+
+        let err = resolve_with_files(Path::new("/work/root.pf"), &files)
+            .expect_err("the library borrows the importer's alias");
+        assert!(format!("{err:#}").contains("'Lib.Gate'"));
+        assert!(format!("{err:#}").contains("uses alias 'Lib', which that file does not import"));
+    }
+
+    #[test]
+    fn test_selective_imports_merge_only_listed_declarations() {
+        let dir = make_temp_dir("pf-import-select");
+        let root_path = dir.join("root.pf");
+        fs::write(dir.join("gate.pf"), GATE_LIBRARY).expect("failed to write gate library");
+        let root = "problem: Plant\nimport { Gate, \"KeepOpen\" } from \"gate.pf\"\ndomain Controller kind causal role machine\n";
+        fs::write(&root_path, root).expect("failed to write root");
+
+        let problem = resolve(&root_path, None).expect("failed to resolve selective import");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["Controller", "Gate"]);
+        assert!(problem.interfaces.is_empty());
+        assert!(problem.subproblems.is_empty());
+        assert_eq!(problem.requirements[0].key(), "GATE-1");
+
+        let offset = root.find("Gate,").unwrap();
+        let (target_path, span) =
+            find_definition(&problem, &root_path, offset).expect("import item resolves");
+        assert!(target_path.is_some_and(|path| path.ends_with("gate.pf")));
+        assert!(GATE_LIBRARY[span.start..].starts_with("domain Gate"));
+
+        fs::write(
+            &root_path,
+            "problem: Plant\nimport { Valve } from \"gate.pf\" as Lib\n",
+        )
+        .expect("failed to rewrite root");
+        let err = resolve(&root_path, None).expect_err("unknown import item must fail");
+        assert!(err.to_string().contains("'Valve' is not declared in"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_selective_import_does_not_hide_a_later_direct_import() {
+        for root in [
+            "problem: Plant\nimport { Operator } from \"lib.pf\"\nimport \"common.pf\"\n",
+            "problem: Plant\nimport \"common.pf\"\nimport { Operator } from \"lib.pf\"\n",
+        ] {
+            let mut files = MemoryFiles::new();
+            files.insert("/work/common.pf", "domain Gate kind causal role given\n");
+            files.insert(
+                "/work/lib.pf",
+                "import \"common.pf\"\ndomain Operator kind biddable role given\n",
+            );
+            files.insert("/work/root.pf", root);
+
+            let problem = resolve_with_files(Path::new("/work/root.pf"), &files).unwrap();
+            let mut domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+            domains.sort();
+            assert_eq!(domains, ["Gate", "Operator"], "imports: {root:?}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_finds_imports_through_manifest_root_and_libraries() {
+        let dir = make_temp_dir("pf-manifest-imports");
+        fs::create_dir_all(dir.join("models/plant")).expect("failed to create models dir");
+        fs::create_dir_all(dir.join("vendor/gates")).expect("failed to create library dir");
+        fs::write(
+            dir.join("pf.toml"),
+            "[workspace]\nroot = \"models\"\n\n[libraries]\ngates = \"vendor/gates\"\n",
+        )
+        .expect("failed to write manifest");
+        fs::write(dir.join("vendor/gates/gate.pf"), GATE_LIBRARY)
+            .expect("failed to write gate library");
+        fs::write(
+            dir.join("models/machine.pf"),
+            "domain Controller kind causal role machine\n",
+        )
+        .expect("failed to write shared model");
+        let root_path = dir.join("models/plant/root.pf");
+        fs::write(
+            &root_path,
+            "problem: Plant\nimport \"machine.pf\"\nimport { Gate } from \"gates/gate.pf\"\n",
+        )
+        .expect("failed to write root");
+
+        let problem = resolve(&root_path, None).expect("failed to resolve through manifest");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["Controller", "Gate"]);
+
+        fs::write(&root_path, "problem: Plant\nimport \"gates/valve.pf\"\n")
+            .expect("failed to rewrite root");
+        assert!(resolve(&root_path, None).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_resolve_records_import_graph_with_cycles_and_redundant_imports() {
+        let dir = make_temp_dir("pf-import-graph");
+        let root_path = dir.join("root.pf");
+        let root = "problem: Root\nimport \"a.pf\"\nimport \"b.pf\"\nimport \"a.pf\"\ndomain M kind causal role machine\n";
+        fs::write(&root_path, root).expect("failed to write root");
+        fs::write(
+            dir.join("a.pf"),
+            "import \"shared.pf\"\nimport \"b.pf\"\ndomain A kind causal role given\n",
+        )
+        .expect("failed to write a.pf");
+        fs::write(
+            dir.join("b.pf"),
+            "import \"shared.pf\"\nimport \"a.pf\"\ndomain B kind causal role given\n",
+        )
+        .expect("failed to write b.pf");
+        fs::write(dir.join("shared.pf"), "domain S kind causal role given\n")
+            .expect("failed to write shared.pf");
+
+        let problem = resolve(&root_path, None).expect("cycles must not abort resolution");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["M", "A", "S", "B"]);
+
+        let graph = &problem.import_graph;
+        let file_names: Vec<&str> = graph
+            .files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(file_names, ["root.pf", "a.pf", "shared.pf", "b.pf"]);
+        assert_eq!(graph.edges.len(), 7);
+
+        assert_eq!(graph.cycles.len(), 1);
+        let chain = graph.describe_cycle(&graph.cycles[0], &problem.sources);
+        assert!(chain.contains("a.pf:2:1 imports \"b.pf\" -> "), "{chain}");
+        assert!(chain.ends_with("b.pf:2:1 imports \"a.pf\""), "{chain}");
+        let errors = validate_with_sources(&problem).expect_err("cycle must be reported");
+        assert!(errors.iter().any(
+            |issue| matches!(issue.error, ValidationError::ImportCycle(..))
+                && issue
+                    .source_path
+                    .as_ref()
+                    .is_some_and(|path| path.ends_with("b.pf"))
+        ));
+
+        let warnings: Vec<&ValidationError> = errors
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+            .map(|issue| &issue.error)
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        let span = validation_error_span(warnings[0]);
+        let message = warnings[0].to_string();
+        assert_eq!(span.start, root.rfind("import \"a.pf\"").unwrap());
+        assert!(message.starts_with("Redundant import of \"a.pf\": already imported at "));
+        assert!(message.ends_with("root.pf:2:1"), "{message}");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_resolve_with_in_memory_and_overlay_files() {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/pf.toml", "[libraries]\ngates = \"vendor/gates\"\n");
+        files.insert("/work/vendor/gates/gate.pf", GATE_LIBRARY);
+        files.insert(
+            "/work/models/root.pf",
+            "problem: Plant\nimport { Gate } from \"gates/gate.pf\"\nimport \"../shared.pf\"\n",
+        );
+        files.insert(
+            "/work/shared.pf",
+            "domain Controller kind causal role machine\n",
+        );
+
+        let root_path = Path::new("/work/models/root.pf");
+        let problem = resolve_with_files(root_path, &files).expect("in-memory resolution");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["Gate", "Controller"]);
+        assert_eq!(
+            problem.domains[1].source_path.as_deref(),
+            Some(Path::new("/work/shared.pf"))
+        );
+
+        // unsaved edits to an imported file are seen by the importing file
+        let mut overlay = OverlayFiles::new(&files);
+        overlay.insert(
+            "/work/shared.pf",
+            "domain Operator kind biddable role given\n",
+        );
+        let (problem, _) =
+            resolve_recovering_with_files(root_path, &overlay).expect("overlay resolution");
+        let domains: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(domains, ["Gate", "Operator"]);
+
+        overlay.insert("/work/shared.pf", "domain Operator kind");
+        let err = resolve_with_files(root_path, &overlay).expect_err("broken import must fail");
+        assert!(format!("{err:#}").contains("shared.pf"));
+    }
+
+    #[test]
+    fn test_module_graph_keeps_each_file_and_flattens_on_demand() {
+        let mut files = MemoryFiles::new();
+        files.insert(
+            "/work/root.pf",
+            "problem: Plant\nimport \"gates.pf\" as A\nimport \"gates.pf\" as B\nimport \"ops.pf\"\n",
+        );
+        files.insert("/work/gates.pf", "domain Gate kind causal role given\n");
+        files.insert("/work/ops.pf", "domain Operator kind biddable role given\n");
+
+        let root_path = Path::new("/work/root.pf");
+        let modules = resolve_modules(root_path, &files).expect("module graph");
+        let paths: Vec<&Path> = modules.modules.iter().map(|m| m.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("/work/root.pf"),
+                Path::new("/work/gates.pf"),
+                Path::new("/work/ops.pf")
+            ]
+        );
+        // each module holds only what its own file declares
+        assert!(modules.entry().problem.domains.is_empty());
+        let gates = modules.module(Path::new("/work/gates.pf")).unwrap();
+        let names: Vec<&str> = gates
+            .problem
+            .domains
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(names, ["Gate"]);
+        assert!(gates.edges.is_empty());
+        assert_eq!(modules.entry().edges.len(), 3);
+
+        let problem = modules.flatten().expect("flattened model");
+        assert_eq!(problem, resolve_with_files(root_path, &files).unwrap());
+        let names: Vec<&str> = problem.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["A.Gate", "B.Gate", "Operator"]);
+
+        // a file imported under two aliases is one module with both copies
+        let gates = modules.find_module(Path::new("gates.pf")).unwrap();
+        let scoped = declared_in(&problem, gates.file);
+        let names: Vec<&str> = scoped.domains.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["A.Gate", "B.Gate"]);
+        assert!(modules.find_module(Path::new("missing.pf")).is_err());
+    }
 }
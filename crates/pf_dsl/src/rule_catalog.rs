@@ -0,0 +1,153 @@
+use serde::Deserialize;
+use std::fmt;
+use std::sync::OnceLock;
+
+const CATALOG: &str = include_str!("../../../metamodel/invariant-catalog.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+pub enum Severity {
+    #[serde(rename = "error")]
+    Error,
+    #[serde(rename = "warn")]
+    Warning,
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "hint")]
+    Hint,
+}
+
+impl Severity {
+    // Errors always fail validation; warnings only under `--deny-warnings`.
+    pub fn is_fatal(self, deny_warnings: bool) -> bool {
+        match self {
+            Severity::Error => true,
+            Severity::Warning => deny_warnings,
+            Severity::Info | Severity::Hint => false,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        })
+    }
+}
+
+// One entry of `metamodel/invariant-catalog.json`, which is compiled in.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Rule {
+    pub rule_id: String,
+    pub error_variant: String,
+    pub severity: Severity,
+    pub title: String,
+    pub rationale: String,
+    pub examples: Examples,
+}
+
+// Minimal models, one line per entry. `// file: name.pf` starts another file, for
+// rules that need imports; the first file is the entry model.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Examples {
+    pub valid: Vec<String>,
+    pub invalid: Vec<String>,
+}
+
+pub const EXAMPLE_FILE_MARKER: &str = "// file: ";
+
+// (file name, text) pairs of an example; an example without markers is `model.pf`.
+pub fn example_files(lines: &[String]) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for line in lines {
+        if let Some(name) = line.strip_prefix(EXAMPLE_FILE_MARKER) {
+            files.push((name.trim().to_string(), String::new()));
+            continue;
+        }
+        if files.is_empty() {
+            files.push(("model.pf".to_string(), String::new()));
+        }
+        let (_, text) = files.last_mut().expect("a file was just pushed");
+        text.push_str(line);
+        text.push('\n');
+    }
+    files
+}
+
+impl Rule {
+    // Markdown shared by `pf_dsl explain` and the language server hover.
+    pub fn explain(&self) -> String {
+        let mut text = format!(
+            "**{}** `{}` ({}): {}\n\n{}\n",
+            self.rule_id, self.error_variant, self.severity, self.title, self.rationale
+        );
+        for (label, lines) in [
+            ("Valid", &self.examples.valid),
+            ("Invalid", &self.examples.invalid),
+        ] {
+            text.push_str(&format!("\n{label}:\n\n```pf\n"));
+            for line in lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+            text.push_str("```\n");
+        }
+        text
+    }
+}
+
+#[derive(Deserialize)]
+struct Catalog {
+    rules: Vec<Rule>,
+}
+
+pub fn rules() -> &'static [Rule] {
+    static RULES: OnceLock<Vec<Rule>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        serde_json::from_str::<Catalog>(CATALOG)
+            .expect("embedded invariant catalog must be valid")
+            .rules
+    })
+}
+
+pub fn rule_by_id(rule_id: &str) -> Option<&'static Rule> {
+    rules().iter().find(|rule| rule.rule_id == rule_id)
+}
+
+// A rule by ID or by the name of its `ValidationError` variant, ignoring case.
+pub fn find_rule(name: &str) -> Option<&'static Rule> {
+    rules().iter().find(|rule| {
+        rule.rule_id.eq_ignore_ascii_case(name) || rule.error_variant.eq_ignore_ascii_case(name)
+    })
+}
+
+pub fn rule_for_variant(error_variant: &str) -> Option<&'static Rule> {
+    rules()
+        .iter()
+        .find(|rule| rule.error_variant == error_variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_catalog_maps_rules_to_severities() {
+        let cycle = rule_by_id("PF-VAL-039").expect("import cycle rule");
+        assert_eq!(cycle.error_variant, "ImportCycle");
+        assert_eq!(cycle.severity, Severity::Error);
+        assert_eq!(
+            rule_for_variant("RedundantImport").map(|rule| rule.severity),
+            Some(Severity::Warning)
+        );
+        assert!(rule_by_id("PF-VAL-999").is_none());
+
+        assert!(Severity::Error.is_fatal(false));
+        assert!(!Severity::Warning.is_fatal(false));
+        assert!(Severity::Warning.is_fatal(true));
+        assert!(!Severity::Hint.is_fatal(true));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::ast::Span;
+use std::path::{Path, PathBuf};
+
+// FileId(0) is the entry file, so spans from a plain `parse` already point at it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FileId(pub u32);
+
+// Zero-based line and column; the column unit depends on the helper that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+    path: PathBuf,
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(path: impl Into<PathBuf>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            path: path.into(),
+            text,
+            line_starts,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // Column counted in UTF-8 bytes.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let (line, line_start) = self.line_of(offset);
+        LineCol {
+            line,
+            column: self.clamp(offset) - line_start,
+        }
+    }
+
+    // Column counted in UTF-16 code units, as LSP positions expect.
+    pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+        let (line, line_start) = self.line_of(offset);
+        LineCol {
+            line,
+            column: utf16_len(&self.text[line_start..self.clamp(offset)]),
+        }
+    }
+
+    pub fn offset_utf16(&self, position: LineCol) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line) else {
+            return self.text.len();
+        };
+        offset_in_line_utf16(&self.text, line_start, position.column)
+    }
+
+    fn line_of(&self, offset: usize) -> (usize, usize) {
+        let offset = self.clamp(offset);
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        (line, self.line_starts[line])
+    }
+
+    // Offsets past the end or inside a code point snap back to a char boundary.
+    fn clamp(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn add(&mut self, path: impl Into<PathBuf>, text: impl Into<String>) -> FileId {
+        self.files.push(SourceFile::new(path, text));
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    pub fn file(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.0 as usize)
+    }
+
+    pub fn path(&self, id: FileId) -> Option<&Path> {
+        self.file(id).map(SourceFile::path)
+    }
+
+    pub fn file_id(&self, path: &Path) -> Option<FileId> {
+        self.files
+            .iter()
+            .position(|file| file.path == path)
+            .map(|index| FileId(index as u32))
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (FileId(index as u32), file))
+    }
+
+    // `path:line:col` with one-based line and UTF-8 column, for terminal output.
+    pub fn location(&self, span: Span) -> Option<String> {
+        let file = self.file(span.file)?;
+        let position = file.line_col(span.start);
+        Some(format!(
+            "{}:{}:{}",
+            file.path.display(),
+            position.line + 1,
+            position.column + 1
+        ))
+    }
+}
+
+// Text-only variants for buffers that are not part of a source map, such as
+// unsaved editor documents.
+pub fn line_col_utf16(text: &str, offset: usize) -> LineCol {
+    SourceFile::new(PathBuf::new(), text).line_col_utf16(offset)
+}
+
+pub fn offset_at_utf16(text: &str, position: LineCol) -> usize {
+    let line_start = if position.line == 0 {
+        Some(0)
+    } else {
+        text.match_indices('\n')
+            .nth(position.line - 1)
+            .map(|(index, _)| index + 1)
+    };
+    match line_start {
+        Some(line_start) => offset_in_line_utf16(text, line_start, position.column),
+        None => text.len(),
+    }
+}
+
+fn offset_in_line_utf16(text: &str, line_start: usize, column: usize) -> usize {
+    let mut units = 0;
+    for (index, ch) in text[line_start..].char_indices() {
+        // a column inside a surrogate pair clamps to the start of the code point
+        if ch == '\n' || units + ch.len_utf16() > column {
+            return line_start + index;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_offsets_to_utf8_and_utf16_columns() {
+        let file = SourceFile::new("model.pf", "a\n😀b\n");
+
+        assert_eq!(file.line_col(0), LineCol { line: 0, column: 0 });
+        assert_eq!(file.line_col(6), LineCol { line: 1, column: 4 });
+        assert_eq!(file.line_col_utf16(6), LineCol { line: 1, column: 2 });
+        assert_eq!(file.line_col_utf16(7), LineCol { line: 1, column: 3 });
+        assert_eq!(file.line_col_utf16(99), LineCol { line: 2, column: 0 });
+        assert_eq!(file.offset_utf16(LineCol { line: 1, column: 2 }), 6);
+        assert_eq!(file.offset_utf16(LineCol { line: 1, column: 1 }), 2);
+        assert_eq!(file.offset_utf16(LineCol { line: 1, column: 9 }), 7);
+        assert_eq!(file.offset_utf16(LineCol { line: 5, column: 0 }), 8);
+        assert_eq!(
+            offset_at_utf16("a\n😀b\n", LineCol { line: 1, column: 3 }),
+            7
+        );
+    }
+
+    #[test]
+    fn source_map_assigns_file_ids_and_formats_locations() {
+        let mut sources = SourceMap::default();
+        let entry = sources.add("root.pf", "problem: Root\n");
+        let import = sources.add(
+            "lib/a.pf",
+            "problem: A\ndomain M kind causal role machine\n",
+        );
+
+        assert_eq!(entry, FileId(0));
+        assert_eq!(import, FileId(1));
+        assert_eq!(sources.file_id(Path::new("lib/a.pf")), Some(import));
+        assert_eq!(sources.path(import), Some(Path::new("lib/a.pf")));
+        assert_eq!(
+            sources.location(Span {
+                start: 18,
+                end: 19,
+                file: import,
+            }),
+            Some("lib/a.pf:2:8".to_string())
+        );
+        assert_eq!(
+            sources.location(Span {
+                start: 0,
+                end: 0,
+                file: FileId(7),
+            }),
+            None
+        );
+    }
+}
@@ -107,11 +107,11 @@ pub fn build_trace_map(problem: &Problem) -> TraceMapReport {
     }
 
     for requirement in &problem.requirements {
-        let requirement_target = trace_target_id("sysml.requirement", &[&requirement.name]);
+        let requirement_target = trace_target_id("sysml.requirement", &[requirement.key()]);
         add_target(requirement_target.clone(), "sysml.requirement");
         add_link(
             "requirement",
-            requirement.name.clone(),
+            requirement.key().to_string(),
             requirement_target,
             "sysml.requirement",
         );
@@ -121,7 +121,7 @@ pub fn build_trace_map(problem: &Problem) -> TraceMapReport {
             add_target(service_target.clone(), "ddd.application_service");
             add_link(
                 "requirement",
-                requirement.name.clone(),
+                requirement.key().to_string(),
                 service_target,
                 "ddd.application_service",
             );
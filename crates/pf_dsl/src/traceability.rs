@@ -215,6 +215,10 @@ fn collect_phenomenon_indexes(
                 .entry(phenomenon.name.clone())
                 .or_default()
                 .insert(phenomenon_entity.clone());
+            name_to_phenomena
+                .entry(format!("{}.{}", interface.name, phenomenon.name))
+                .or_default()
+                .insert(phenomenon_entity.clone());
 
             for domain_name in [
                 phenomenon.from.name.as_str(),
@@ -281,7 +285,7 @@ pub fn build_traceability_graph(problem: &Problem) -> TraceabilityGraph {
     }
 
     for requirement in &problem.requirements {
-        let requirement_entity = TraceEntity::Requirement(requirement.name.clone());
+        let requirement_entity = TraceEntity::Requirement(requirement.key().to_string());
         graph.insert_node(requirement_entity.clone());
 
         let mut linked_domains = BTreeSet::new();
@@ -315,6 +319,11 @@ pub fn build_traceability_graph(problem: &Problem) -> TraceabilityGraph {
                 }
             }
 
+            // Explicit `phenomena:` lists replace the domain-derived approximation.
+            if !requirement.phenomena.is_empty() {
+                continue;
+            }
+
             if let Some(phenomena) = domain_to_phenomena.get(&domain_name) {
                 for phenomenon_entity in phenomena {
                     graph.insert_edge(
@@ -326,8 +335,8 @@ pub fn build_traceability_graph(problem: &Problem) -> TraceabilityGraph {
             }
         }
 
-        for phenomenon_name in &requirement.phenomena {
-            if let Some(phenomena) = name_to_phenomena.get(phenomenon_name) {
+        for phenomenon_ref in &requirement.phenomena {
+            if let Some(phenomena) = name_to_phenomena.get(&phenomenon_ref.name) {
                 for phenomenon_entity in phenomena {
                     graph.insert_edge(
                         requirement_entity.clone(),
@@ -360,9 +369,13 @@ pub fn build_traceability_graph(problem: &Problem) -> TraceabilityGraph {
         }
 
         for requirement in &subproblem.requirements {
+            let key = problem
+                .requirement(&requirement.name)
+                .map(|requirement| requirement.key())
+                .unwrap_or(requirement.name.as_str());
             graph.insert_edge(
                 subproblem_entity.clone(),
-                TraceEntity::Requirement(requirement.name.clone()),
+                TraceEntity::Requirement(key.to_string()),
                 TraceRelation::SubproblemIncludesRequirement,
             );
         }
@@ -372,13 +385,55 @@ pub fn build_traceability_graph(problem: &Problem) -> TraceabilityGraph {
 }
 
 fn csv_escape(value: &str) -> String {
-    if value.contains(',') || value.contains('"') || value.contains('\n') {
+    if value.contains([',', '"', '\n', '\r']) {
         format!("\"{}\"", value.replace('"', "\"\""))
     } else {
         value.to_string()
     }
 }
 
+fn documented_entities(problem: &Problem) -> Vec<(TraceEntity, &str)> {
+    let mut documented = Vec::new();
+    for requirement in &problem.requirements {
+        if let Some(doc) = &requirement.doc {
+            documented.push((
+                TraceEntity::Requirement(requirement.key().to_string()),
+                doc.as_str(),
+            ));
+        }
+    }
+    for domain in &problem.domains {
+        if let Some(doc) = &domain.doc {
+            documented.push((TraceEntity::Domain(domain.name.clone()), doc.as_str()));
+        }
+    }
+    for interface in &problem.interfaces {
+        if let Some(doc) = &interface.doc {
+            documented.push((TraceEntity::Interface(interface.name.clone()), doc.as_str()));
+        }
+        for phenomenon in &interface.shared_phenomena {
+            if let Some(doc) = &phenomenon.doc {
+                documented.push((
+                    TraceEntity::Phenomenon {
+                        interface: interface.name.clone(),
+                        name: phenomenon.name.clone(),
+                    },
+                    doc.as_str(),
+                ));
+            }
+        }
+    }
+    for subproblem in &problem.subproblems {
+        if let Some(doc) = &subproblem.doc {
+            documented.push((
+                TraceEntity::Subproblem(subproblem.name.clone()),
+                doc.as_str(),
+            ));
+        }
+    }
+    documented
+}
+
 fn sorted_join(values: BTreeSet<String>) -> String {
     if values.is_empty() {
         "-".to_string()
@@ -447,7 +502,7 @@ pub fn generate_traceability_markdown(
     output.push_str("| --- | --- | --- | --- | --- | --- |\n");
 
     for requirement in &problem.requirements {
-        let requirement_entity = TraceEntity::Requirement(requirement.name.clone());
+        let requirement_entity = TraceEntity::Requirement(requirement.key().to_string());
 
         let mut constrains = BTreeSet::new();
         let mut references = BTreeSet::new();
@@ -481,7 +536,7 @@ pub fn generate_traceability_markdown(
 
         output.push_str(&format!(
             "| {} | {} | {} | {} | {} | {} |\n",
-            requirement.name,
+            requirement.key(),
             sorted_join(constrains),
             sorted_join(references),
             sorted_join(interfaces),
@@ -491,6 +546,19 @@ pub fn generate_traceability_markdown(
     }
     output.push('\n');
 
+    let documented = documented_entities(problem);
+    if !documented.is_empty() {
+        output.push_str("## Documentation\n");
+        for (entity, doc) in documented {
+            output.push_str(&format!(
+                "- `{}`: {}\n",
+                entity,
+                doc.split_whitespace().collect::<Vec<_>>().join(" ")
+            ));
+        }
+        output.push('\n');
+    }
+
     output.push_str("## Impact Analysis\n");
     output.push_str(&format!("- Max hops: {}\n", max_hops));
     if impact_seeds.is_empty() {
@@ -591,7 +659,11 @@ mod tests {
     use super::*;
 
     fn span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn reference(name: &str) -> Reference {
@@ -604,9 +676,12 @@ mod tests {
     fn domain(name: &str, kind: DomainKind, role: DomainRole) -> Domain {
         Domain {
             name: name.to_string(),
+            doc: None,
             kind,
             role,
             marks: vec![],
+            behavior: None,
+            schema: None,
             span: span(),
             source_path: None,
         }
@@ -621,6 +696,8 @@ mod tests {
     ) -> Phenomenon {
         Phenomenon {
             name: name.to_string(),
+            doc: None,
+            parameters: vec![],
             type_,
             from: reference(from),
             to: reference(to),
@@ -632,6 +709,7 @@ mod tests {
     fn interface(name: &str, connects: &[&str], shared_phenomena: Vec<Phenomenon>) -> Interface {
         Interface {
             name: name.to_string(),
+            doc: None,
             connects: connects.iter().map(|name| reference(name)).collect(),
             shared_phenomena,
             span: span(),
@@ -644,6 +722,8 @@ mod tests {
             name: "TraceabilityDogfood".to_string(),
             span: span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("Operator", DomainKind::Biddable, DomainRole::Given),
@@ -688,6 +768,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "DisplayState".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::InformationDisplay,
                     phenomena: vec![],
                     marks: vec![],
@@ -699,8 +784,13 @@ mod tests {
                 },
                 Requirement {
                     name: "StoreRecord".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::Transformation,
-                    phenomena: vec!["PersistRecord".to_string()],
+                    phenomena: vec![reference("Machine-Ledger.PersistRecord")],
                     marks: vec![],
                     constraint: "store transformed record".to_string(),
                     constrains: Some(reference("Ledger")),
@@ -712,6 +802,7 @@ mod tests {
             subproblems: vec![
                 Subproblem {
                     name: "DisplayFlow".to_string(),
+                    doc: None,
                     machine: Some(reference("Machine")),
                     participants: vec![
                         reference("Machine"),
@@ -724,6 +815,7 @@ mod tests {
                 },
                 Subproblem {
                     name: "StorageFlow".to_string(),
+                    doc: None,
                     machine: Some(reference("Machine")),
                     participants: vec![reference("Machine"), reference("Ledger")],
                     requirements: vec![reference("StoreRecord")],
@@ -733,6 +825,8 @@ mod tests {
             ],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         }
     }
 
@@ -786,6 +880,57 @@ mod tests {
         assert!(!impacted_from_sensor.contains("StoreRecord"));
     }
 
+    #[test]
+    fn requirement_nodes_key_on_stable_id() {
+        let mut problem = sample_problem();
+        problem.requirements[0].id = Some(reference("REQ-7"));
+
+        let graph = build_traceability_graph(&problem);
+
+        assert!(graph
+            .nodes()
+            .contains(&TraceEntity::Requirement("REQ-7".to_string())));
+        assert!(!graph
+            .nodes()
+            .contains(&TraceEntity::Requirement("DisplayState".to_string())));
+        assert!(graph.edges().contains(&TraceEdge {
+            from: TraceEntity::Subproblem("DisplayFlow".to_string()),
+            to: TraceEntity::Requirement("REQ-7".to_string()),
+            relation: TraceRelation::SubproblemIncludesRequirement,
+        }));
+    }
+
+    #[test]
+    fn explicit_requirement_phenomena_replace_domain_derived_links() {
+        let mut problem = sample_problem();
+        problem.interfaces[2].shared_phenomena.push(phenomenon(
+            "ExportRecord",
+            PhenomenonType::Value,
+            "Ledger",
+            "Machine",
+            "Ledger",
+        ));
+        let export_record = TraceEntity::Phenomenon {
+            interface: "Machine-Ledger".to_string(),
+            name: "ExportRecord".to_string(),
+        };
+
+        let graph = build_traceability_graph(&problem);
+        assert!(!graph.edges().contains(&TraceEdge {
+            from: TraceEntity::Requirement("StoreRecord".to_string()),
+            to: export_record.clone(),
+            relation: TraceRelation::RequirementTouchesPhenomenon,
+        }));
+
+        problem.requirements[1].phenomena.clear();
+        let graph = build_traceability_graph(&problem);
+        assert!(graph.edges().contains(&TraceEdge {
+            from: TraceEntity::Requirement("StoreRecord".to_string()),
+            to: export_record,
+            relation: TraceRelation::RequirementTouchesPhenomenon,
+        }));
+    }
+
     #[test]
     fn renders_traceability_markdown_with_impact_section() {
         let problem = sample_problem();
@@ -804,6 +949,20 @@ mod tests {
         assert!(markdown.contains("sysml.block:sysml.block.sensor"));
     }
 
+    #[test]
+    fn renders_documentation_section_for_documented_entities() {
+        let mut problem = sample_problem();
+        let markdown = generate_traceability_markdown(&problem, &[], 2);
+        assert!(!markdown.contains("## Documentation"));
+
+        problem.requirements[0].doc = Some("Shows the\nsensor state.".to_string());
+        let markdown = generate_traceability_markdown(&problem, &[], 2);
+        assert!(markdown.contains(&format!(
+            "## Documentation\n- `requirement:{}`: Shows the sensor state.\n",
+            problem.requirements[0].name
+        )));
+    }
+
     #[test]
     fn renders_traceability_csv_with_edges_and_impact_rows() {
         let problem = sample_problem();
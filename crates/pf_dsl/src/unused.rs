@@ -0,0 +1,132 @@
+use crate::ast::*;
+use crate::traceability::{build_traceability_graph, TraceEntity, TraceabilityGraph};
+use crate::validator::{extract_interface_vocab_tokens, ValidationError};
+use std::collections::BTreeSet;
+
+// Declarations nothing else in the model uses. The findings are warnings that
+// `lints::lint` reports next to `validate`; a model under construction is full
+// of them, so they are not part of validation itself. Only declarations of the
+// entry file are reported: an imported library is used through the parts the
+// model needs, not all of it.
+pub fn check_unused(problem: &Problem) -> Result<(), Vec<ValidationError>> {
+    let graph = build_traceability_graph(problem);
+    let mut errors = Vec::new();
+    let in_entry = |span: &Span| span.file == FileId::default();
+
+    for domain in problem
+        .domains
+        .iter()
+        .filter(|domain| in_entry(&domain.span))
+    {
+        let on_interface = graph
+            .neighbors(&TraceEntity::Domain(domain.name.clone()))
+            .iter()
+            .any(|entity| matches!(entity, TraceEntity::Interface(_)));
+        if domain.role == DomainRole::Given && !on_interface {
+            errors.push(ValidationError::OrphanDomain(
+                domain.name.clone(),
+                domain.span,
+            ));
+        }
+    }
+
+    let vocabulary: BTreeSet<String> = problem
+        .assertion_sets
+        .iter()
+        .flat_map(|set| &set.assertions)
+        .flat_map(|assertion| extract_interface_vocab_tokens(&assertion.text))
+        .collect();
+    let subproblem_domains: Vec<BTreeSet<TraceEntity>> = problem
+        .subproblems
+        .iter()
+        .map(|subproblem| domains_of(&graph, &TraceEntity::Subproblem(subproblem.name.clone())))
+        .collect();
+    for interface in problem
+        .interfaces
+        .iter()
+        .filter(|interface| in_entry(&interface.span))
+    {
+        for phenomenon in &interface.shared_phenomena {
+            let entity = TraceEntity::Phenomenon {
+                interface: interface.name.clone(),
+                name: phenomenon.name.clone(),
+            };
+            let by_requirement = graph
+                .neighbors(&entity)
+                .iter()
+                .any(|entity| matches!(entity, TraceEntity::Requirement(_)));
+            let by_assertion = vocabulary.contains(&phenomenon.name)
+                || vocabulary.contains(&format!("{}.{}", interface.name, phenomenon.name));
+            let endpoints = domains_of(&graph, &entity);
+            let by_subproblem = subproblem_domains
+                .iter()
+                .any(|domains| endpoints.is_subset(domains));
+            if !by_requirement && !by_assertion && !by_subproblem {
+                errors.push(ValidationError::UnusedPhenomenon(
+                    phenomenon.name.clone(),
+                    interface.name.clone(),
+                    phenomenon.span,
+                ));
+            }
+        }
+    }
+
+    let argued: BTreeSet<&str> = problem
+        .correctness_arguments
+        .iter()
+        .flat_map(|argument| {
+            [
+                argument.specification_set.as_str(),
+                argument.world_set.as_str(),
+                argument.requirement_set.as_str(),
+            ]
+        })
+        .collect();
+    for set in &problem.assertion_sets {
+        if in_entry(&set.span) && !argued.contains(set.name.as_str()) {
+            errors.push(ValidationError::UnusedAssertionSet(
+                set.name.clone(),
+                set.span,
+            ));
+        }
+    }
+
+    for (subproblem, domains) in problem.subproblems.iter().zip(&subproblem_domains) {
+        if !in_entry(&subproblem.span) {
+            continue;
+        }
+        for participant in &subproblem.participants {
+            let participant_entity = TraceEntity::Domain(participant.name.clone());
+            let shares_interface = graph
+                .neighbors(&participant_entity)
+                .iter()
+                .filter(|entity| matches!(entity, TraceEntity::Interface(_)))
+                .any(|interface| {
+                    domains_of(&graph, interface)
+                        .iter()
+                        .any(|domain| *domain != participant_entity && domains.contains(domain))
+                });
+            if !shares_interface {
+                errors.push(ValidationError::DisconnectedSubproblemParticipant(
+                    participant.name.clone(),
+                    subproblem.name.clone(),
+                    participant.span,
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn domains_of(graph: &TraceabilityGraph, entity: &TraceEntity) -> BTreeSet<TraceEntity> {
+    graph
+        .neighbors(entity)
+        .into_iter()
+        .filter(|entity| matches!(entity, TraceEntity::Domain(_)))
+        .collect()
+}
@@ -1,4 +1,12 @@
 use crate::ast::*;
+use crate::composition::COMPOSITION_DECISION_MARK;
+use crate::fixes::{fixes_for, Fix};
+use crate::frames::builtin_frames;
+use crate::language::{
+    domain_kind_keyword, domain_role_keyword, frame_type_name, parse_domain_kind,
+    phenomenon_type_keyword, DOMAIN_KINDS, PRIMITIVE_TYPES,
+};
+use crate::rule_catalog::{rule_for_variant, Rule, Severity};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -6,7 +14,7 @@ use thiserror::Error;
 const FORMAL_ARGUMENT_MARK: &str = "formal.argument";
 const MDA_LAYER_MARK: &str = "mda.layer";
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum ValidationError {
     #[error("Domain '{0}' referenced in interface '{1}' but not defined.")]
     UndefinedDomainInInterface(String, String, Span, usize),
@@ -60,16 +68,181 @@ pub enum ValidationError {
         "Specification assertion set '{0}' references non-shared interface vocabulary '{1}'. Use [[Interface.Phenomenon]] from declared shared phenomena."
     )]
     InvalidSpecificationVocabulary(String, String, Span),
+    #[error(
+        "World property set '{0}' references '{1}', which is neither shared interface vocabulary nor internal to a domain. Use [[Interface.Phenomenon]] or [[Domain.State]]."
+    )]
+    InvalidWorldVocabulary(String, String, Span),
+    #[error(
+        "Requirement assertion set '{0}' references '{1}', which is not a phenomenon of a domain its requirements constrain or reference."
+    )]
+    InvalidRequirementVocabulary(String, String, Span),
+    #[error(
+        "Requirement assertion set '{0}' is not tied to any requirement: no requirement's @formal.argument names a correctness argument that proves it."
+    )]
+    UntiedRequirementAssertions(String, Span),
     #[error("Domain '{0}' has invalid mark contract: {1}")]
     InvalidDomainMark(String, String, Span),
     #[error("Requirement '{0}' has invalid mark contract: {1}")]
     InvalidRequirementMark(String, String, Span),
+    #[error("Phenomenon '{0}' referenced in requirement '{1}' but not defined.")]
+    UndefinedPhenomenonInRequirement(String, String, Span),
+    #[error("Duplicate type definition: '{0}'")]
+    DuplicateType(String, Span, usize),
+    #[error("Type '{0}' referenced in '{1}' but not defined.")]
+    UndefinedType(String, String, Span),
+    #[error("Type '{0}' is invalid: {1}")]
+    InvalidTypeDefinition(String, String, Span),
+    #[error("Behavior of domain '{0}' is invalid: {1}")]
+    InvalidBehavior(String, String, Span),
+    #[error("Domain '{0}' behavior {2}: '{1}'")]
+    BehaviorPhenomenonMismatch(String, String, String, Span),
+    #[error("Schema of domain '{0}' is invalid: {1}")]
+    InvalidSchema(String, String, Span),
+    #[error(
+        "Phenomenon '{0}' from lexical domain '{1}' does not refer to a declared schema entity or field."
+    )]
+    UndeclaredSchemaField(String, String, Span),
+    #[error("Duplicate requirement id: '{0}'")]
+    DuplicateRequirementId(String, Span, usize),
+    #[error("Duplicate frame definition: '{0}'")]
+    DuplicateFrame(String, Span, usize),
+    #[error("Frame '{0}' is invalid: {1}")]
+    InvalidFrameDeclaration(String, String, Span),
+    #[error("Import cycle: {0}")]
+    ImportCycle(String, Span),
+    #[error("Redundant import of \"{0}\": already imported at {1}")]
+    RedundantImport(String, String, Span),
+    #[error("Domain '{0}' is not connected by any interface.")]
+    OrphanDomain(String, Span),
+    #[error("Phenomenon '{0}' in interface '{1}' is not used by any requirement, assertion or subproblem.")]
+    UnusedPhenomenon(String, String, Span),
+    #[error("Assertion set '{0}' is not used by any correctness argument.")]
+    UnusedAssertionSet(String, Span),
+    #[error(
+        "Participant '{0}' of subproblem '{1}' shares no interface with the other participants."
+    )]
+    DisconnectedSubproblemParticipant(String, String, Span),
+    #[error("Requirement '{0}' with frame '{1}' lacks the frame's phenomena: {2}")]
+    FramePhenomenaMismatch(String, String, String, Span),
+    #[error("Composition concern for '{0}': {1}")]
+    CompositionConcern(String, String, Span),
+    #[error("Suppression 'pf-allow({0})' has no effect: {1}")]
+    InvalidSuppression(String, String, Span),
+    #[error(
+        "Requirement '{0}' is in no subproblem, so none of the problem's machines answers for it."
+    )]
+    RequirementWithoutMachine(String, Span),
 }
 
-#[derive(Debug)]
+// Findings that leave a reference pointing at nothing. Exports would draw the
+// missing declaration, so no suppression or project level downgrades them.
+pub const UNRESOLVED_REFERENCE_VARIANTS: [&str; 6] = [
+    "UndefinedDomainInInterface",
+    "UndefinedDomainInRequirement",
+    "UndefinedDomainInSubproblem",
+    "UndefinedRequirementInSubproblem",
+    "UndefinedPhenomenonInRequirement",
+    "UndefinedType",
+];
+
+impl ValidationError {
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ValidationError::UndefinedDomainInInterface(..) => "UndefinedDomainInInterface",
+            ValidationError::UndefinedDomainInRequirement(..) => "UndefinedDomainInRequirement",
+            ValidationError::InvalidFrameDomain(..) => "InvalidFrameDomain",
+            ValidationError::DuplicateDomain(..) => "DuplicateDomain",
+            ValidationError::DuplicateInterface(..) => "DuplicateInterface",
+            ValidationError::DuplicateRequirement(..) => "DuplicateRequirement",
+            ValidationError::MissingConnection(..) => "MissingConnection",
+            ValidationError::InvalidCausality(..) => "InvalidCausality",
+            ValidationError::MissingRequiredField(..) => "MissingRequiredField",
+            ValidationError::UnsupportedFrame(..) => "UnsupportedFrame",
+            ValidationError::InvalidDomainRole(..) => "InvalidDomainRole",
+            ValidationError::InterfaceInsufficientConnections(..) => {
+                "InterfaceInsufficientConnections"
+            }
+            ValidationError::InterfaceWithoutPhenomena(..) => "InterfaceWithoutPhenomena",
+            ValidationError::InterfaceControllerMismatch(..) => "InterfaceControllerMismatch",
+            ValidationError::RequirementReferencesMachine(..) => "RequirementReferencesMachine",
+            ValidationError::MissingSubproblemField(..) => "MissingSubproblemField",
+            ValidationError::UndefinedDomainInSubproblem(..) => "UndefinedDomainInSubproblem",
+            ValidationError::UndefinedRequirementInSubproblem(..) => {
+                "UndefinedRequirementInSubproblem"
+            }
+            ValidationError::DuplicateSubproblem(..) => "DuplicateSubproblem",
+            ValidationError::InvalidSubproblem(..) => "InvalidSubproblem",
+            ValidationError::DuplicateAssertionSet(..) => "DuplicateAssertionSet",
+            ValidationError::EmptyAssertionSet(..) => "EmptyAssertionSet",
+            ValidationError::InvalidCorrectnessArgument(..) => "InvalidCorrectnessArgument",
+            ValidationError::DuplicateCorrectnessArgument(..) => "DuplicateCorrectnessArgument",
+            ValidationError::InvalidSpecificationVocabulary(..) => "InvalidSpecificationVocabulary",
+            ValidationError::InvalidWorldVocabulary(..) => "InvalidWorldVocabulary",
+            ValidationError::InvalidRequirementVocabulary(..) => "InvalidRequirementVocabulary",
+            ValidationError::UntiedRequirementAssertions(..) => "UntiedRequirementAssertions",
+            ValidationError::InvalidDomainMark(..) => "InvalidDomainMark",
+            ValidationError::InvalidRequirementMark(..) => "InvalidRequirementMark",
+            ValidationError::UndefinedPhenomenonInRequirement(..) => {
+                "UndefinedPhenomenonInRequirement"
+            }
+            ValidationError::DuplicateType(..) => "DuplicateType",
+            ValidationError::UndefinedType(..) => "UndefinedType",
+            ValidationError::InvalidTypeDefinition(..) => "InvalidTypeDefinition",
+            ValidationError::InvalidBehavior(..) => "InvalidBehavior",
+            ValidationError::BehaviorPhenomenonMismatch(..) => "BehaviorPhenomenonMismatch",
+            ValidationError::InvalidSchema(..) => "InvalidSchema",
+            ValidationError::UndeclaredSchemaField(..) => "UndeclaredSchemaField",
+            ValidationError::DuplicateRequirementId(..) => "DuplicateRequirementId",
+            ValidationError::DuplicateFrame(..) => "DuplicateFrame",
+            ValidationError::InvalidFrameDeclaration(..) => "InvalidFrameDeclaration",
+            ValidationError::ImportCycle(..) => "ImportCycle",
+            ValidationError::RedundantImport(..) => "RedundantImport",
+            ValidationError::OrphanDomain(..) => "OrphanDomain",
+            ValidationError::UnusedPhenomenon(..) => "UnusedPhenomenon",
+            ValidationError::UnusedAssertionSet(..) => "UnusedAssertionSet",
+            ValidationError::DisconnectedSubproblemParticipant(..) => {
+                "DisconnectedSubproblemParticipant"
+            }
+            ValidationError::FramePhenomenaMismatch(..) => "FramePhenomenaMismatch",
+            ValidationError::CompositionConcern(..) => "CompositionConcern",
+            ValidationError::InvalidSuppression(..) => "InvalidSuppression",
+            ValidationError::RequirementWithoutMachine(..) => "RequirementWithoutMachine",
+        }
+    }
+
+    pub fn rule_id(&self) -> &'static str {
+        &self.rule().rule_id
+    }
+
+    // Every variant has a catalog entry; the metamodel contract tests enforce it.
+    pub fn rule(&self) -> &'static Rule {
+        rule_for_variant(self.variant_name())
+            .unwrap_or_else(|| panic!("no catalog rule for {}", self.variant_name()))
+    }
+
+    pub fn leaves_reference_unresolved(&self) -> bool {
+        UNRESOLVED_REFERENCE_VARIANTS.contains(&self.variant_name())
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ValidationIssue {
     pub error: ValidationError,
     pub source_path: Option<PathBuf>,
+    pub rule_id: &'static str,
+    pub severity: Severity,
+    pub fixes: Vec<Fix>,
+}
+
+impl ValidationIssue {
+    pub fn is_fatal(&self, deny_warnings: bool) -> bool {
+        self.severity.is_fatal(deny_warnings)
+    }
+
+    pub fn help(&self) -> Option<String> {
+        let fix = self.fixes.first()?;
+        Some(format!("did you mean `{}`?", fix.replacement))
+    }
 }
 
 fn is_connected(problem: &Problem, domain1: &str, domain2: &str) -> bool {
@@ -89,10 +262,50 @@ fn find_domain<'a>(problem: &'a Problem, name: &str) -> Option<&'a Domain> {
     problem.domains.iter().find(|domain| domain.name == name)
 }
 
-fn connected_to_machine(problem: &Problem, domain_name: &str) -> bool {
-    problem.domains.iter().any(|domain| {
-        domain.role == DomainRole::Machine && is_connected(problem, domain_name, &domain.name)
-    })
+fn connected_to_machine(problem: &Problem, machines: &[&str], domain_name: &str) -> bool {
+    machines
+        .iter()
+        .any(|machine| is_connected(problem, domain_name, machine))
+}
+
+// A problem with several machines gives each one its own subproblems, and a
+// requirement answers to the machines of the subproblems that include it. A
+// requirement outside every subproblem has no machine there (`None`).
+fn requirement_machines<'a>(problem: &'a Problem, req: &Requirement) -> Option<Vec<&'a str>> {
+    let machines: Vec<&str> = problem
+        .domains
+        .iter()
+        .filter(|domain| is_machine(domain))
+        .map(|domain| domain.name.as_str())
+        .collect();
+    if machines.len() < 2 {
+        return Some(machines);
+    }
+    let including: Vec<&Subproblem> = problem
+        .subproblems
+        .iter()
+        .filter(|subproblem| {
+            subproblem
+                .requirements
+                .iter()
+                .any(|name| req.is_named(&name.name))
+        })
+        .collect();
+    if including.is_empty() {
+        return None;
+    }
+    let own: Vec<&str> = including
+        .iter()
+        .filter_map(|subproblem| subproblem.machine.as_ref())
+        .map(|machine| machine.name.as_str())
+        .filter(|machine| machines.contains(machine))
+        .collect();
+    // subproblems with a missing or invalid machine are reported on their own
+    if own.is_empty() {
+        Some(machines)
+    } else {
+        Some(own)
+    }
 }
 
 fn shared_interface_vocabulary(problem: &Problem) -> HashSet<String> {
@@ -105,7 +318,93 @@ fn shared_interface_vocabulary(problem: &Problem) -> HashSet<String> {
     vocabulary
 }
 
-fn extract_interface_vocab_tokens(assertion: &str) -> Vec<String> {
+// `Domain.State` for behavior states, `Domain.Entity` and `Domain.Entity.field` for
+// schema entries: phenomena a domain has without sharing them.
+fn domain_internal_vocabulary(domain: &Domain) -> Vec<String> {
+    let mut vocabulary = Vec::new();
+    if let Some(behavior) = &domain.behavior {
+        for state in &behavior.states {
+            vocabulary.push(format!("{}.{}", domain.name, state.name));
+        }
+    }
+    if let Some(schema) = &domain.schema {
+        for entity in &schema.entities {
+            vocabulary.push(format!("{}.{}", domain.name, entity.name));
+            for field in &entity.fields {
+                vocabulary.push(format!("{}.{}.{}", domain.name, entity.name, field.name));
+            }
+        }
+    }
+    vocabulary
+}
+
+// A requirement assertion set speaks for the requirements whose `@formal.argument`
+// proves it, and may only use phenomena of the domains they constrain or
+// reference, leaving out the machine's own interfaces. Sets no requirement argues
+// for are not tied to any domain.
+fn requirement_assertion_vocabulary(problem: &Problem, set_name: &str) -> Option<HashSet<String>> {
+    let arguments: HashSet<&str> = problem
+        .correctness_arguments
+        .iter()
+        .filter(|argument| argument.requirement_set == set_name)
+        .map(|argument| argument.name.as_str())
+        .collect();
+    let domains: HashSet<&str> = problem
+        .requirements
+        .iter()
+        .filter(|requirement| {
+            requirement_formal_argument_mark(requirement)
+                .is_some_and(|(argument, _)| arguments.contains(argument.as_str()))
+        })
+        .flat_map(|requirement| requirement.constrains.iter().chain(&requirement.reference))
+        .map(|domain| domain.name.as_str())
+        .collect();
+    if domains.is_empty() {
+        return None;
+    }
+
+    let machines: HashSet<&str> = problem
+        .domains
+        .iter()
+        .filter(|domain| is_machine(domain))
+        .map(|domain| domain.name.as_str())
+        .collect();
+    let mut vocabulary = HashSet::new();
+    for interface in &problem.interfaces {
+        if interface
+            .connects
+            .iter()
+            .any(|domain| domains.contains(domain.name.as_str()))
+            && !interface
+                .connects
+                .iter()
+                .any(|domain| machines.contains(domain.name.as_str()))
+        {
+            for phenomenon in &interface.shared_phenomena {
+                vocabulary.insert(format!("{}.{}", interface.name, phenomenon.name));
+            }
+        }
+    }
+    vocabulary.extend(
+        problem
+            .domains
+            .iter()
+            .filter(|domain| domains.contains(domain.name.as_str()))
+            .flat_map(domain_internal_vocabulary),
+    );
+    Some(vocabulary)
+}
+
+fn shared_phenomenon_names(problem: &Problem) -> HashSet<String> {
+    problem
+        .interfaces
+        .iter()
+        .flat_map(|interface| interface.shared_phenomena.iter())
+        .map(|phenomenon| phenomenon.name.clone())
+        .collect()
+}
+
+pub(crate) fn extract_interface_vocab_tokens(assertion: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut cursor = 0;
 
@@ -125,6 +424,134 @@ fn extract_interface_vocab_tokens(assertion: &str) -> Vec<String> {
     tokens
 }
 
+// A behavior may only react to phenomena the domain receives and only emit
+// phenomena it controls, so it stays consistent with the interface diagram.
+fn validate_behavior(problem: &Problem, domain: &Domain, errors: &mut Vec<ValidationError>) {
+    let Some(behavior) = &domain.behavior else {
+        return;
+    };
+    if domain.kind != DomainKind::Causal || domain.role == DomainRole::Machine {
+        errors.push(ValidationError::InvalidBehavior(
+            domain.name.clone(),
+            "behavior is only allowed on causal given or designed domains".to_string(),
+            behavior.span,
+        ));
+        return;
+    }
+    if behavior.states.is_empty() {
+        errors.push(ValidationError::InvalidBehavior(
+            domain.name.clone(),
+            "behavior declares no states".to_string(),
+            behavior.span,
+        ));
+    }
+
+    let shared = || {
+        problem
+            .interfaces
+            .iter()
+            .flat_map(|interface| &interface.shared_phenomena)
+    };
+    for transition in &behavior.transitions {
+        for state in [&transition.from, &transition.to] {
+            if !behavior.states.iter().any(|s| s.name == state.name) {
+                errors.push(ValidationError::InvalidBehavior(
+                    domain.name.clone(),
+                    format!(
+                        "transition on '{}' uses undeclared state '{}'",
+                        transition.trigger.name, state.name
+                    ),
+                    state.span,
+                ));
+            }
+        }
+        if !shared().any(|p| p.name == transition.trigger.name && p.to.name == domain.name) {
+            errors.push(ValidationError::BehaviorPhenomenonMismatch(
+                domain.name.clone(),
+                transition.trigger.name.clone(),
+                "consumes a phenomenon it does not receive".to_string(),
+                transition.trigger.span,
+            ));
+        }
+        for emitted in &transition.emits {
+            if !shared().any(|p| p.name == emitted.name && p.controlled_by.name == domain.name) {
+                errors.push(ValidationError::BehaviorPhenomenonMismatch(
+                    domain.name.clone(),
+                    emitted.name.clone(),
+                    "emits a phenomenon it does not control".to_string(),
+                    emitted.span,
+                ));
+            }
+        }
+    }
+}
+
+// A `value`/`state` phenomenon sourced from a lexical domain with a schema must
+// name an entity, a field, or an entity followed by one of its fields
+// (`OrderTotal` for `Order.total`), ignoring case.
+fn schema_declares(schema: &Schema, phenomenon: &str) -> bool {
+    let phenomenon = phenomenon.to_lowercase();
+    schema.entities.iter().any(|entity| {
+        let entity_name = entity.name.to_lowercase();
+        entity_name == phenomenon
+            || entity.fields.iter().any(|field| {
+                let field_name = field.name.to_lowercase();
+                field_name == phenomenon || format!("{entity_name}{field_name}") == phenomenon
+            })
+    })
+}
+
+fn validate_schema(problem: &Problem, domain: &Domain, errors: &mut Vec<ValidationError>) {
+    let Some(schema) = &domain.schema else {
+        return;
+    };
+    if domain.kind != DomainKind::Lexical {
+        errors.push(ValidationError::InvalidSchema(
+            domain.name.clone(),
+            "schema is only allowed on lexical domains".to_string(),
+            schema.span,
+        ));
+        return;
+    }
+
+    for entity in &schema.entities {
+        for field in &entity.fields {
+            let type_name = field.type_ref.name.as_str();
+            let defined = PRIMITIVE_TYPES.contains(&type_name)
+                || problem.types.iter().any(|t| t.name == type_name)
+                || schema.entities.iter().any(|other| other.name == type_name);
+            if !defined {
+                errors.push(ValidationError::UndefinedType(
+                    field.type_ref.name.clone(),
+                    format!("{}.{}.{}", domain.name, entity.name, field.name),
+                    field.type_ref.span,
+                ));
+            }
+        }
+    }
+
+    for phenomenon in problem
+        .interfaces
+        .iter()
+        .flat_map(|interface| &interface.shared_phenomena)
+    {
+        let carries_data = matches!(
+            phenomenon.type_,
+            PhenomenonType::Value | PhenomenonType::State
+        );
+        if carries_data
+            && phenomenon.from.name == domain.name
+            && !schema_declares(schema, &phenomenon.name)
+        {
+            errors.push(ValidationError::UndeclaredSchemaField(
+                phenomenon.name.clone(),
+                domain.name.clone(),
+                phenomenon.span,
+            ));
+        }
+    }
+}
+
 fn validate_domain_marks(domain: &Domain, errors: &mut Vec<ValidationError>) {
     if domain.marks.is_empty() {
         return;
@@ -183,14 +610,14 @@ fn validate_domain_marks(domain: &Domain, errors: &mut Vec<ValidationError>) {
             | "ddd.external_system"
             | "sysml.block"
             | "sysml.port"
-            | "sysml.signal" => {
-                if mark.value.is_some() {
-                    errors.push(ValidationError::InvalidDomainMark(
-                        domain.name.clone(),
-                        format!("mark '{}' does not accept a value", mark.name),
-                        mark.span,
-                    ));
-                }
+            | "sysml.signal"
+                if mark.value.is_some() =>
+            {
+                errors.push(ValidationError::InvalidDomainMark(
+                    domain.name.clone(),
+                    format!("mark '{}' does not accept a value", mark.name),
+                    mark.span,
+                ));
             }
             _ => {}
         }
@@ -226,6 +653,7 @@ fn validate_requirement_marks(requirement: &Requirement, errors: &mut Vec<Valida
         "ddd.application_service",
         FORMAL_ARGUMENT_MARK,
         MDA_LAYER_MARK,
+        COMPOSITION_DECISION_MARK,
     ];
     let mut seen_marks = HashSet::new();
 
@@ -249,14 +677,12 @@ fn validate_requirement_marks(requirement: &Requirement, errors: &mut Vec<Valida
         }
 
         match mark.name.as_str() {
-            "sysml.requirement" => {
-                if mark.value.is_some() {
-                    errors.push(ValidationError::InvalidRequirementMark(
-                        requirement.name.clone(),
-                        "mark 'sysml.requirement' does not accept a value".to_string(),
-                        mark.span,
-                    ));
-                }
+            "sysml.requirement" if mark.value.is_some() => {
+                errors.push(ValidationError::InvalidRequirementMark(
+                    requirement.name.clone(),
+                    "mark 'sysml.requirement' does not accept a value".to_string(),
+                    mark.span,
+                ));
             }
             "ddd.application_service" => {
                 let is_missing = mark
@@ -273,7 +699,7 @@ fn validate_requirement_marks(requirement: &Requirement, errors: &mut Vec<Valida
                     ));
                 }
             }
-            FORMAL_ARGUMENT_MARK => {
+            FORMAL_ARGUMENT_MARK | COMPOSITION_DECISION_MARK => {
                 let is_missing = mark
                     .value
                     .as_ref()
@@ -282,10 +708,7 @@ fn validate_requirement_marks(requirement: &Requirement, errors: &mut Vec<Valida
                 if is_missing {
                     errors.push(ValidationError::InvalidRequirementMark(
                         requirement.name.clone(),
-                        format!(
-                            "mark '{}' requires non-empty string value",
-                            FORMAL_ARGUMENT_MARK
-                        ),
+                        format!("mark '{}' requires non-empty string value", mark.name),
                         mark.span,
                     ));
                 }
@@ -325,6 +748,387 @@ fn requirement_formal_argument_mark(requirement: &Requirement) -> Option<(String
     })
 }
 
+// Records that contain themselves, directly or through other records, have no finite value.
+fn record_cycle<'a>(
+    problem: &'a Problem,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+) -> Option<Vec<&'a str>> {
+    if let Some(position) = path.iter().position(|seen| *seen == name) {
+        let mut cycle = path[position..].to_vec();
+        cycle.push(name);
+        return Some(cycle);
+    }
+    let data_type = problem
+        .types
+        .iter()
+        .find(|data_type| data_type.name == name)?;
+    let TypeDefinition::Record(fields) = &data_type.definition else {
+        return None;
+    };
+    path.push(name);
+    for field in fields {
+        if let Some(cycle) = record_cycle(problem, &field.type_ref.name, path) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    None
+}
+
+fn validate_types(problem: &Problem, errors: &mut Vec<ValidationError>) {
+    let mut type_names: HashSet<&str> = PRIMITIVE_TYPES.iter().copied().collect();
+    for (index, data_type) in problem.types.iter().enumerate() {
+        if !type_names.insert(data_type.name.as_str()) {
+            errors.push(ValidationError::DuplicateType(
+                data_type.name.clone(),
+                data_type.span,
+                index,
+            ));
+        }
+    }
+
+    let defined = |name: &str| {
+        PRIMITIVE_TYPES.contains(&name) || problem.types.iter().any(|t| t.name == name)
+    };
+
+    for data_type in &problem.types {
+        match &data_type.definition {
+            TypeDefinition::Record(fields) => {
+                for field in fields {
+                    if !defined(&field.type_ref.name) {
+                        errors.push(ValidationError::UndefinedType(
+                            field.type_ref.name.clone(),
+                            format!("{}.{}", data_type.name, field.name),
+                            field.type_ref.span,
+                        ));
+                    }
+                }
+                if let Some(cycle) = record_cycle(problem, &data_type.name, &mut Vec::new()) {
+                    if cycle.first() == Some(&data_type.name.as_str()) {
+                        errors.push(ValidationError::InvalidTypeDefinition(
+                            data_type.name.clone(),
+                            format!("record contains itself via {}", cycle.join(" -> ")),
+                            data_type.span,
+                        ));
+                    }
+                }
+            }
+            TypeDefinition::Range { min, max, .. } if min > max => {
+                errors.push(ValidationError::InvalidTypeDefinition(
+                    data_type.name.clone(),
+                    format!("range minimum {min} is greater than maximum {max}"),
+                    data_type.span,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    for interface in &problem.interfaces {
+        for phenomenon in &interface.shared_phenomena {
+            for parameter in &phenomenon.parameters {
+                if !defined(&parameter.type_ref.name) {
+                    errors.push(ValidationError::UndefinedType(
+                        parameter.type_ref.name.clone(),
+                        format!("{}.{}", interface.name, phenomenon.name),
+                        parameter.type_ref.span,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn participant_name(participant: &FrameParticipant) -> &'static str {
+    match participant {
+        FrameParticipant::Constrains => "constrains",
+        FrameParticipant::Reference => "reference",
+        FrameParticipant::Machine => "machine",
+    }
+}
+
+fn same_frame_rules(left: &Frame, right: &Frame) -> bool {
+    let slot_rules = |slot: &Option<FrameSlot>| {
+        slot.as_ref()
+            .map(|slot| (slot.kinds.clone(), slot.roles.clone()))
+    };
+    let flow_rules = |frame: &Frame| {
+        frame
+            .flows
+            .iter()
+            .map(|flow| {
+                (
+                    flow.types.clone(),
+                    flow.from.clone(),
+                    flow.to.clone(),
+                    flow.controlled_by.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    slot_rules(&left.constrains) == slot_rules(&right.constrains)
+        && slot_rules(&left.reference) == slot_rules(&right.reference)
+        && left.connects == right.connects
+        && flow_rules(left) == flow_rules(right)
+}
+
+fn validate_frame_declaration(
+    frame: &Frame,
+    builtin_frames: &[Frame],
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(builtin) = builtin_frames.iter().find(|b| b.name == frame.name) {
+        if !same_frame_rules(frame, builtin) {
+            errors.push(ValidationError::InvalidFrameDeclaration(
+                frame.name.clone(),
+                "redefines a built-in frame with different rules".to_string(),
+                frame.span,
+            ));
+        }
+    }
+
+    let declares = |participant: &FrameParticipant| match participant {
+        FrameParticipant::Constrains => frame.constrains.is_some(),
+        FrameParticipant::Reference => frame.reference.is_some(),
+        FrameParticipant::Machine => true,
+    };
+    for participant in &frame.connects {
+        if *participant == FrameParticipant::Machine {
+            errors.push(ValidationError::InvalidFrameDeclaration(
+                frame.name.clone(),
+                "'connects' lists slots that must reach the machine, not 'machine' itself"
+                    .to_string(),
+                frame.span,
+            ));
+        } else if !declares(participant) {
+            errors.push(ValidationError::InvalidFrameDeclaration(
+                frame.name.clone(),
+                format!(
+                    "'connects' uses slot '{}' that the frame does not declare",
+                    participant_name(participant)
+                ),
+                frame.span,
+            ));
+        }
+    }
+    for flow in &frame.flows {
+        if flow.from == flow.to {
+            errors.push(ValidationError::InvalidFrameDeclaration(
+                frame.name.clone(),
+                format!(
+                    "phenomenon flow cannot start and end at '{}'",
+                    participant_name(&flow.from)
+                ),
+                flow.span,
+            ));
+        }
+        for participant in [&flow.from, &flow.to]
+            .into_iter()
+            .chain(flow.controlled_by.as_ref())
+        {
+            if !declares(participant) {
+                errors.push(ValidationError::InvalidFrameDeclaration(
+                    frame.name.clone(),
+                    format!(
+                        "phenomenon flow uses slot '{}' that the frame does not declare",
+                        participant_name(participant)
+                    ),
+                    flow.span,
+                ));
+            }
+        }
+    }
+}
+
+fn slot_mismatch(slot: &FrameSlot, label: &str, domain: &Domain) -> Option<String> {
+    let found = format!("{:?}/{:?}", domain.kind, domain.role);
+    if !slot.kinds.is_empty() && !slot.kinds.contains(&domain.kind) {
+        let excluded: Vec<&str> = DOMAIN_KINDS
+            .iter()
+            .copied()
+            .filter(|kind| !slot.kinds.contains(&parse_domain_kind(kind)))
+            .collect();
+        return Some(match (slot.kinds.as_slice(), excluded.as_slice()) {
+            ([kind], _) => format!(
+                "{label} domain '{}' should be {}, found {found}",
+                domain.name,
+                domain_kind_keyword(kind)
+            ),
+            (_, [excluded]) => format!("{label} domain '{}' cannot be {excluded}", domain.name),
+            _ => format!(
+                "{label} domain '{}' should be one of {}, found {found}",
+                domain.name,
+                slot.kinds
+                    .iter()
+                    .map(domain_kind_keyword)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        });
+    }
+    if !slot.roles.is_empty() && !slot.roles.contains(&domain.role) {
+        let roles = slot
+            .roles
+            .iter()
+            .map(domain_role_keyword)
+            .collect::<Vec<_>>()
+            .join(" or ");
+        return Some(format!(
+            "{label} domain '{}' should have role {roles}, found {found}",
+            domain.name
+        ));
+    }
+    None
+}
+
+// An empty `types` list accepts any type, and no `controllers` any controller.
+fn has_flow(
+    problem: &Problem,
+    types: &[PhenomenonType],
+    from: &[&str],
+    to: &[&str],
+    controllers: Option<&[&str]>,
+) -> bool {
+    problem.interfaces.iter().any(|interface| {
+        interface.shared_phenomena.iter().any(|phenomenon| {
+            (types.is_empty() || types.contains(&phenomenon.type_))
+                && from.contains(&phenomenon.from.name.as_str())
+                && to.contains(&phenomenon.to.name.as_str())
+                && controllers.is_none_or(|controllers| {
+                    controllers.contains(&phenomenon.controlled_by.name.as_str())
+                })
+        })
+    })
+}
+
+fn check_frame_fit<'a>(
+    problem: &Problem,
+    req: &'a Requirement,
+    req_index: usize,
+    frame: &Frame,
+    machines: &[&'a str],
+    errors: &mut Vec<ValidationError>,
+) {
+    let slots = [
+        (
+            FrameParticipant::Reference,
+            "reference",
+            &frame.reference,
+            &req.reference,
+        ),
+        (
+            FrameParticipant::Constrains,
+            "constrained",
+            &frame.constrains,
+            &req.constrains,
+        ),
+    ];
+
+    for (participant, _, slot, field) in &slots {
+        if slot.is_some() && field.is_none() {
+            errors.push(ValidationError::MissingRequiredField(
+                req.name.clone(),
+                participant_name(participant).to_string(),
+                req.span,
+            ));
+        }
+    }
+
+    for (participant, label, slot, field) in &slots {
+        let Some(field) = field else {
+            continue;
+        };
+        let Some(domain) = find_domain(problem, &field.name) else {
+            continue;
+        };
+        if let Some(message) = slot
+            .as_ref()
+            .and_then(|slot| slot_mismatch(slot, label, domain))
+        {
+            errors.push(ValidationError::InvalidFrameDomain(
+                req.name.clone(),
+                frame.name.clone(),
+                message,
+                field.span,
+            ));
+        }
+        if frame.connects.contains(participant)
+            && !connected_to_machine(problem, machines, &domain.name)
+        {
+            errors.push(ValidationError::MissingConnection(
+                domain.name.clone(),
+                "machine".to_string(),
+                frame.name.clone(),
+                req.span,
+                req_index,
+            ));
+        }
+    }
+
+    // Slots that are missing, undefined or cut off from the machine are reported
+    // above or elsewhere, so their flows are not checked.
+    let connected_slot = |field: &'a Option<Reference>| -> Vec<&'a str> {
+        field
+            .iter()
+            .filter(|field| find_domain(problem, &field.name).is_some())
+            .filter(|field| connected_to_machine(problem, machines, &field.name))
+            .map(|field| field.name.as_str())
+            .collect()
+    };
+    let participant_domains = |participant: &FrameParticipant| -> Vec<&str> {
+        match participant {
+            FrameParticipant::Constrains => connected_slot(&req.constrains),
+            FrameParticipant::Reference => connected_slot(&req.reference),
+            FrameParticipant::Machine => machines.to_vec(),
+        }
+    };
+    for flow in &frame.flows {
+        let from = participant_domains(&flow.from);
+        let to = participant_domains(&flow.to);
+        let controllers = flow.controlled_by.as_ref().map(participant_domains);
+        if from.is_empty()
+            || to.is_empty()
+            || controllers
+                .as_ref()
+                .is_some_and(|controllers| controllers.is_empty())
+        {
+            continue;
+        }
+        if has_flow(problem, &flow.types, &from, &to, controllers.as_deref()) {
+            continue;
+        }
+        let types: Vec<&str> = flow.types.iter().map(phenomenon_type_keyword).collect();
+        let mut message = format!(
+            "expected a shared {}phenomenon from {} '{}' to {} '{}'",
+            types
+                .iter()
+                .map(|type_| format!("{type_} "))
+                .collect::<Vec<_>>()
+                .join("or "),
+            participant_name(&flow.from),
+            from.join("', '"),
+            participant_name(&flow.to),
+            to.join("', '")
+        );
+        if let (Some(participant), Some(controllers)) = (&flow.controlled_by, &controllers) {
+            message.push_str(&format!(
+                " controlled by {} '{}'",
+                participant_name(participant),
+                controllers.join("', '")
+            ));
+        }
+        errors.push(ValidationError::FramePhenomenaMismatch(
+            req.name.clone(),
+            frame.name.clone(),
+            message,
+            req.span,
+        ));
+    }
+}
+
+// Returns every finding, including warnings; `validate_with_sources` attaches the
+// catalog severity that decides whether a finding fails the model.
 pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
     let mut errors = vec![];
     let mut defined_domains = HashSet::new();
@@ -353,14 +1157,28 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
 
         validate_domain_marks(domain, &mut errors);
+        validate_behavior(problem, domain, &mut errors);
+        validate_schema(problem, domain, &mut errors);
     }
 
     if machine_count > 1 {
-        errors.push(ValidationError::InvalidDomainRole(
-            "<problem>".to_string(),
-            format!("expected at most one machine domain, found {machine_count}"),
-            problem.span,
-        ));
+        let subproblem_machines: HashSet<&str> = problem
+            .subproblems
+            .iter()
+            .filter_map(|subproblem| subproblem.machine.as_ref())
+            .map(|machine| machine.name.as_str())
+            .collect();
+        for domain in problem.domains.iter().filter(|domain| is_machine(domain)) {
+            if !subproblem_machines.contains(domain.name.as_str()) {
+                errors.push(ValidationError::InvalidDomainRole(
+                    domain.name.clone(),
+                    format!(
+                        "one of {machine_count} machine domains but the machine of no subproblem"
+                    ),
+                    domain.span,
+                ));
+            }
+        }
     } else if machine_count == 0 && !problem.requirements.is_empty() {
         errors.push(ValidationError::InvalidDomainRole(
             "<problem>".to_string(),
@@ -508,6 +1326,25 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    // an ID must not be reused or shadow another requirement's name
+    let mut requirement_ids = HashSet::new();
+    for (index, req) in problem.requirements.iter().enumerate() {
+        let Some(id) = &req.id else {
+            continue;
+        };
+        let shadows_name = problem
+            .requirements
+            .iter()
+            .any(|other| other.name == id.name && other.name != req.name);
+        if !requirement_ids.insert(id.name.clone()) || shadows_name {
+            errors.push(ValidationError::DuplicateRequirementId(
+                id.name.clone(),
+                id.span,
+                index,
+            ));
+        }
+    }
+
     for req in &problem.requirements {
         validate_requirement_marks(req, &mut errors);
 
@@ -532,6 +1369,25 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    let interface_vocabulary = shared_interface_vocabulary(problem);
+    let phenomenon_names = shared_phenomenon_names(problem);
+    for req in &problem.requirements {
+        for phenomenon_ref in &req.phenomena {
+            let is_defined = if phenomenon_ref.name.contains('.') {
+                interface_vocabulary.contains(&phenomenon_ref.name)
+            } else {
+                phenomenon_names.contains(&phenomenon_ref.name)
+            };
+            if !is_defined {
+                errors.push(ValidationError::UndefinedPhenomenonInRequirement(
+                    phenomenon_ref.name.clone(),
+                    req.name.clone(),
+                    phenomenon_ref.span,
+                ));
+            }
+        }
+    }
+
     let mut subproblem_names = HashSet::new();
     for (index, subproblem) in problem.subproblems.iter().enumerate() {
         if !subproblem_names.insert(subproblem.name.clone()) {
@@ -611,7 +1467,7 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
             if !problem
                 .requirements
                 .iter()
-                .any(|requirement| requirement.name == requirement_ref.name)
+                .any(|requirement| requirement.is_named(&requirement_ref.name))
             {
                 errors.push(ValidationError::UndefinedRequirementInSubproblem(
                     requirement_ref.name.clone(),
@@ -625,7 +1481,7 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
             if let Some(requirement) = problem
                 .requirements
                 .iter()
-                .find(|requirement| requirement.name == requirement_ref.name)
+                .find(|requirement| requirement.is_named(&requirement_ref.name))
             {
                 if let Some(constrains) = &requirement.constrains {
                     if !participant_names.contains(constrains.name.as_str()) {
@@ -672,20 +1528,44 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
     }
 
-    let interface_vocabulary = shared_interface_vocabulary(problem);
+    let world_vocabulary: HashSet<String> = interface_vocabulary
+        .iter()
+        .cloned()
+        .chain(problem.domains.iter().flat_map(domain_internal_vocabulary))
+        .collect();
     for assertion_set in &problem.assertion_sets {
-        if assertion_set.scope != AssertionScope::Specification {
-            continue;
-        }
+        let requirement_vocabulary;
+        let (vocabulary, invalid): (_, fn(String, String, Span) -> ValidationError) =
+            match assertion_set.scope {
+                AssertionScope::Specification => (
+                    &interface_vocabulary,
+                    ValidationError::InvalidSpecificationVocabulary,
+                ),
+                AssertionScope::WorldProperties => {
+                    (&world_vocabulary, ValidationError::InvalidWorldVocabulary)
+                }
+                AssertionScope::RequirementAssertions => {
+                    let Some(vocabulary) =
+                        requirement_assertion_vocabulary(problem, &assertion_set.name)
+                    else {
+                        errors.push(ValidationError::UntiedRequirementAssertions(
+                            assertion_set.name.clone(),
+                            assertion_set.span,
+                        ));
+                        continue;
+                    };
+                    requirement_vocabulary = vocabulary;
+                    (
+                        &requirement_vocabulary,
+                        ValidationError::InvalidRequirementVocabulary,
+                    )
+                }
+            };
 
         for assertion in &assertion_set.assertions {
             for token in extract_interface_vocab_tokens(&assertion.text) {
-                if !interface_vocabulary.contains(&token) {
-                    errors.push(ValidationError::InvalidSpecificationVocabulary(
-                        assertion_set.name.clone(),
-                        token,
-                        assertion.span,
-                    ));
+                if !vocabulary.contains(&token) {
+                    errors.push(invalid(assertion_set.name.clone(), token, assertion.span));
                 }
             }
         }
@@ -789,6 +1669,28 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    for requirement in &problem.requirements {
+        let decisions = requirement
+            .marks
+            .iter()
+            .filter(|mark| mark.name == COMPOSITION_DECISION_MARK);
+        for mark in decisions {
+            let names = mark.value.as_deref().unwrap_or_default().split(',');
+            for name in names.map(str::trim).filter(|name| !name.is_empty()) {
+                if problem.requirement(name).is_none() {
+                    errors.push(ValidationError::InvalidRequirementMark(
+                        requirement.name.clone(),
+                        format!(
+                            "mark '{}' references undefined requirement '{}'",
+                            COMPOSITION_DECISION_MARK, name
+                        ),
+                        mark.span,
+                    ));
+                }
+            }
+        }
+    }
+
     for req in &problem.requirements {
         if let Some(ref r) = req.reference {
             if let Some(domain) = problem.domains.iter().find(|d| d.name == r.name) {
@@ -823,9 +1725,24 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    validate_types(problem, &mut errors);
+
+    let builtin_frames = builtin_frames();
+    let mut frame_names = HashSet::new();
+    for (index, frame) in problem.frames.iter().enumerate() {
+        if !frame_names.insert(frame.name.as_str()) {
+            errors.push(ValidationError::DuplicateFrame(
+                frame.name.clone(),
+                frame.span,
+                index,
+            ));
+        }
+        validate_frame_declaration(frame, builtin_frames, &mut errors);
+    }
+
     for (req_index, req) in problem.requirements.iter().enumerate() {
-        match &req.frame {
-            FrameType::Custom(frame_name) if frame_name.is_empty() => {
+        if let FrameType::Custom(frame_name) = &req.frame {
+            if frame_name.is_empty() {
                 errors.push(ValidationError::MissingRequiredField(
                     req.name.clone(),
                     "frame".to_string(),
@@ -833,261 +1750,53 @@ pub fn validate(problem: &Problem) -> Result<(), Vec<ValidationError>> {
                 ));
                 continue;
             }
-            FrameType::Custom(frame_name) => {
-                errors.push(ValidationError::UnsupportedFrame(
-                    req.name.clone(),
-                    frame_name.clone(),
-                    req.span,
-                ));
-                continue;
-            }
-            _ => {}
         }
 
-        match req.frame {
-            FrameType::CommandedBehavior => {
-                if req.reference.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "reference".to_string(),
-                        req.span,
-                    ));
-                }
-                if req.constrains.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "constrains".to_string(),
-                        req.span,
-                    ));
-                }
-
-                if let Some(ref r) = req.reference {
-                    if let Some(domain) = find_domain(problem, &r.name) {
-                        if domain.kind != DomainKind::Biddable {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "CommandedBehavior".to_string(),
-                                format!(
-                                    "reference domain '{}' should be biddable, found {:?}/{:?}",
-                                    r.name, domain.kind, domain.role
-                                ),
-                                r.span,
-                            ));
-                        }
-
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "CommandedBehavior".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-            }
-            FrameType::RequiredBehavior => {
-                if req.constrains.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "constrains".to_string(),
-                        req.span,
-                    ));
-                }
-
-                if let Some(ref c) = req.constrains {
-                    if let Some(domain) = find_domain(problem, &c.name) {
-                        if domain.kind != DomainKind::Causal {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "RequiredBehavior".to_string(),
-                                format!(
-                                    "constrained domain '{}' should be causal, found {:?}/{:?}",
-                                    c.name, domain.kind, domain.role
-                                ),
-                                c.span,
-                            ));
-                        }
-
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "RequiredBehavior".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-            }
-            FrameType::InformationDisplay => {
-                if req.reference.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "reference".to_string(),
-                        req.span,
-                    ));
-                }
-                if req.constrains.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "constrains".to_string(),
-                        req.span,
-                    ));
-                }
-
-                if let Some(ref r) = req.reference {
-                    if let Some(domain) = find_domain(problem, &r.name) {
-                        if domain.kind != DomainKind::Biddable {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "InformationDisplay".to_string(),
-                                format!(
-                                    "reference domain '{}' should be biddable, found {:?}/{:?}",
-                                    r.name, domain.kind, domain.role
-                                ),
-                                r.span,
-                            ));
-                        }
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "InformationDisplay".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-
-                if let Some(ref c) = req.constrains {
-                    if let Some(domain) = find_domain(problem, &c.name) {
-                        if domain.kind == DomainKind::Biddable {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "InformationDisplay".to_string(),
-                                format!("constrained domain '{}' cannot be biddable", c.name),
-                                c.span,
-                            ));
-                        }
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "InformationDisplay".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-            }
-            FrameType::SimpleWorkpieces => {
-                if req.reference.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "reference".to_string(),
-                        req.span,
-                    ));
-                }
-                if req.constrains.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "constrains".to_string(),
-                        req.span,
-                    ));
-                }
-
-                if let Some(ref r) = req.reference {
-                    if let Some(domain) = find_domain(problem, &r.name) {
-                        if domain.kind != DomainKind::Biddable {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "SimpleWorkpieces".to_string(),
-                                format!(
-                                    "reference domain '{}' should be biddable, found {:?}/{:?}",
-                                    r.name, domain.kind, domain.role
-                                ),
-                                r.span,
-                            ));
-                        }
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "SimpleWorkpieces".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-
-                if let Some(ref c) = req.constrains {
-                    if let Some(domain) = find_domain(problem, &c.name) {
-                        if domain.kind != DomainKind::Lexical {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "SimpleWorkpieces".to_string(),
-                                format!(
-                                    "constrained domain '{}' should be lexical, found {:?}/{:?}",
-                                    c.name, domain.kind, domain.role
-                                ),
-                                c.span,
-                            ));
-                        }
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "SimpleWorkpieces".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-            }
-            FrameType::Transformation => {
-                if req.constrains.is_none() {
-                    errors.push(ValidationError::MissingRequiredField(
-                        req.name.clone(),
-                        "constrains".to_string(),
-                        req.span,
-                    ));
+        // Built-in frames always use the bundled rules, even if a model redeclares them.
+        let frame_name = frame_type_name(&req.frame);
+        let frame = builtin_frames
+            .iter()
+            .chain(problem.frames.iter())
+            .find(|frame| frame.name == frame_name);
+        match frame {
+            Some(frame) => match requirement_machines(problem, req) {
+                Some(machines) => {
+                    check_frame_fit(problem, req, req_index, frame, &machines, &mut errors)
                 }
+                None => errors.push(ValidationError::RequirementWithoutMachine(
+                    req.name.clone(),
+                    req.span,
+                )),
+            },
+            None => errors.push(ValidationError::UnsupportedFrame(
+                req.name.clone(),
+                frame_name.to_string(),
+                req.span,
+            )),
+        }
+    }
 
-                if let Some(ref c) = req.constrains {
-                    if let Some(domain) = find_domain(problem, &c.name) {
-                        if domain.kind != DomainKind::Lexical {
-                            errors.push(ValidationError::InvalidFrameDomain(
-                                req.name.clone(),
-                                "Transformation".to_string(),
-                                format!(
-                                    "constrained domain '{}' should be lexical, found {:?}/{:?}",
-                                    c.name, domain.kind, domain.role
-                                ),
-                                c.span,
-                            ));
-                        }
-                        if !connected_to_machine(problem, &domain.name) {
-                            errors.push(ValidationError::MissingConnection(
-                                domain.name.clone(),
-                                "machine".to_string(),
-                                "Transformation".to_string(),
-                                req.span,
-                                req_index,
-                            ));
-                        }
-                    }
-                }
-            }
-            _ => {}
+    // The resolver stops at the import that closes a cycle; report it with the chain.
+    for cycle in &problem.import_graph.cycles {
+        if let Some(&closing) = cycle.last() {
+            errors.push(ValidationError::ImportCycle(
+                problem.import_graph.describe_cycle(cycle, &problem.sources),
+                problem.import_graph.edges[closing].span,
+            ));
         }
     }
+    for &(repeated, first) in &problem.import_graph.redundant {
+        let repeated = &problem.import_graph.edges[repeated];
+        let first = &problem.import_graph.edges[first];
+        errors.push(ValidationError::RedundantImport(
+            repeated.path.clone(),
+            problem
+                .sources
+                .location(first.span)
+                .unwrap_or_else(|| first.from.display().to_string()),
+            repeated.span,
+        ));
+    }
 
     if errors.is_empty() {
         Ok(())
@@ -1101,9 +1810,11 @@ pub fn validation_error_span(error: &ValidationError) -> Span {
         ValidationError::UndefinedDomainInInterface(_, _, span, _)
         | ValidationError::UndefinedDomainInRequirement(_, _, span)
         | ValidationError::InvalidFrameDomain(_, _, _, span)
+        | ValidationError::FramePhenomenaMismatch(_, _, _, span)
         | ValidationError::DuplicateDomain(_, span, _)
         | ValidationError::DuplicateInterface(_, span, _)
         | ValidationError::DuplicateRequirement(_, span, _)
+        | ValidationError::DuplicateRequirementId(_, span, _)
         | ValidationError::MissingConnection(_, _, _, span, _)
         | ValidationError::InvalidCausality(_, _, _, _, span, _)
         | ValidationError::MissingRequiredField(_, _, span)
@@ -1122,13 +1833,41 @@ pub fn validation_error_span(error: &ValidationError) -> Span {
         | ValidationError::EmptyAssertionSet(_, span)
         | ValidationError::InvalidCorrectnessArgument(_, _, span)
         | ValidationError::InvalidSpecificationVocabulary(_, _, span)
+        | ValidationError::InvalidWorldVocabulary(_, _, span)
+        | ValidationError::InvalidRequirementVocabulary(_, _, span)
         | ValidationError::InvalidDomainMark(_, _, span)
-        | ValidationError::InvalidRequirementMark(_, _, span) => *span,
-        ValidationError::DuplicateCorrectnessArgument(_, span, _) => *span,
+        | ValidationError::InvalidRequirementMark(_, _, span)
+        | ValidationError::UndefinedPhenomenonInRequirement(_, _, span) => *span,
+        ValidationError::DuplicateCorrectnessArgument(_, span, _)
+        | ValidationError::DuplicateFrame(_, span, _)
+        | ValidationError::DuplicateType(_, span, _)
+        | ValidationError::UndefinedType(_, _, span)
+        | ValidationError::InvalidTypeDefinition(_, _, span)
+        | ValidationError::InvalidBehavior(_, _, span)
+        | ValidationError::BehaviorPhenomenonMismatch(_, _, _, span)
+        | ValidationError::InvalidSchema(_, _, span)
+        | ValidationError::UndeclaredSchemaField(_, _, span)
+        | ValidationError::InvalidFrameDeclaration(_, _, span)
+        | ValidationError::ImportCycle(_, span)
+        | ValidationError::RedundantImport(_, _, span)
+        | ValidationError::OrphanDomain(_, span)
+        | ValidationError::UnusedPhenomenon(_, _, span)
+        | ValidationError::UnusedAssertionSet(_, span)
+        | ValidationError::UntiedRequirementAssertions(_, span)
+        | ValidationError::DisconnectedSubproblemParticipant(_, _, span)
+        | ValidationError::CompositionConcern(_, _, span)
+        | ValidationError::InvalidSuppression(_, _, span)
+        | ValidationError::RequirementWithoutMachine(_, span) => *span,
     }
 }
 
 fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<PathBuf> {
+    // Resolved models know the file of every span; hand-built models fall back to
+    // the `source_path` of the declaration that owns the span.
+    if let Some(path) = problem.sources.path(validation_error_span(error).file) {
+        return Some(path.to_path_buf());
+    }
+
     let requirement_matches_span = |requirement: &Requirement, span: Span| {
         requirement.span == span
             || requirement
@@ -1141,6 +1880,10 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                 .as_ref()
                 .map(|reference| reference.span == span)
                 .unwrap_or(false)
+            || requirement
+                .phenomena
+                .iter()
+                .any(|reference| reference.span == span)
     };
 
     let subproblem_matches_span = |subproblem: &Subproblem, span: Span| {
@@ -1200,7 +1943,8 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                 .and_then(|requirement| requirement.source_path.clone())
         }
         ValidationError::MissingRequiredField(requirement_name, _, span)
-        | ValidationError::UnsupportedFrame(requirement_name, _, span) => problem
+        | ValidationError::UnsupportedFrame(requirement_name, _, span)
+        | ValidationError::RequirementWithoutMachine(requirement_name, span) => problem
             .requirements
             .iter()
             .find(|requirement| requirement.name == *requirement_name && requirement.span == *span)
@@ -1241,7 +1985,8 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                 })
                 .and_then(|requirement| requirement.source_path.clone())
         }
-        ValidationError::InvalidFrameDomain(requirement_name, _, _, span) => problem
+        ValidationError::InvalidFrameDomain(requirement_name, _, _, span)
+        | ValidationError::FramePhenomenaMismatch(requirement_name, _, _, span) => problem
             .requirements
             .iter()
             .find(|requirement| {
@@ -1255,6 +2000,26 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                     .find(|requirement| requirement.name == *requirement_name)
             })
             .and_then(|requirement| requirement.source_path.clone()),
+        ValidationError::UndefinedPhenomenonInRequirement(
+            phenomenon_name,
+            requirement_name,
+            span,
+        ) => problem
+            .requirements
+            .iter()
+            .find(|requirement| {
+                requirement.name == *requirement_name
+                    && requirement.phenomena.iter().any(|reference| {
+                        reference.name == *phenomenon_name && reference.span == *span
+                    })
+            })
+            .or_else(|| {
+                problem
+                    .requirements
+                    .iter()
+                    .find(|requirement| requirement.name == *requirement_name)
+            })
+            .and_then(|requirement| requirement.source_path.clone()),
         ValidationError::MissingConnection(_, _, _, _, index) => problem
             .requirements
             .get(*index)
@@ -1267,7 +2032,8 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
             .interfaces
             .get(*index)
             .and_then(|interface| interface.source_path.clone()),
-        ValidationError::DuplicateRequirement(_, _, index) => problem
+        ValidationError::DuplicateRequirement(_, _, index)
+        | ValidationError::DuplicateRequirementId(_, _, index) => problem
             .requirements
             .get(*index)
             .and_then(|requirement| requirement.source_path.clone()),
@@ -1301,7 +2067,9 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                     .find(|argument| argument.name == *name)
             })
             .and_then(|argument| argument.source_path.clone()),
-        ValidationError::InvalidSpecificationVocabulary(name, _, span) => problem
+        ValidationError::InvalidSpecificationVocabulary(name, _, span)
+        | ValidationError::InvalidWorldVocabulary(name, _, span)
+        | ValidationError::InvalidRequirementVocabulary(name, _, span) => problem
             .assertion_sets
             .iter()
             .find(|set| {
@@ -1362,6 +2130,67 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                     .find(|subproblem| subproblem.name == *name)
             })
             .and_then(|subproblem| subproblem.source_path.clone()),
+        ValidationError::DuplicateType(_, _, index) => problem
+            .types
+            .get(*index)
+            .and_then(|data_type| data_type.source_path.clone()),
+        ValidationError::InvalidTypeDefinition(name, _, span) => problem
+            .types
+            .iter()
+            .find(|data_type| data_type.name == *name && data_type.span == *span)
+            .and_then(|data_type| data_type.source_path.clone()),
+        ValidationError::UndefinedType(_, _, span) => problem
+            .types
+            .iter()
+            .find(|data_type| match &data_type.definition {
+                TypeDefinition::Record(fields) => {
+                    fields.iter().any(|field| field.type_ref.span == *span)
+                }
+                _ => false,
+            })
+            .and_then(|data_type| data_type.source_path.clone())
+            .or_else(|| {
+                problem
+                    .interfaces
+                    .iter()
+                    .find(|interface| {
+                        interface.shared_phenomena.iter().any(|phenomenon| {
+                            phenomenon
+                                .parameters
+                                .iter()
+                                .any(|parameter| parameter.type_ref.span == *span)
+                        })
+                    })
+                    .and_then(|interface| interface.source_path.clone())
+            })
+            .or_else(|| {
+                problem
+                    .domains
+                    .iter()
+                    .find(|domain| {
+                        domain.schema.as_ref().is_some_and(|schema| {
+                            schema.entities.iter().any(|entity| {
+                                entity
+                                    .fields
+                                    .iter()
+                                    .any(|field| field.type_ref.span == *span)
+                            })
+                        })
+                    })
+                    .and_then(|domain| domain.source_path.clone())
+            }),
+        ValidationError::DuplicateFrame(_, _, index) => problem
+            .frames
+            .get(*index)
+            .and_then(|frame| frame.source_path.clone()),
+        ValidationError::InvalidFrameDeclaration(name, _, span) => problem
+            .frames
+            .iter()
+            .find(|frame| {
+                frame.name == *name
+                    && (frame.span == *span || frame.flows.iter().any(|flow| flow.span == *span))
+            })
+            .and_then(|frame| frame.source_path.clone()),
         ValidationError::DuplicateSubproblem(_, _, index) => problem
             .subproblems
             .get(*index)
@@ -1381,6 +2210,28 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
             })
             .or_else(|| problem.domains.iter().find(|domain| domain.name == *name))
             .and_then(|domain| domain.source_path.clone()),
+        ValidationError::UndeclaredSchemaField(_, _, span) => problem
+            .interfaces
+            .iter()
+            .find(|interface| {
+                interface
+                    .shared_phenomena
+                    .iter()
+                    .any(|phenomenon| phenomenon.span == *span)
+            })
+            .and_then(|interface| interface.source_path.clone()),
+        ValidationError::InvalidBehavior(name, _, span)
+        | ValidationError::InvalidSchema(name, _, span)
+        | ValidationError::BehaviorPhenomenonMismatch(name, _, _, span) => problem
+            .domains
+            .iter()
+            .find(|domain| {
+                domain.name == *name
+                    && domain.span.start <= span.start
+                    && span.end <= domain.span.end
+            })
+            .or_else(|| problem.domains.iter().find(|domain| domain.name == *name))
+            .and_then(|domain| domain.source_path.clone()),
         ValidationError::InvalidRequirementMark(name, _, span) => problem
             .requirements
             .iter()
@@ -1398,18 +2249,75 @@ fn source_path_for_error(problem: &Problem, error: &ValidationError) -> Option<P
                     .find(|requirement| requirement.name == *name)
             })
             .and_then(|requirement| requirement.source_path.clone()),
+        ValidationError::OrphanDomain(name, _) => problem
+            .domains
+            .iter()
+            .find(|domain| domain.name == *name)
+            .and_then(|domain| domain.source_path.clone()),
+        ValidationError::UnusedPhenomenon(_, interface_name, _) => problem
+            .interfaces
+            .iter()
+            .find(|interface| interface.name == *interface_name)
+            .and_then(|interface| interface.source_path.clone()),
+        ValidationError::UnusedAssertionSet(name, _)
+        | ValidationError::UntiedRequirementAssertions(name, _) => problem
+            .assertion_sets
+            .iter()
+            .find(|set| set.name == *name)
+            .and_then(|set| set.source_path.clone()),
+        ValidationError::DisconnectedSubproblemParticipant(_, name, _) => problem
+            .subproblems
+            .iter()
+            .find(|subproblem| subproblem.name == *name)
+            .and_then(|subproblem| subproblem.source_path.clone()),
+        ValidationError::CompositionConcern(_, _, span) => problem
+            .requirements
+            .iter()
+            .find(|requirement| requirement.span == *span)
+            .and_then(|requirement| requirement.source_path.clone())
+            .or_else(|| {
+                problem
+                    .interfaces
+                    .iter()
+                    .find(|interface| {
+                        interface
+                            .shared_phenomena
+                            .iter()
+                            .any(|phenomenon| phenomenon.span == *span)
+                    })
+                    .and_then(|interface| interface.source_path.clone())
+            }),
+        // only resolved models have import graphs and comments, and their spans are in
+        // the source map
+        ValidationError::ImportCycle(..)
+        | ValidationError::RedundantImport(..)
+        | ValidationError::InvalidSuppression(..) => None,
     }
 }
 
 pub fn validate_with_sources(problem: &Problem) -> Result<(), Vec<ValidationIssue>> {
     match validate(problem) {
         Ok(()) => Ok(()),
-        Err(errors) => Err(errors
-            .into_iter()
-            .map(|error| ValidationIssue {
+        Err(errors) => Err(issues_with_sources(problem, errors)),
+    }
+}
+
+// Locates findings of `validate` or of another pass over the same model.
+pub fn issues_with_sources(
+    problem: &Problem,
+    errors: Vec<ValidationError>,
+) -> Vec<ValidationIssue> {
+    errors
+        .into_iter()
+        .map(|error| {
+            let rule = error.rule();
+            ValidationIssue {
                 source_path: source_path_for_error(problem, &error),
+                rule_id: &rule.rule_id,
+                severity: rule.severity,
+                fixes: fixes_for(problem, &error),
                 error,
-            })
-            .collect()),
-    }
+            }
+        })
+        .collect()
 }
@@ -1,12 +1,23 @@
 #[cfg(test)]
 mod tests {
     use crate::ast::*;
+    use crate::composition::check_composition;
+    use crate::import_graph::ImportEdge;
+    use crate::lints::{check_suppressions, inline_suppressions};
     use crate::parser::parse;
+    use crate::resolver::resolve_with_files;
+    use crate::rule_catalog::Severity;
+    use crate::unused::check_unused;
     use crate::validator::{validate, validate_with_sources, ValidationError};
-    use std::path::PathBuf;
+    use crate::vfs::MemoryFiles;
+    use std::path::{Path, PathBuf};
 
     fn mock_span() -> Span {
-        Span { start: 0, end: 0 }
+        Span {
+            start: 0,
+            end: 0,
+            file: FileId::default(),
+        }
     }
 
     fn mock_ref(name: &str) -> Reference {
@@ -19,16 +30,23 @@ mod tests {
     fn mock_ref_with_span(name: &str, start: usize, end: usize) -> Reference {
         Reference {
             name: name.to_string(),
-            span: Span { start, end },
+            span: Span {
+                start,
+                end,
+                file: FileId::default(),
+            },
         }
     }
 
     fn domain(name: &str, kind: DomainKind, role: DomainRole) -> Domain {
         Domain {
             name: name.to_string(),
+            doc: None,
             kind,
             role,
             marks: vec![],
+            behavior: None,
+            schema: None,
             span: mock_span(),
             source_path: None,
         }
@@ -43,6 +61,8 @@ mod tests {
     ) -> Phenomenon {
         Phenomenon {
             name: name.to_string(),
+            doc: None,
+            parameters: vec![],
             type_,
             from: mock_ref(from),
             to: mock_ref(to),
@@ -54,6 +74,7 @@ mod tests {
     fn interface(name: &str, connects: &[&str], shared_phenomena: Vec<Phenomenon>) -> Interface {
         Interface {
             name: name.to_string(),
+            doc: None,
             connects: connects.iter().map(|name| mock_ref(name)).collect(),
             shared_phenomena,
             span: mock_span(),
@@ -61,12 +82,38 @@ mod tests {
         }
     }
 
+    // A requirement whose `@formal.argument` ties `argument`'s requirement set to it.
+    fn argued_requirement(name: &str, constrains: &str, argument: &str) -> Requirement {
+        Requirement {
+            name: name.to_string(),
+            id: None,
+            priority: None,
+            status: None,
+            owner: None,
+            doc: None,
+            frame: FrameType::RequiredBehavior,
+            constrains: Some(mock_ref(constrains)),
+            reference: None,
+            constraint: "".to_string(),
+            phenomena: vec![],
+            marks: vec![Mark {
+                name: "formal.argument".to_string(),
+                value: Some(argument.to_string()),
+                span: mock_span(),
+            }],
+            span: mock_span(),
+            source_path: None,
+        }
+    }
+
     #[test]
     fn test_duplicate_domain_detection() {
         let problem = Problem {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Machine", DomainKind::Causal, DomainRole::Machine),
                 domain("D1", DomainKind::Causal, DomainRole::Given),
@@ -77,6 +124,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -93,11 +142,16 @@ mod tests {
             name: "InvalidDomainRoleSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![Domain {
                 name: "M".to_string(),
+                doc: None,
                 kind: DomainKind::Lexical,
                 role: DomainRole::Machine,
                 marks: vec![],
+                behavior: None,
+                schema: None,
                 span: mock_span(),
                 source_path: Some(PathBuf::from("domain.pf")),
             }],
@@ -106,6 +160,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -130,20 +186,28 @@ mod tests {
             name: "DuplicateDomainSources".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 Domain {
                     name: "D1".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(),
                     source_path: Some(PathBuf::from("a.pf")),
                 },
                 Domain {
                     name: "D1".to_string(),
+                    doc: None,
                     kind: DomainKind::Causal,
                     role: DomainRole::Given,
                     marks: vec![],
+                    behavior: None,
+                    schema: None,
                     span: mock_span(),
                     source_path: Some(PathBuf::from("b.pf")),
                 },
@@ -153,6 +217,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -177,6 +243,8 @@ mod tests {
             name: "DuplicateInterfaceSources".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -184,6 +252,7 @@ mod tests {
             interfaces: vec![
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("C")],
                     shared_phenomena: vec![phenomenon("P1", PhenomenonType::Event, "C", "M", "C")],
                     span: mock_span(),
@@ -191,6 +260,7 @@ mod tests {
                 },
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("C")],
                     shared_phenomena: vec![phenomenon("P2", PhenomenonType::Event, "C", "M", "C")],
                     span: mock_span(),
@@ -201,6 +271,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -225,6 +297,8 @@ mod tests {
             name: "InterfaceUndefinedDomainSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -232,6 +306,7 @@ mod tests {
             interfaces: vec![
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("A")],
                     shared_phenomena: vec![phenomenon("P1", PhenomenonType::Event, "A", "M", "A")],
                     span: mock_span(),
@@ -239,17 +314,24 @@ mod tests {
                 },
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![
                         mock_ref_with_span("M", 10, 11),
                         mock_ref_with_span("Missing", 20, 27),
                     ],
                     shared_phenomena: vec![Phenomenon {
                         name: "P2".to_string(),
+                        doc: None,
+                        parameters: vec![],
                         type_: PhenomenonType::Event,
                         from: mock_ref_with_span("Missing", 30, 37),
                         to: mock_ref_with_span("M", 38, 39),
                         controlled_by: mock_ref_with_span("Missing", 40, 47),
-                        span: Span { start: 30, end: 47 },
+                        span: Span {
+                            start: 30,
+                            end: 47,
+                            file: FileId::default(),
+                        },
                     }],
                     span: mock_span(),
                     source_path: Some(PathBuf::from("b.pf")),
@@ -259,6 +341,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -283,6 +367,8 @@ mod tests {
             name: "InterfaceControllerMismatchSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -290,6 +376,7 @@ mod tests {
             interfaces: vec![
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("A")],
                     shared_phenomena: vec![phenomenon("P1", PhenomenonType::Event, "A", "M", "A")],
                     span: mock_span(),
@@ -297,6 +384,7 @@ mod tests {
                 },
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("A")],
                     shared_phenomena: vec![phenomenon(
                         "P_bad",
@@ -313,6 +401,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -338,12 +428,15 @@ mod tests {
             name: "InterfaceConnectionsSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
             ],
             interfaces: vec![Interface {
                 name: "I1".to_string(),
+                doc: None,
                 connects: vec![mock_ref("M")],
                 shared_phenomena: vec![phenomenon("P1", PhenomenonType::Event, "M", "A", "M")],
                 span: mock_span(),
@@ -353,6 +446,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -377,12 +472,15 @@ mod tests {
             name: "InterfacePhenomenaSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
             ],
             interfaces: vec![Interface {
                 name: "I1".to_string(),
+                doc: None,
                 connects: vec![mock_ref("M"), mock_ref("A")],
                 shared_phenomena: vec![],
                 span: mock_span(),
@@ -392,6 +490,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -416,6 +516,8 @@ mod tests {
             name: "InterfaceCausalitySource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("L", DomainKind::Lexical, DomainRole::Given),
@@ -423,6 +525,7 @@ mod tests {
             interfaces: vec![
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("L")],
                     shared_phenomena: vec![phenomenon("E", PhenomenonType::Event, "M", "L", "M")],
                     span: mock_span(),
@@ -430,6 +533,7 @@ mod tests {
                 },
                 Interface {
                     name: "I1".to_string(),
+                    doc: None,
                     connects: vec![mock_ref("M"), mock_ref("L")],
                     shared_phenomena: vec![phenomenon("E", PhenomenonType::Event, "L", "M", "L")],
                     span: mock_span(),
@@ -440,6 +544,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -465,6 +571,8 @@ mod tests {
             name: "DuplicateRequirement".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -477,6 +585,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
@@ -488,6 +601,11 @@ mod tests {
                 },
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
@@ -501,6 +619,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -517,6 +637,8 @@ mod tests {
             name: "DuplicateAssertionSet".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
@@ -524,6 +646,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world fact 1".to_string(),
@@ -535,6 +658,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world fact 2".to_string(),
@@ -546,6 +670,8 @@ mod tests {
                 },
             ],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -562,6 +688,8 @@ mod tests {
             name: "DuplicateRequirementSources".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -574,6 +702,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
@@ -585,6 +718,11 @@ mod tests {
                 },
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
@@ -598,6 +736,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -622,10 +762,17 @@ mod tests {
             name: "RequirementUndefinedDomainSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("Missing")),
                 reference: None,
@@ -638,6 +785,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -663,6 +812,8 @@ mod tests {
             name: "RequirementSourceMapping".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -675,30 +826,50 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
                     constraint: "".to_string(),
                     phenomena: vec![],
                     marks: vec![],
-                    span: Span { start: 1, end: 2 },
+                    span: Span {
+                        start: 1,
+                        end: 2,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("a.pf")),
                 },
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::CommandedBehavior,
                     constrains: Some(mock_ref("C")),
                     reference: None,
                     constraint: "".to_string(),
                     phenomena: vec![],
                     marks: vec![],
-                    span: Span { start: 10, end: 11 },
+                    span: Span {
+                        start: 10,
+                        end: 11,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("b.pf")),
                 },
             ],
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -724,6 +895,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("Op", DomainKind::Biddable, DomainRole::Given),
                 domain("M", DomainKind::Causal, DomainRole::Machine),
@@ -732,6 +905,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::CommandedBehavior,
                 constrains: Some(mock_ref("C")),
                 reference: Some(mock_ref("Op")),
@@ -744,6 +922,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -760,6 +940,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -767,6 +949,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("C")),
                 reference: None,
@@ -779,6 +966,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -795,6 +984,8 @@ mod tests {
             name: "MissingConnectionSourceMapping".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Connected", DomainKind::Causal, DomainRole::Given),
@@ -814,6 +1005,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "R1".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("Connected")),
                     reference: None,
@@ -825,6 +1021,11 @@ mod tests {
                 },
                 Requirement {
                     name: "R2".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::RequiredBehavior,
                     constrains: Some(mock_ref("Disconnected")),
                     reference: None,
@@ -838,6 +1039,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -862,6 +1065,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("L", DomainKind::Lexical, DomainRole::Given),
                 domain("M", DomainKind::Causal, DomainRole::Machine),
@@ -875,6 +1080,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -891,6 +1098,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -904,6 +1113,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -924,10 +1135,17 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::Custom("".to_string()),
                 constrains: None,
                 reference: None,
@@ -940,6 +1158,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -960,10 +1180,17 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::Custom("FutureFrame".to_string()),
                 constrains: None,
                 reference: None,
@@ -976,6 +1203,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -996,6 +1225,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -1003,6 +1234,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::CommandedBehavior,
                 constrains: Some(mock_ref("C")),
                 reference: None,
@@ -1015,6 +1251,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1035,6 +1273,8 @@ mod tests {
             name: "Test".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("C", DomainKind::Causal, DomainRole::Given),
@@ -1042,6 +1282,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("C")),
                 reference: Some(mock_ref("M")),
@@ -1054,6 +1299,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1074,6 +1321,8 @@ mod tests {
             name: "InfoDisplay".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Ops", DomainKind::Causal, DomainRole::Given),
@@ -1099,6 +1348,11 @@ mod tests {
             ],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::InformationDisplay,
                 constrains: Some(mock_ref("Metrics")),
                 reference: Some(mock_ref("Ops")),
@@ -1111,6 +1365,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1131,6 +1387,8 @@ mod tests {
             name: "SimpleWorkpieces".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("User", DomainKind::Biddable, DomainRole::Given),
@@ -1162,6 +1420,11 @@ mod tests {
             ],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::SimpleWorkpieces,
                 constrains: Some(mock_ref("Work")),
                 reference: Some(mock_ref("User")),
@@ -1174,6 +1437,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1194,6 +1459,8 @@ mod tests {
             name: "Transformation".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Out", DomainKind::Lexical, DomainRole::Given),
@@ -1201,6 +1468,11 @@ mod tests {
             interfaces: vec![],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::Transformation,
                 constrains: Some(mock_ref("Out")),
                 reference: None,
@@ -1213,6 +1485,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1233,6 +1507,8 @@ mod tests {
             name: "AllFrames".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Viewer", DomainKind::Biddable, DomainRole::Given),
@@ -1244,13 +1520,16 @@ mod tests {
                 interface(
                     "Viewer-M",
                     &["Viewer", "M"],
-                    vec![phenomenon(
-                        "RequestView",
-                        PhenomenonType::Event,
-                        "Viewer",
-                        "M",
-                        "Viewer",
-                    )],
+                    vec![
+                        phenomenon(
+                            "RequestView",
+                            PhenomenonType::Event,
+                            "Viewer",
+                            "M",
+                            "Viewer",
+                        ),
+                        phenomenon("View", PhenomenonType::Value, "M", "Viewer", "M"),
+                    ],
                 ),
                 interface(
                     "Sensor-M",
@@ -1289,6 +1568,11 @@ mod tests {
             requirements: vec![
                 Requirement {
                     name: "ShowState".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::InformationDisplay,
                     constrains: Some(mock_ref("Sensor")),
                     reference: Some(mock_ref("Viewer")),
@@ -1300,6 +1584,11 @@ mod tests {
                 },
                 Requirement {
                     name: "EditWorkpiece".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::SimpleWorkpieces,
                     constrains: Some(mock_ref("Workpiece")),
                     reference: Some(mock_ref("Viewer")),
@@ -1311,6 +1600,11 @@ mod tests {
                 },
                 Requirement {
                     name: "GenerateOutput".to_string(),
+                    id: None,
+                    priority: None,
+                    status: None,
+                    owner: None,
+                    doc: None,
                     frame: FrameType::Transformation,
                     constrains: Some(mock_ref("Output")),
                     reference: None,
@@ -1324,6 +1618,8 @@ mod tests {
             subproblems: vec![],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1336,13 +1632,23 @@ mod tests {
             name: "CorrectnessValid".to_string(),
             span: mock_span(),
             imports: vec![],
-            domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
-            interfaces: vec![],
-            requirements: vec![],
+            frames: vec![],
+            types: vec![],
+            domains: vec![
+                domain("M", DomainKind::Causal, DomainRole::Machine),
+                domain("Plant", DomainKind::Causal, DomainRole::Given),
+            ],
+            interfaces: vec![interface(
+                "M-Plant",
+                &["M", "Plant"],
+                vec![phenomenon("Act", PhenomenonType::Event, "M", "Plant", "M")],
+            )],
+            requirements: vec![argued_requirement("Reach target", "Plant", "A1")],
             subproblems: vec![],
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "machine strategy".to_string(),
@@ -1354,6 +1660,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world remains stable".to_string(),
@@ -1365,6 +1672,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "requirement is met".to_string(),
@@ -1386,6 +1694,8 @@ mod tests {
                 span: mock_span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1398,6 +1708,8 @@ mod tests {
             name: "SpecificationVocabularyValid".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Plant", DomainKind::Causal, DomainRole::Given),
@@ -1410,11 +1722,12 @@ mod tests {
                     phenomenon("Act", PhenomenonType::Event, "M", "Plant", "M"),
                 ],
             )],
-            requirements: vec![],
+            requirements: vec![argued_requirement("Track plant", "Plant", "A1")],
             subproblems: vec![],
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "controller uses [[M-Plant.Sense]] and emits [[M-Plant.Act]]"
@@ -1427,6 +1740,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "plant follows causal dynamics".to_string(),
@@ -1438,6 +1752,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "target behavior achieved".to_string(),
@@ -1459,6 +1774,8 @@ mod tests {
                 span: mock_span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1471,6 +1788,8 @@ mod tests {
             name: "SpecificationVocabularyInvalid".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("Plant", DomainKind::Causal, DomainRole::Given),
@@ -1491,17 +1810,27 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "controller claims [[M-Plant.Missing]]".to_string(),
                         language: Some("LTL".to_string()),
-                        span: Span { start: 50, end: 70 },
+                        span: Span {
+                            start: 50,
+                            end: 70,
+                            file: FileId::default(),
+                        },
                     }],
-                    span: Span { start: 40, end: 80 },
+                    span: Span {
+                        start: 40,
+                        end: 80,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("spec.pf")),
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world fact".to_string(),
@@ -1513,6 +1842,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "requirement fact".to_string(),
@@ -1534,6 +1864,8 @@ mod tests {
                 span: mock_span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -1559,18 +1891,23 @@ mod tests {
             name: "Assertions".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
             subproblems: vec![],
             assertion_sets: vec![AssertionSet {
                 name: "W".to_string(),
+                doc: None,
                 scope: AssertionScope::WorldProperties,
                 assertions: vec![],
                 span: mock_span(),
                 source_path: None,
             }],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1587,12 +1924,15 @@ mod tests {
             name: "Correctness".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
             subproblems: vec![],
             assertion_sets: vec![AssertionSet {
                 name: "S_ok".to_string(),
+                doc: None,
                 scope: AssertionScope::Specification,
                 assertions: vec![Assertion {
                     text: "machine controls output".to_string(),
@@ -1613,6 +1953,8 @@ mod tests {
                 span: mock_span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1633,6 +1975,8 @@ mod tests {
             name: "CorrectnessScope".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
@@ -1640,6 +1984,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S_wrong".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world fact".to_string(),
@@ -1651,6 +1996,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W_ok".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "stable world".to_string(),
@@ -1662,6 +2008,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R_ok".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "goal holds".to_string(),
@@ -1683,6 +2030,8 @@ mod tests {
                 span: mock_span(),
                 source_path: None,
             }],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1703,6 +2052,8 @@ mod tests {
             name: "DuplicateCorrectnessArgs".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
@@ -1710,6 +2061,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "spec".to_string(),
@@ -1721,6 +2073,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world".to_string(),
@@ -1732,6 +2085,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "req".to_string(),
@@ -1766,6 +2120,8 @@ mod tests {
                     source_path: Some(PathBuf::from("b.pf")),
                 },
             ],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -1790,6 +2146,8 @@ mod tests {
             name: "CorrectnessSourceMapping".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
@@ -1797,6 +2155,7 @@ mod tests {
             assertion_sets: vec![
                 AssertionSet {
                     name: "S".to_string(),
+                    doc: None,
                     scope: AssertionScope::Specification,
                     assertions: vec![Assertion {
                         text: "spec".to_string(),
@@ -1808,6 +2167,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "W".to_string(),
+                    doc: None,
                     scope: AssertionScope::WorldProperties,
                     assertions: vec![Assertion {
                         text: "world".to_string(),
@@ -1819,6 +2179,7 @@ mod tests {
                 },
                 AssertionSet {
                     name: "R".to_string(),
+                    doc: None,
                     scope: AssertionScope::RequirementAssertions,
                     assertions: vec![Assertion {
                         text: "req".to_string(),
@@ -1838,7 +2199,11 @@ mod tests {
                     specification_ref: mock_ref("S"),
                     world_ref: mock_ref("W"),
                     requirement_ref: mock_ref("R"),
-                    span: Span { start: 1, end: 2 },
+                    span: Span {
+                        start: 1,
+                        end: 2,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("a.pf")),
                 },
                 CorrectnessArgument {
@@ -1849,10 +2214,16 @@ mod tests {
                     specification_ref: mock_ref("S"),
                     world_ref: mock_ref("W_missing"),
                     requirement_ref: mock_ref("R"),
-                    span: Span { start: 10, end: 11 },
+                    span: Span {
+                        start: 10,
+                        end: 11,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("b.pf")),
                 },
             ],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -1878,11 +2249,14 @@ mod tests {
             name: "SubproblemMissingMachine".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![domain("M", DomainKind::Causal, DomainRole::Machine)],
             interfaces: vec![],
             requirements: vec![],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: None,
                 participants: vec![mock_ref("M")],
                 requirements: vec![],
@@ -1891,6 +2265,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -1911,6 +2287,8 @@ mod tests {
             name: "DuplicateSubproblems".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -1922,6 +2300,11 @@ mod tests {
             )],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("A")),
                 reference: None,
@@ -1934,6 +2317,7 @@ mod tests {
             subproblems: vec![
                 Subproblem {
                     name: "Core".to_string(),
+                    doc: None,
                     machine: Some(mock_ref("M")),
                     participants: vec![mock_ref("M"), mock_ref("A")],
                     requirements: vec![mock_ref("R1")],
@@ -1942,6 +2326,7 @@ mod tests {
                 },
                 Subproblem {
                     name: "Core".to_string(),
+                    doc: None,
                     machine: Some(mock_ref("M")),
                     participants: vec![mock_ref("M"), mock_ref("A")],
                     requirements: vec![mock_ref("R1")],
@@ -1951,6 +2336,8 @@ mod tests {
             ],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -1975,6 +2362,8 @@ mod tests {
             name: "SubproblemSourceMapping".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -1986,6 +2375,11 @@ mod tests {
             )],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("A")),
                 reference: None,
@@ -1998,23 +2392,35 @@ mod tests {
             subproblems: vec![
                 Subproblem {
                     name: "Core".to_string(),
+                    doc: None,
                     machine: Some(mock_ref("M")),
                     participants: vec![mock_ref("M"), mock_ref("A")],
                     requirements: vec![mock_ref("R1")],
-                    span: Span { start: 1, end: 2 },
+                    span: Span {
+                        start: 1,
+                        end: 2,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("a.pf")),
                 },
                 Subproblem {
                     name: "Core".to_string(),
+                    doc: None,
                     machine: None,
                     participants: vec![mock_ref("M"), mock_ref("A")],
                     requirements: vec![mock_ref("R1")],
-                    span: Span { start: 10, end: 11 },
+                    span: Span {
+                        start: 10,
+                        end: 11,
+                        file: FileId::default(),
+                    },
                     source_path: Some(PathBuf::from("b.pf")),
                 },
             ],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -2040,6 +2446,8 @@ mod tests {
             name: "SubproblemUndefinedDomainSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -2051,6 +2459,11 @@ mod tests {
             )],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("A")),
                 reference: None,
@@ -2062,6 +2475,7 @@ mod tests {
             }],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: Some(mock_ref("M")),
                 participants: vec![mock_ref("M"), mock_ref("A"), mock_ref("Ghost")],
                 requirements: vec![mock_ref("R1")],
@@ -2070,6 +2484,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -2095,6 +2511,8 @@ mod tests {
             name: "SubproblemUndefinedRequirementSource".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -2103,6 +2521,7 @@ mod tests {
             requirements: vec![],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: Some(mock_ref("M")),
                 participants: vec![mock_ref("M"), mock_ref("A")],
                 requirements: vec![mock_ref("R_missing")],
@@ -2111,6 +2530,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate_with_sources(&problem);
@@ -2136,6 +2557,8 @@ mod tests {
             name: "SubproblemBoundary".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -2155,6 +2578,11 @@ mod tests {
             ],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("B")),
                 reference: None,
@@ -2166,6 +2594,7 @@ mod tests {
             }],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: Some(mock_ref("M")),
                 participants: vec![mock_ref("M"), mock_ref("A")],
                 requirements: vec![mock_ref("R1")],
@@ -2174,6 +2603,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -2194,6 +2625,8 @@ mod tests {
             name: "SubproblemValid".to_string(),
             span: mock_span(),
             imports: vec![],
+            frames: vec![],
+            types: vec![],
             domains: vec![
                 domain("M", DomainKind::Causal, DomainRole::Machine),
                 domain("A", DomainKind::Causal, DomainRole::Given),
@@ -2205,6 +2638,11 @@ mod tests {
             )],
             requirements: vec![Requirement {
                 name: "R1".to_string(),
+                id: None,
+                priority: None,
+                status: None,
+                owner: None,
+                doc: None,
                 frame: FrameType::RequiredBehavior,
                 constrains: Some(mock_ref("A")),
                 reference: None,
@@ -2216,6 +2654,7 @@ mod tests {
             }],
             subproblems: vec![Subproblem {
                 name: "Core".to_string(),
+                doc: None,
                 machine: Some(mock_ref("M")),
                 participants: vec![mock_ref("M"), mock_ref("A")],
                 requirements: vec![mock_ref("R1")],
@@ -2224,6 +2663,8 @@ mod tests {
             }],
             assertion_sets: vec![],
             correctness_arguments: vec![],
+            sources: SourceMap::default(),
+            import_graph: ImportGraph::default(),
         };
 
         let result = validate(&problem);
@@ -2244,6 +2685,7 @@ mod tests {
             interface "Tool-Payments" connects Tool, Payments {
                 shared: {
                     phenomenon Sync : event [Tool -> Payments] controlledBy Tool
+                    phenomenon Balance : state [Payments -> Tool] controlledBy Payments
                 }
             }
             interface "Tool-Console" connects Tool, Console {
@@ -2499,4 +2941,1303 @@ mod tests {
             )
         }));
     }
+
+    #[test]
+    fn test_requirement_phenomena_resolve_to_shared_phenomena() {
+        let input = r#"
+            problem: RequirementPhenomenaValid
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                    phenomenon TopPos : state [Gate -> Controller] controlledBy Gate
+                }
+            }
+            requirement "OpenGate" {
+                frame: RequiredBehavior
+                phenomena: Controller-Gate.PulseOpen, TopPos
+                constrains: Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse requirement phenomena model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_requirement_phenomena_resolve_through_quoted_interface_names() {
+        let input = r#"
+            problem: RequirementPhenomenaValid
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller to gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            requirement "OpenGate" {
+                frame: RequiredBehavior
+                phenomena: "Controller to gate".PulseOpen
+                constrains: Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse requirement phenomena model");
+        assert!(validate(&problem).is_ok());
+    }
+
+    #[test]
+    fn test_undefined_phenomenon_in_requirement_uses_matching_source_path() {
+        let input = r#"
+            problem: RequirementPhenomenaInvalid
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            requirement "OpenGate" {
+                frame: RequiredBehavior
+                phenomena: Gate-Controller.PulseOpen, BottomPos
+                constrains: Gate
+            }
+        "#;
+
+        let requirement_path = PathBuf::from("/tmp/requirements.pf");
+        let mut problem = parse(input).expect("failed to parse requirement phenomena model");
+        problem.requirements[0].source_path = Some(requirement_path.clone());
+
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        let undefined: Vec<&str> = errors
+            .iter()
+            .filter_map(|error| match error {
+                ValidationError::UndefinedPhenomenonInRequirement(name, requirement, _)
+                    if requirement == "OpenGate" =>
+                {
+                    Some(name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(undefined, vec!["Gate-Controller.PulseOpen", "BottomPos"]);
+
+        let issues = validate_with_sources(&problem).expect_err("expected validation issues");
+        assert!(issues.iter().any(|issue| {
+            matches!(
+                issue.error,
+                ValidationError::UndefinedPhenomenonInRequirement(_, _, _)
+            ) && issue.source_path.as_ref() == Some(&requirement_path)
+        }));
+    }
+
+    const APPROVAL_FRAME: &str = r#"
+            problem: HouseFrame
+            frame Approval {
+                constrains: lexical role designed
+                reference: biddable
+                connects: constrains, reference
+                phenomenon: command [reference -> machine]
+            }
+            domain Desk kind causal role machine
+            domain Ledger kind lexical role designed
+            domain Clerk kind biddable role given
+            interface "Desk-Ledger" connects Desk, Ledger {
+                shared: {
+                    phenomenon Record : event [Desk -> Ledger] controlledBy Desk
+                }
+            }
+            interface "Clerk-Desk" connects Clerk, Desk {
+                shared: {
+                    phenomenon Approve : command [Clerk -> Desk] controlledBy Clerk
+                }
+            }
+    "#;
+
+    #[test]
+    fn test_declared_frame_is_enforced_like_builtin_frames() {
+        let input = format!(
+            r#"{APPROVAL_FRAME}
+            requirement "ApproveEntries" {{
+                frame: Approval
+                constrains: Ledger
+                reference: Clerk
+            }}
+        "#
+        );
+
+        let problem = parse(&input).expect("failed to parse frame declaration");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+
+        // Swapping the slots breaks the kind, connection and phenomenon-flow rules.
+        let swapped = input
+            .replace("constrains: Ledger", "constrains: Clerk")
+            .replace("reference: Clerk", "reference: Ledger")
+            .replace("phenomenon Approve : command", "phenomenon Approve : event");
+        let problem = parse(&swapped).expect("failed to parse swapped model");
+        let errors = validate(&problem).unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::InvalidFrameDomain(req, frame, message, _)
+                if req == "ApproveEntries"
+                    && frame == "Approval"
+                    && message == "reference domain 'Ledger' should be biddable, found Lexical/Designed"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::FramePhenomenaMismatch(_, frame, message, _)
+                if frame == "Approval"
+                    && message.contains("expected a shared command phenomenon from reference 'Ledger'")
+        )));
+    }
+
+    #[test]
+    fn test_declared_frame_requires_slots_and_connections() {
+        let input = format!(
+            r#"{APPROVAL_FRAME}
+            domain Archive kind lexical role designed
+            requirement "ArchiveEntries" {{
+                frame: Approval
+                constrains: Archive
+            }}
+        "#
+        );
+
+        let problem = parse(&input).expect("failed to parse frame declaration");
+        let errors = validate(&problem).unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::MissingRequiredField(req, field, _)
+                if req == "ArchiveEntries" && field == "reference"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::MissingConnection(domain, _, frame, _, _)
+                if domain == "Archive" && frame == "Approval"
+        )));
+        assert!(!errors
+            .iter()
+            .any(|error| matches!(error, ValidationError::UnsupportedFrame(_, _, _))));
+    }
+
+    #[test]
+    fn test_duplicate_frame_declaration() {
+        let input = format!(
+            r#"{APPROVAL_FRAME}
+            frame Approval {{
+                constrains: lexical
+            }}
+        "#
+        );
+
+        let problem = parse(&input).expect("failed to parse duplicate frames");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateFrame(name, _, 1) if name == "Approval"
+        )));
+    }
+
+    #[test]
+    fn test_frame_declaration_rejects_undeclared_slots_and_builtin_redefinition() {
+        let input = r#"
+            problem: BadFrames
+            frame Review {
+                constrains: causal
+                connects: reference
+                phenomenon: event [machine -> machine]
+            }
+            frame RequiredBehavior {
+                constrains: lexical
+                connects: constrains
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse frame declarations");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        let messages: Vec<String> = errors
+            .iter()
+            .filter_map(|error| match error {
+                ValidationError::InvalidFrameDeclaration(_, message, _) => Some(message.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(messages.contains(
+            &"'connects' uses slot 'reference' that the frame does not declare".to_string()
+        ));
+        assert!(messages.contains(&"phenomenon flow cannot start and end at 'machine'".to_string()));
+        assert!(messages.contains(&"redefines a built-in frame with different rules".to_string()));
+    }
+
+    #[test]
+    fn test_builtin_frame_redeclared_unchanged_is_accepted() {
+        let input = r#"
+            problem: StdCopy
+            frame RequiredBehavior {
+                constrains: causal
+                connects: constrains
+                phenomenon: any [machine -> constrains] controlledBy machine
+            }
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            requirement "OpenGate" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+        "#;
+
+        let problem = parse(input).expect("failed to parse redeclared builtin frame");
+        assert!(validate(&problem).is_ok());
+    }
+
+    const TYPED_DEVICE: &str = r#"
+            problem: TypedDevice
+            type Percent = range 0..100 unit "%"
+            type Mode = enum { Auto, Manual }
+            type Setting = record { level: Percent, mode: Mode, locked: Boolean }
+            domain Tool kind causal role machine
+            domain Heater kind causal role given
+            interface "Tool-Heater" connects Tool, Heater {
+                shared: {
+                    phenomenon Configure(setting: Setting, retries: Integer) : event [Tool -> Heater] controlledBy Tool
+                }
+            }
+            requirement "Heat" {
+                frame: RequiredBehavior
+                constrains: Heater
+            }
+        "#;
+
+    #[test]
+    fn test_typed_phenomenon_parameters_resolve_to_declared_types() {
+        let problem = parse(TYPED_DEVICE).expect("failed to parse typed model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_type_declaration() {
+        let input = r#"
+            problem: DuplicateTypes
+            type Mode = enum { Auto, Manual }
+            type Mode = enum { On, Off }
+            type Integer = range 0..10
+        "#;
+
+        let problem = parse(input).expect("failed to parse duplicate types");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateType(name, _, 1) if name == "Mode"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateType(name, _, 2) if name == "Integer"
+        )));
+    }
+
+    #[test]
+    fn test_undefined_parameter_and_field_types() {
+        let input = TYPED_DEVICE
+            .replace("retries: Integer", "retries: Count")
+            .replace("locked: Boolean", "locked: Flag");
+
+        let problem = parse(&input).expect("failed to parse undefined types");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::UndefinedType(name, owner, _)
+                if name == "Count" && owner == "Tool-Heater.Configure"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::UndefinedType(name, owner, _)
+                if name == "Flag" && owner == "Setting.locked"
+        )));
+    }
+
+    #[test]
+    fn test_invalid_type_definitions_are_reported() {
+        let input = r#"
+            problem: BadTypes
+            type Level = range 10..-10
+            type Node = record { next: Link }
+            type Link = record { node: Node }
+        "#;
+
+        let problem = parse(input).expect("failed to parse bad types");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        let messages: Vec<String> = errors
+            .iter()
+            .filter_map(|error| match error {
+                ValidationError::InvalidTypeDefinition(name, message, _) => {
+                    Some(format!("{name}: {message}"))
+                }
+                _ => None,
+            })
+            .collect();
+        assert!(
+            messages.contains(&"Level: range minimum 10 is greater than maximum -10".to_string())
+        );
+        assert!(
+            messages.contains(&"Node: record contains itself via Node -> Link -> Node".to_string())
+        );
+        assert!(
+            messages.contains(&"Link: record contains itself via Link -> Node -> Link".to_string())
+        );
+    }
+
+    const GATE_BEHAVIOR: &str = r#"
+            problem: GateStates
+            domain Controller kind causal role machine
+            domain Gate kind causal role given behavior {
+                state Closed, Open
+                on PulseOpen from Closed to Open emits GateOpened
+                on PulseClose from Open to Closed
+            }
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon PulseOpen : event [Controller -> Gate] controlledBy Controller
+                    phenomenon PulseClose : event [Controller -> Gate] controlledBy Controller
+                    phenomenon GateOpened : state [Gate -> Controller] controlledBy Gate
+                }
+            }
+            requirement "OpenOnPulse" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+        "#;
+
+    #[test]
+    fn test_domain_behavior_consistent_with_shared_phenomena() {
+        let problem = parse(GATE_BEHAVIOR).expect("failed to parse behavior");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_domain_behavior_rejects_undeclared_states_and_non_causal_domains() {
+        let input = GATE_BEHAVIOR
+            .replace("to Closed", "to Jammed")
+            .replace(
+                "domain Controller kind causal role machine",
+                "domain Controller kind causal role machine\n            domain Clerk kind biddable role given behavior { state Idle }",
+            );
+
+        let problem = parse(&input).expect("failed to parse behavior");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::InvalidBehavior(domain, message, _)
+                if domain == "Gate"
+                    && message == "transition on 'PulseClose' uses undeclared state 'Jammed'"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::InvalidBehavior(domain, message, _)
+                if domain == "Clerk"
+                    && message == "behavior is only allowed on causal given or designed domains"
+        )));
+    }
+
+    #[test]
+    fn test_domain_behavior_phenomena_must_match_interface_direction() {
+        let input = GATE_BEHAVIOR
+            .replace("on PulseOpen from", "on GateOpened from")
+            .replace("emits GateOpened", "emits PulseClose");
+
+        let problem = parse(&input).expect("failed to parse behavior");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::BehaviorPhenomenonMismatch(domain, phenomenon, reason, _)
+                if domain == "Gate"
+                    && phenomenon == "GateOpened"
+                    && reason == "consumes a phenomenon it does not receive"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::BehaviorPhenomenonMismatch(domain, phenomenon, reason, _)
+                if domain == "Gate"
+                    && phenomenon == "PulseClose"
+                    && reason == "emits a phenomenon it does not control"
+        )));
+    }
+
+    const LEDGER_SCHEMA: &str = r#"
+            problem: Orders
+            domain Clerk kind causal role machine
+            domain Ledger kind lexical role designed schema {
+                entity Order {
+                    field total: Money
+                    field status: Status
+                }
+                value Money {
+                    field amount: Decimal
+                }
+            }
+            type Status = enum { Open, Paid }
+            interface "Ledger-Clerk" connects Ledger, Clerk {
+                shared: {
+                    phenomenon OrderTotal : value [Ledger -> Clerk] controlledBy Ledger
+                    phenomenon Status : state [Ledger -> Clerk] controlledBy Ledger
+                    phenomenon Order : value [Ledger -> Clerk] controlledBy Ledger
+                }
+            }
+        "#;
+
+    #[test]
+    fn test_lexical_schema_phenomena_refer_to_declared_fields() {
+        let problem = parse(LEDGER_SCHEMA).expect("failed to parse schema");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_schema_only_allowed_on_lexical_domains_with_defined_field_types() {
+        let input = LEDGER_SCHEMA
+            .replace("field amount: Decimal", "field amount: Amount")
+            .replace(
+                "domain Clerk kind causal role machine",
+                "domain Clerk kind causal role machine\n            domain Printer kind causal role given schema { entity Job { } }",
+            );
+
+        let problem = parse(&input).expect("failed to parse schema");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::InvalidSchema(domain, message, _)
+                if domain == "Printer" && message == "schema is only allowed on lexical domains"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::UndefinedType(name, owner, _)
+                if name == "Amount" && owner == "Ledger.Money.amount"
+        )));
+    }
+
+    #[test]
+    fn test_lexical_value_phenomena_must_refer_to_schema_fields() {
+        let input = LEDGER_SCHEMA.replace("phenomenon OrderTotal", "phenomenon OrderCount");
+
+        let problem = parse(&input).expect("failed to parse schema");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::UndeclaredSchemaField(phenomenon, domain, _)
+                if phenomenon == "OrderCount" && domain == "Ledger"
+        ));
+    }
+
+    const TRACKED_REQUIREMENTS: &str = r#"
+            problem: TrackedGate
+            domain Controller kind causal role machine
+            domain Gate kind causal role given
+            interface "Controller-Gate" connects Controller, Gate {
+                shared: {
+                    phenomenon Pulse : event [Controller -> Gate] controlledBy Controller
+                }
+            }
+            requirement "Gate opens on request" {
+                id: REQ-1
+                priority: high
+                status: approved
+                owner: "Gate Team"
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            requirement "Gate closes at night" {
+                id: REQ-2
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            subproblem GateControl {
+                machine: Controller
+                participants: Controller, Gate
+                requirements: "REQ-1", "Gate closes at night"
+            }
+        "#;
+
+    #[test]
+    fn test_requirement_ids_are_unique_and_resolve_from_subproblems() {
+        let problem = parse(TRACKED_REQUIREMENTS).expect("failed to parse requirements");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_requirement_id_detection() {
+        let input = TRACKED_REQUIREMENTS.replace("id: REQ-2", "id: REQ-1");
+        let problem = parse(&input).expect("failed to parse requirements");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateRequirementId(id, _, index) if id == "REQ-1" && *index == 1
+        )));
+
+        // an ID that matches another requirement's name makes references ambiguous
+        let input = TRACKED_REQUIREMENTS
+            .replace("id: REQ-2", "id: REQ-3")
+            .replace(
+                "requirement \"Gate closes at night\"",
+                "requirement \"REQ-1\"",
+            );
+        let problem = parse(&input).expect("failed to parse requirements");
+        let errors = validate(&problem).unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateRequirementId(id, _, index) if id == "REQ-1" && *index == 0
+        )));
+    }
+
+    fn import_edge(sources: &mut SourceMap, from: &str, to: &str) -> ImportEdge {
+        let file = sources
+            .file_id(Path::new(from))
+            .unwrap_or_else(|| sources.add(from, format!("import \"{to}\"\n")));
+        ImportEdge {
+            from: PathBuf::from(from),
+            to: PathBuf::from(to),
+            path: to.to_string(),
+            alias: None,
+            items: vec![],
+            span: Span {
+                start: 0,
+                end: 8 + to.len(),
+                file,
+            },
+        }
+    }
+
+    #[test]
+    fn test_diamond_imports_are_not_cycles() {
+        let mut problem = parse(TRACKED_REQUIREMENTS).expect("failed to parse requirements");
+        let mut sources = SourceMap::default();
+        for (from, to) in [
+            ("root.pf", "a.pf"),
+            ("root.pf", "b.pf"),
+            ("a.pf", "shared.pf"),
+            ("b.pf", "shared.pf"),
+        ] {
+            let edge = import_edge(&mut sources, from, to);
+            problem.import_graph.add_edge(edge);
+        }
+        problem.sources = sources;
+
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_import_cycle_detection_reports_the_chain() {
+        let mut problem = parse(TRACKED_REQUIREMENTS).expect("failed to parse requirements");
+        let mut sources = SourceMap::default();
+        for (from, to) in [("root.pf", "a.pf"), ("a.pf", "b.pf"), ("b.pf", "a.pf")] {
+            let edge = import_edge(&mut sources, from, to);
+            problem.import_graph.add_edge(edge);
+        }
+        problem.import_graph.add_cycle(vec![1, 2]);
+        problem.sources = sources;
+
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::ImportCycle(chain, span)
+                if chain == "a.pf:1:1 imports \"b.pf\" -> b.pf:1:1 imports \"a.pf\""
+                    && *span == problem.import_graph.edges[2].span
+        )));
+    }
+
+    #[test]
+    fn test_redundant_import_is_reported_as_a_warning() {
+        let mut problem = parse(TRACKED_REQUIREMENTS).expect("failed to parse requirements");
+        let mut sources = SourceMap::default();
+        for start in [0, 14] {
+            let mut edge = import_edge(&mut sources, "root.pf", "a.pf");
+            edge.span.start = start;
+            problem.import_graph.add_edge(edge);
+        }
+        problem.import_graph.add_redundant(1, 0);
+        problem.sources = sources;
+
+        let result = validate(&problem);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().iter().any(|error| matches!(
+            error,
+            ValidationError::RedundantImport(path, first, _)
+                if path == "a.pf" && first == "root.pf:1:1"
+        )));
+
+        let issues = validate_with_sources(&problem).expect_err("warning is reported");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_id, "PF-VAL-040");
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert!(!issues[0].is_fatal(false));
+        assert!(issues[0].is_fatal(true));
+    }
+
+    const USED_MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+domain Operator kind biddable role given
+interface "Operator-Controller" connects Operator, Controller {
+  shared: {
+    phenomenon Request : command [Operator -> Controller] controlledBy Operator
+  }
+}
+interface "Controller-Gate" connects Controller, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+    phenomenon Position : state [Gate -> Controller] controlledBy Gate
+  }
+}
+requirement "Open on request" {
+  frame: CommandedBehavior
+  constrains: Gate
+  reference: Operator
+  marks: {
+    @formal.argument("A")
+  }
+}
+subproblem GateControl {
+  machine: Controller
+  participants: Controller, Gate, Operator
+  requirements: "Open on request"
+}
+specification S {
+  assert "the controller issues [[Controller-Gate.Open]]"
+}
+worldProperties W {
+  assert "a pulsed gate opens"
+}
+requirementAssertions R {
+  assert "the gate opens on request"
+}
+correctnessArgument A {
+  prove S and W entail R
+}
+"#;
+
+    #[test]
+    fn test_unused_element_lints_accept_a_fully_used_model() {
+        let problem = parse(USED_MODEL).expect("failed to parse used model");
+        assert!(validate(&problem).is_ok());
+        let result = check_unused(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_orphan_domain_is_reported() {
+        let input = USED_MODEL.replace(
+            "domain Operator kind biddable role given\n",
+            "domain Operator kind biddable role given\ndomain Valve kind causal role given\n",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_unused(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::OrphanDomain(name, _) if name == "Valve"
+        ));
+    }
+
+    #[test]
+    fn test_unused_declarations_of_imported_files_are_not_reported() {
+        let library = "domain Valve kind causal role given\ndomain Pump kind causal role given\ninterface \"Valve-Pump\" connects Valve, Pump {\n  shared: {\n    phenomenon Flow : value [Pump -> Valve] controlledBy Pump\n  }\n}\nworldProperties Hydraulics {\n  assert \"water flows downhill\"\n}\n";
+        let mut files = MemoryFiles::new();
+        files.insert("/work/lib.pf", library);
+        files.insert(
+            "/work/root.pf",
+            USED_MODEL.replace(
+                "domain Operator kind biddable role given\n",
+                "domain Operator kind biddable role given\nimport \"lib.pf\"\n",
+            ),
+        );
+        let problem = resolve_with_files(Path::new("/work/root.pf"), &files).unwrap();
+        assert!(check_unused(&problem).is_ok());
+
+        files.insert("/work/root.pf", format!("{USED_MODEL}{library}"));
+        let problem = resolve_with_files(Path::new("/work/root.pf"), &files).unwrap();
+        let errors = check_unused(&problem).unwrap_err();
+        assert!(errors.iter().any(
+            |error| matches!(error, ValidationError::UnusedPhenomenon(name, ..) if name == "Flow")
+        ));
+        assert!(errors.iter().any(
+            |error| matches!(error, ValidationError::UnusedAssertionSet(name, _) if name == "Hydraulics")
+        ));
+    }
+
+    #[test]
+    fn test_unconnected_designed_domain_is_not_an_orphan() {
+        let input = USED_MODEL.replace(
+            "domain Operator kind biddable role given\n",
+            "domain Operator kind biddable role given\ndomain Log kind lexical role designed\n",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        assert!(check_unused(&problem).is_ok());
+    }
+
+    #[test]
+    fn test_unused_phenomenon_is_reported() {
+        let input = USED_MODEL
+            .replace(
+                "  reference: Operator\n",
+                "  reference: Operator\n  phenomena: Request, Open\n",
+            )
+            .replace("subproblem GateControl", "subproblem Requests")
+            .replace(
+                "participants: Controller, Gate, Operator",
+                "participants: Controller, Operator",
+            );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_unused(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::UnusedPhenomenon(name, interface, _)
+                if name == "Position" && interface == "Controller-Gate"
+        ));
+    }
+
+    #[test]
+    fn test_unused_assertion_set_is_reported() {
+        let input = format!("{USED_MODEL}worldProperties Spare {{\n  assert \"unused\"\n}}\n");
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_unused(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::UnusedAssertionSet(name, _) if name == "Spare"
+        ));
+    }
+
+    #[test]
+    fn test_disconnected_subproblem_participant_is_reported() {
+        let input = USED_MODEL
+            .replace(
+                "domain Operator kind biddable role given\n",
+                "domain Operator kind biddable role given\ndomain Lamp kind causal role given\ninterface \"Operator-Lamp\" connects Operator, Lamp {\n  shared: {\n    phenomenon Lit : state [Lamp -> Operator] controlledBy Lamp\n  }\n}\n",
+            )
+            .replace(
+                "participants: Controller, Gate, Operator",
+                "participants: Controller, Gate, Lamp",
+            );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_unused(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DisconnectedSubproblemParticipant(name, subproblem, _)
+                if name == "Lamp" && subproblem == "GateControl"
+        )));
+        assert!(!errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DisconnectedSubproblemParticipant(name, _, _) if name != "Lamp"
+        )));
+    }
+
+    const FRAME_PHENOMENA_MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+domain Operator kind biddable role given
+domain Panel kind causal role given
+interface "Controller-Gate" connects Controller, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+    phenomenon Position : state [Gate -> Controller] controlledBy Gate
+  }
+}
+interface "Operator-Controller" connects Operator, Controller {
+  shared: {
+    phenomenon Request : command [Operator -> Controller] controlledBy Operator
+    phenomenon Status : value [Controller -> Operator] controlledBy Controller
+  }
+}
+interface "Controller-Panel" connects Controller, Panel {
+  shared: {
+    phenomenon Show : value [Controller -> Panel] controlledBy Controller
+  }
+}
+requirement "Keep gate open" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+requirement "Open on request" {
+  frame: CommandedBehavior
+  constrains: Gate
+  reference: Operator
+}
+requirement "Show gate position" {
+  frame: InformationDisplay
+  constrains: Gate
+  reference: Operator
+}
+"#;
+
+    #[test]
+    fn test_frame_phenomena_accept_prescribed_flows() {
+        let problem = parse(FRAME_PHENOMENA_MODEL).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_frame_phenomena_mismatch_is_reported() {
+        let input = FRAME_PHENOMENA_MODEL
+            .replace(
+                "phenomenon Open : event [Controller -> Gate] controlledBy Controller",
+                "phenomenon Opened : event [Gate -> Controller] controlledBy Gate",
+            )
+            .replace(
+                "    phenomenon Position : state [Gate -> Controller] controlledBy Gate\n",
+                "",
+            )
+            .replace(
+                "phenomenon Request : command [Operator -> Controller]",
+                "phenomenon Request : state [Operator -> Controller]",
+            )
+            // the panel is not the requirement's display domain
+            .replace(
+                "    phenomenon Status : value [Controller -> Operator] controlledBy Controller\n",
+                "",
+            );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let mismatched: Vec<String> = result
+            .unwrap_err()
+            .iter()
+            .filter_map(|error| match error {
+                ValidationError::FramePhenomenaMismatch(requirement, _, message, _) => {
+                    Some(format!("{requirement}: {message}"))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            mismatched,
+            [
+                "Keep gate open: expected a shared phenomenon from machine 'Controller' to constrains 'Gate' controlled by machine 'Controller'",
+                "Open on request: expected a shared command or event phenomenon from reference 'Operator' to machine 'Controller' controlled by reference 'Operator'",
+                "Show gate position: expected a shared state or value phenomenon from constrains 'Gate' to machine 'Controller'",
+                "Show gate position: expected a shared phenomenon from machine 'Controller' to reference 'Operator' controlled by machine 'Controller'",
+            ]
+        );
+    }
+
+    const VOCABULARY_MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given behavior {
+  state Closed, Opened
+  on Open from Closed to Opened
+}
+domain Panel kind lexical role given schema {
+  entity Reading {
+    field position: String
+  }
+}
+interface "Controller-Gate" connects Controller, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+  }
+}
+interface "Controller-Panel" connects Controller, Panel {
+  shared: {
+    phenomenon Show : event [Controller -> Panel] controlledBy Controller
+  }
+}
+requirement "Open gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+  marks: {
+    @formal.argument("A")
+  }
+}
+specification S {
+  assert "the controller issues [[Controller-Gate.Open]]"
+}
+worldProperties W {
+  assert "[[Controller-Gate.Open]] moves the gate to [[Gate.Opened]]"
+  assert "[[Panel.Reading.position]] mirrors the gate"
+}
+requirementAssertions R {
+  assert "the gate reaches [[Gate.Opened]] once requested"
+}
+correctnessArgument A {
+  prove S and W entail R
+}
+"#;
+
+    #[test]
+    fn test_world_vocabulary_accepts_shared_and_domain_internal_refs() {
+        let problem = parse(VOCABULARY_MODEL).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_world_vocabulary_rejects_unknown_ref() {
+        let input = VOCABULARY_MODEL.replace(
+            "moves the gate to [[Gate.Opened]]",
+            "moves the gate to [[Gate.Ajar]]",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidWorldVocabulary(set, token, _)
+                if set == "W" && token == "Gate.Ajar"
+        ));
+    }
+
+    #[test]
+    fn test_requirement_vocabulary_accepts_requirement_domain_refs() {
+        let input = VOCABULARY_MODEL.replace(
+            "  constrains: Gate\n",
+            "  constrains: Gate\n  reference: Panel\n",
+        );
+        let input = input.replace(
+            "the gate reaches [[Gate.Opened]]",
+            "the gate reaches [[Gate.Opened]] as [[Panel.Reading]] shows",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_requirement_vocabulary_rejects_machine_interface_refs() {
+        // the constrained domain's own interface with the machine is not world vocabulary
+        let input = VOCABULARY_MODEL.replace("once requested", "after [[Controller-Gate.Open]]");
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidRequirementVocabulary(set, token, _)
+                if set == "R" && token == "Controller-Gate.Open"
+        ));
+
+        let input = VOCABULARY_MODEL.replace("once requested", "after [[Controller-Panel.Show]]");
+        let problem = parse(&input).expect("failed to parse model");
+        let errors = validate(&problem).unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidRequirementVocabulary(set, token, _)
+                if set == "R" && token == "Controller-Panel.Show"
+        ));
+    }
+
+    #[test]
+    fn test_requirement_vocabulary_reports_untied_sets() {
+        let input = VOCABULARY_MODEL.replace("@formal.argument(\"A\")", "@sysml.requirement");
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::UntiedRequirementAssertions(set, _) if set == "R"
+        ));
+    }
+
+    const TWO_MACHINE_MODEL: &str = r#"problem: Crossing
+domain GateController kind causal role machine
+domain LightController kind causal role machine
+domain Gate kind causal role given
+domain Light kind causal role given
+interface "GateController-Gate" connects GateController, Gate {
+  shared: {
+    phenomenon Lower : event [GateController -> Gate] controlledBy GateController
+  }
+}
+interface "LightController-Light" connects LightController, Light {
+  shared: {
+    phenomenon Flash : event [LightController -> Light] controlledBy LightController
+  }
+}
+requirement "Lower gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+requirement "Flash light" {
+  frame: RequiredBehavior
+  constrains: Light
+}
+subproblem GateControl {
+  machine: GateController
+  participants: GateController, Gate
+  requirements: "Lower gate"
+}
+subproblem LightControl {
+  machine: LightController
+  participants: LightController, Light
+  requirements: "Flash light"
+}
+"#;
+
+    #[test]
+    fn test_machines_of_subproblems_may_share_a_problem() {
+        let problem = parse(TWO_MACHINE_MODEL).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_machine_without_subproblem_is_rejected_in_multi_machine_problem() {
+        let input = TWO_MACHINE_MODEL.replace(
+            "domain Light kind causal role given\n",
+            "domain Light kind causal role given\ndomain Spare kind causal role machine\n",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidDomainRole(name, message, _)
+                if name == "Spare" && message.contains("machine of no subproblem")
+        ));
+    }
+
+    #[test]
+    fn test_requirement_is_checked_against_the_machine_of_its_subproblem() {
+        let input = TWO_MACHINE_MODEL.replace(
+            "  participants: LightController, Light\n  requirements: \"Flash light\"",
+            "  participants: LightController, Light\n  requirements: \"Flash light\", \"Lower gate\"",
+        );
+        let input = input.replace(
+            "  participants: GateController, Gate\n  requirements: \"Lower gate\"",
+            "  participants: GateController, Gate\n  requirements: \"Flash light\"",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::MissingConnection(domain, _, _, _, _) if domain == "Gate"
+        )));
+        assert!(!errors.iter().any(|error| matches!(
+            error,
+            ValidationError::MissingConnection(domain, _, _, _, _) if domain == "Light"
+        )));
+    }
+
+    #[test]
+    fn test_requirement_of_a_subproblem_has_a_machine() {
+        let problem = parse(TWO_MACHINE_MODEL).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_requirement_outside_every_subproblem_is_rejected_in_multi_machine_problem() {
+        // either machine is connected to the gate, but neither answers for this one
+        let input = TWO_MACHINE_MODEL.replace(
+            "subproblem GateControl {",
+            "requirement \"Raise gate\" {\n  frame: RequiredBehavior\n  constrains: Gate\n}\nsubproblem GateControl {",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::RequirementWithoutMachine(name, _) if name == "Raise gate"
+        ));
+    }
+
+    const COMPOSITION_MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Remote kind causal role machine
+domain Gate kind causal role given
+interface "Controller-Gate" connects Controller, Gate {
+  shared: {
+    phenomenon Open : event [Controller -> Gate] controlledBy Controller
+  }
+}
+interface "Remote-Gate" connects Remote, Gate {
+  shared: {
+    phenomenon Close : event [Remote -> Gate] controlledBy Remote
+  }
+}
+requirement "Open gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+requirement "Close gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+  marks: {
+    @composition.decision("Open gate")
+  }
+}
+subproblem Opening {
+  machine: Controller
+  participants: Controller, Gate
+  requirements: "Open gate"
+}
+subproblem Closing {
+  machine: Remote
+  participants: Remote, Gate
+  requirements: "Close gate"
+}
+"#;
+
+    #[test]
+    fn test_composition_accepts_decided_requirement_pair() {
+        let problem = parse(COMPOSITION_MODEL).expect("failed to parse model");
+        assert!(validate(&problem).is_ok());
+        let result = check_composition(&problem);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_composition_reports_undecided_requirement_pair() {
+        let input = COMPOSITION_MODEL.replace(
+            "  marks: {\n    @composition.decision(\"Open gate\")\n  }\n",
+            "",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_composition(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::CompositionConcern(name, message, _)
+                if name == "Close gate" && message.contains("as 'Open gate' does in subproblem Opening")
+        ));
+    }
+
+    #[test]
+    fn test_composition_reports_shared_controlled_phenomenon() {
+        let input = COMPOSITION_MODEL.replace(
+            "  requirements: \"Close gate\"\n}\n",
+            "  requirements: \"Close gate\"\n}\nsubproblem Servicing {\n  machine: Controller\n  participants: Controller, Gate\n  requirements: \"Open gate\"\n}\n",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = check_composition(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::CompositionConcern(name, message, _)
+                if name == "Open" && message.ends_with("in subproblems Opening, Servicing")
+        ));
+    }
+
+    #[test]
+    fn test_composition_decision_must_name_a_requirement() {
+        let input = COMPOSITION_MODEL.replace(
+            "@composition.decision(\"Open gate\")",
+            "@composition.decision(\"Open gate, Shut gate\")",
+        );
+        let problem = parse(&input).expect("failed to parse model");
+        let result = validate(&problem);
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidRequirementMark(name, message, _)
+                if name == "Close gate" && message.contains("undefined requirement 'Shut gate'")
+        ));
+    }
+
+    const SUPPRESSED_MODEL: &str = r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+// pf-allow(PF-VAL-007): the gate is wired by another team
+requirement "Open gate" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+"#;
+
+    fn resolved(text: &str) -> Problem {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/plant.pf", text);
+        resolve_with_files(Path::new("/work/plant.pf"), &files).expect("model resolves")
+    }
+
+    #[test]
+    fn test_inline_suppression_accepts_catalog_rules() {
+        let problem = resolved(SUPPRESSED_MODEL);
+        let result = check_suppressions(&inline_suppressions(&problem));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_inline_suppression_rejects_unknown_and_unresolved_rules() {
+        let problem = resolved(&SUPPRESSED_MODEL.replace("PF-VAL-007", "PF-VAL-07"));
+        let result = check_suppressions(&inline_suppressions(&problem));
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidSuppression(rule_id, reason, _)
+                if rule_id == "PF-VAL-07" && reason == "unknown rule"
+        ));
+
+        let problem = resolved(&SUPPRESSED_MODEL.replace("PF-VAL-007", "PF-VAL-002"));
+        let errors = check_suppressions(&inline_suppressions(&problem)).unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            ValidationError::InvalidSuppression(rule_id, reason, _)
+                if rule_id == "PF-VAL-002" && reason.contains("cannot be suppressed")
+        ));
+    }
 }
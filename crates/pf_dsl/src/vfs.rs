@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// Where the resolver and manifest discovery read model files from.
+pub trait FileProvider {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+    fn is_file(&self, path: &Path) -> bool;
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiskFiles;
+
+impl FileProvider for DiskFiles {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+}
+
+// Files keyed by their lexically normalized path; a directory exists when some
+// file lies below it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryFiles {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemoryFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: impl AsRef<Path>, text: impl Into<String>) {
+        self.files.insert(normalize(path.as_ref()), text.into());
+    }
+}
+
+impl FileProvider for MemoryFiles {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files
+            .get(&normalize(path))
+            .cloned()
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let path_key = normalize(path);
+        if self.files.keys().any(|file| file.starts_with(&path_key)) {
+            Ok(path_key)
+        } else {
+            Err(not_found(path))
+        }
+    }
+}
+
+// In-memory files shadowing a base provider, such as unsaved editor buffers over
+// the disk. Overlay paths are canonicalized through the base when it knows them,
+// so a buffer is found under whichever path an import reaches it by.
+pub struct OverlayFiles<'a> {
+    base: &'a dyn FileProvider,
+    overlay: MemoryFiles,
+}
+
+impl<'a> OverlayFiles<'a> {
+    pub fn new(base: &'a dyn FileProvider) -> Self {
+        Self {
+            base,
+            overlay: MemoryFiles::new(),
+        }
+    }
+
+    pub fn insert(&mut self, path: impl AsRef<Path>, text: impl Into<String>) {
+        let key = self.key(path.as_ref());
+        self.overlay.insert(key, text);
+    }
+
+    fn key(&self, path: &Path) -> PathBuf {
+        self.base
+            .canonicalize(path)
+            .unwrap_or_else(|_| normalize(path))
+    }
+}
+
+impl FileProvider for OverlayFiles<'_> {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.overlay.read_to_string(&self.key(path)) {
+            Ok(text) => Ok(text),
+            Err(_) => self.base.read_to_string(path),
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.overlay.is_file(&self.key(path)) || self.base.is_file(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.base
+            .canonicalize(path)
+            .or_else(|_| self.overlay.canonicalize(path))
+    }
+}
+
+// `a/./b/../c.pf` becomes `a/c.pf`; `..` above the start of a relative path is kept.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let at_root = matches!(
+                    normalized.components().next_back(),
+                    None | Some(Component::ParentDir)
+                );
+                if at_root && !normalized.has_root() {
+                    normalized.push("..");
+                } else {
+                    normalized.pop();
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not exist", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_files_normalize_paths_and_know_their_directories() {
+        let mut files = MemoryFiles::new();
+        files.insert("/work/models/./plant.pf", "problem: Plant\n");
+
+        assert!(files.is_file(Path::new("/work/shared/../models/plant.pf")));
+        assert_eq!(
+            files
+                .read_to_string(Path::new("/work/models/plant.pf"))
+                .unwrap(),
+            "problem: Plant\n"
+        );
+        assert_eq!(
+            files.canonicalize(Path::new("/work/models/")).unwrap(),
+            Path::new("/work/models")
+        );
+        assert!(files.canonicalize(Path::new("/work/other")).is_err());
+        assert!(files.read_to_string(Path::new("/work/missing.pf")).is_err());
+        assert_eq!(
+            normalize(Path::new("../a/../../b.pf")),
+            Path::new("../../b.pf")
+        );
+    }
+
+    #[test]
+    fn overlay_files_shadow_the_base_provider() {
+        let mut disk = MemoryFiles::new();
+        disk.insert("/work/a.pf", "saved a");
+        disk.insert("/work/b.pf", "saved b");
+        let mut files = OverlayFiles::new(&disk);
+        files.insert("/work/./a.pf", "unsaved a");
+        files.insert("/work/new.pf", "unsaved new");
+
+        assert_eq!(
+            files.read_to_string(Path::new("/work/a.pf")).unwrap(),
+            "unsaved a"
+        );
+        assert_eq!(
+            files.read_to_string(Path::new("/work/b.pf")).unwrap(),
+            "saved b"
+        );
+        assert!(files.is_file(Path::new("/work/new.pf")));
+        assert_eq!(
+            files.canonicalize(Path::new("/work/x/../new.pf")).unwrap(),
+            Path::new("/work/new.pf")
+        );
+    }
+}
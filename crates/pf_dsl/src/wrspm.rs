@@ -4,7 +4,10 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct WrspmProjection {
     pub problem: String,
+    // the only machine domain; `None` when there are none or several
     pub machine_domain: Option<String>,
+    // several when the problem gives each subproblem its own machine
+    pub machine_domains: Vec<String>,
     pub artifacts: WrspmArtifacts,
     pub interface_phenomena: Vec<WrspmPhenomenon>,
     pub obligations: Vec<WrspmObligation>,
@@ -35,11 +38,12 @@ pub struct WrspmObligation {
 }
 
 pub fn project(problem: &Problem) -> WrspmProjection {
-    let machine_domain = problem
+    let machine_domains = problem
         .domains
         .iter()
-        .find(|domain| domain.role == DomainRole::Machine)
-        .map(|domain| domain.name.clone());
+        .filter(|domain| domain.role == DomainRole::Machine)
+        .map(|domain| domain.name.clone())
+        .collect::<Vec<_>>();
 
     let w_sets = problem
         .assertion_sets
@@ -63,11 +67,8 @@ pub fn project(problem: &Problem) -> WrspmProjection {
     let mut interface_phenomena = Vec::new();
     for interface in &problem.interfaces {
         for phenomenon in &interface.shared_phenomena {
-            let partition = if machine_domain
-                .as_ref()
-                .map(|machine| phenomenon.controlled_by.name == *machine)
-                .unwrap_or(false)
-            {
+            // Whichever machine controls it, the phenomenon belongs to the system.
+            let partition = if machine_domains.contains(&phenomenon.controlled_by.name) {
                 "sv"
             } else {
                 "ev"
@@ -95,7 +96,11 @@ pub fn project(problem: &Problem) -> WrspmProjection {
 
     WrspmProjection {
         problem: problem.name.clone(),
-        machine_domain: machine_domain.clone(),
+        machine_domain: match machine_domains.as_slice() {
+            [machine] => Some(machine.clone()),
+            _ => None,
+        },
+        machine_domains,
         artifacts: WrspmArtifacts {
             w_sets,
             r_sets,
@@ -119,8 +124,13 @@ pub fn generate_markdown(problem: &Problem) -> String {
     output.push_str(&format!("# WRSPM Report: {}\n\n", projection.problem));
     output.push_str("## 1. Artifact Projection (W/R/S/P/M)\n");
     output.push_str(&format!(
-        "- Machine domain: {}\n",
-        machine_name(&projection)
+        "- Machine domain{}: {}\n",
+        if projection.machine_domains.len() > 1 {
+            "s"
+        } else {
+            ""
+        },
+        machine_names(&projection)
     ));
     output.push_str(&format!(
         "- W sets: {}\n",
@@ -179,11 +189,12 @@ pub fn generate_json(problem: &Problem) -> Result<String, serde_json::Error> {
     serde_json::to_string_pretty(&project(problem))
 }
 
-fn machine_name(projection: &WrspmProjection) -> &str {
-    projection
-        .machine_domain
-        .as_deref()
-        .unwrap_or("(not declared)")
+fn machine_names(projection: &WrspmProjection) -> String {
+    if projection.machine_domains.is_empty() {
+        "(not declared)".to_string()
+    } else {
+        projection.machine_domains.join(", ")
+    }
 }
 
 fn join_or_none(values: &[String]) -> String {
@@ -252,6 +263,7 @@ correctnessArgument A1 {
         let problem = parse(input).expect("parse must succeed");
         let projection = project(&problem);
         assert_eq!(projection.machine_domain.as_deref(), Some("Tool"));
+        assert_eq!(projection.machine_domains, ["Tool"]);
         assert!(projection
             .interface_phenomena
             .iter()
@@ -287,4 +299,57 @@ requirement "R1" {
         assert!(json.contains("\"problem\": \"Minimal\""));
         assert!(json.contains("\"interface_phenomena\""));
     }
+
+    #[test]
+    fn wrspm_projection_lists_every_machine() {
+        let input = r#"
+            problem: Crossing
+            domain GateController kind causal role machine
+            domain LightController kind causal role machine
+            domain Gate kind causal role given
+            domain Light kind causal role given
+            interface "GateController-Gate" connects GateController, Gate {
+                shared: {
+                    phenomenon Lower : event [GateController -> Gate] controlledBy GateController
+                }
+            }
+            interface "LightController-Light" connects LightController, Light {
+                shared: {
+                    phenomenon Flash : event [LightController -> Light] controlledBy LightController
+                    phenomenon Burnt : state [Light -> LightController] controlledBy Light
+                }
+            }
+            requirement "Lower gate" {
+                frame: RequiredBehavior
+                constrains: Gate
+            }
+            requirement "Flash light" {
+                frame: RequiredBehavior
+                constrains: Light
+            }
+            subproblem GateControl {
+                machine: GateController
+                participants: GateController, Gate
+                requirements: "Lower gate"
+            }
+            subproblem LightControl {
+                machine: LightController
+                participants: LightController, Light
+                requirements: "Flash light"
+            }
+"#;
+        let problem = parse(input).expect("parse must succeed");
+        let projection = project(&problem);
+        assert_eq!(projection.machine_domain, None);
+        assert_eq!(
+            projection.machine_domains,
+            ["GateController", "LightController"]
+        );
+        assert!(projection
+            .interface_phenomena
+            .iter()
+            .all(|p| (p.partition == "sv") == (p.name != "Burnt")));
+        assert!(generate_markdown(&problem)
+            .contains("- Machine domains: GateController, LightController\n"));
+    }
 }
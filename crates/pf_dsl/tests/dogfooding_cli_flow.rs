@@ -204,10 +204,18 @@ requirement "R_shared" {
         !output.status.success(),
         "validation should fail when imports collide"
     );
+    let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(
-        String::from_utf8_lossy(&output.stderr).contains("Duplicate requirement definition"),
+        stderr.contains("Duplicate requirement definition"),
         "stderr should report duplicate requirement collision"
     );
+    assert!(
+        stderr.contains(&format!(
+            "{}:10:1: Duplicate requirement definition: 'R_shared'",
+            b_path.canonicalize().unwrap().display()
+        )),
+        "stderr should locate the collision in the second import: {stderr}"
+    );
 
     let _ = fs::remove_dir_all(dir);
 }
@@ -549,6 +557,7 @@ subproblem Execution {
     assert!(stdout.contains("# Decomposition Closure Report: Closure"));
     assert!(stdout.contains("| R_uncovered | - | uncovered |"));
     assert!(stdout.contains("### Orphan Subproblems"));
+    assert!(stdout.contains("## Composition Concerns\n- Undecided requirement pairs: 0\n"));
     assert!(stdout.contains("- None."));
 
     let concern_output = run_pf_dsl(&path, "--concern-coverage");
@@ -566,3 +575,447 @@ subproblem Execution {
 
     let _ = fs::remove_dir_all(dir);
 }
+
+#[test]
+fn dogfooding_cli_fmt_rewrites_file_and_check_reports_drift() {
+    let dir = make_temp_dir("pf-cli-fmt");
+    let path = dir.join("model.pf");
+    fs::write(
+        &path,
+        "problem: P\n// the machine\ndomain M kind causal role machine\ndomain A kind causal role given\ninterface \"M-A\" connects M, A { shared: { phenomenon Go : event [M -> A] controlledBy M } }\n",
+    )
+    .expect("failed to write model");
+
+    let run_fmt = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+            .arg("fmt")
+            .args(args)
+            .arg(&path)
+            .output()
+            .expect("failed to execute pf_dsl binary")
+    };
+
+    let check = run_fmt(&["--check"]);
+    assert!(
+        !check.status.success(),
+        "unformatted file should fail --check"
+    );
+    assert!(String::from_utf8_lossy(&check.stderr).contains("Not formatted"));
+
+    let rewrite = run_fmt(&[]);
+    assert!(
+        rewrite.status.success(),
+        "fmt should succeed: {}",
+        String::from_utf8_lossy(&rewrite.stderr)
+    );
+    let formatted = fs::read_to_string(&path).expect("failed to read formatted model");
+    assert!(formatted.contains("// the machine\ndomain M kind causal role machine\n"));
+    assert!(formatted.contains("        phenomenon Go : event [M -> A] controlledBy M\n"));
+
+    let recheck = run_fmt(&["--check"]);
+    assert!(
+        recheck.status.success(),
+        "formatted file should pass --check"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_check_validates_manifest_entries_with_library_imports() {
+    let dir = make_temp_dir("pf-cli-manifest");
+    fs::create_dir_all(dir.join("models")).expect("failed to create models dir");
+    fs::create_dir_all(dir.join("shared/catalog")).expect("failed to create catalog dir");
+    fs::write(
+        dir.join("pf.toml"),
+        "[workspace]\nroot = \"models\"\nentries = [\"plant.pf\", \"broken.pf\"]\n\n[libraries]\ncatalog = \"shared/catalog\"\n",
+    )
+    .expect("failed to write manifest");
+    fs::write(
+        dir.join("shared/catalog/domains.pf"),
+        "domain A kind causal role given\n",
+    )
+    .expect("failed to write catalog");
+    fs::write(
+        dir.join("models/plant.pf"),
+        "problem: Plant\nimport \"catalog/domains.pf\"\ndomain M kind causal role machine\ninterface \"M-A\" connects M, A { shared: { phenomenon Go : event [M -> A] controlledBy M } }\nrequirement \"R\" { frame: RequiredBehavior constrains: A }\n",
+    )
+    .expect("failed to write plant model");
+    fs::write(
+        dir.join("models/broken.pf"),
+        "problem: Broken\nimport \"catalog/domains.pf\"\ndomain A kind causal role given\n",
+    )
+    .expect("failed to write broken model");
+
+    let direct = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(dir.join("models/plant.pf"))
+        .arg("--dot")
+        .env_remove("PF_PATH")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    assert!(
+        direct.status.success(),
+        "library import should resolve through pf.toml: {}",
+        String::from_utf8_lossy(&direct.stderr)
+    );
+
+    let check = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg("check")
+        .arg(&dir)
+        .env_remove("PF_PATH")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    let stderr = String::from_utf8_lossy(&check.stderr);
+    assert!(!check.status.success(), "broken entry should fail check");
+    assert!(
+        stdout.contains("ok: ") && stdout.contains("plant.pf"),
+        "{stdout}"
+    );
+    assert!(
+        stderr.contains("broken.pf:\n")
+            && stderr.contains("Validation Errors:\n- ")
+            && stderr.contains("catalog/domains.pf:1:1: Duplicate domain definition: 'A'"),
+        "{stderr}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_reports_import_cycles_and_draws_the_import_graph() {
+    let dir = make_temp_dir("pf-cli-import-graph");
+    let root_path = dir.join("root.pf");
+    fs::write(
+        &root_path,
+        "problem: Root\nimport \"a.pf\"\nimport \"a.pf\"\ndomain M kind causal role machine\n",
+    )
+    .expect("failed to write root model");
+    fs::write(
+        dir.join("a.pf"),
+        "import \"b.pf\" as B\ndomain A kind causal role given\n",
+    )
+    .expect("failed to write a.pf");
+    fs::write(
+        dir.join("b.pf"),
+        "import \"a.pf\"\ndomain C kind causal role given\n",
+    )
+    .expect("failed to write b.pf");
+    let a_path = fs::canonicalize(dir.join("a.pf")).expect("a.pf exists");
+    let b_path = fs::canonicalize(dir.join("b.pf")).expect("b.pf exists");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "import cycle must fail validation"
+    );
+    assert!(
+        stderr.contains("warning: ")
+            && stderr.contains("root.pf:3:1: Redundant import of \"a.pf\": already imported at "),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!(
+            "- {}:1:1: Import cycle: {}:1:1 imports \"b.pf\" -> {}:1:1 imports \"a.pf\"",
+            b_path.display(),
+            a_path.display(),
+            b_path.display()
+        )),
+        "{stderr}"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--imports-dot")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "--imports-dot must draw cyclic graphs"
+    );
+    assert!(stdout.contains("\"root.pf\" -> \"a.pf\";"), "{stdout}");
+    assert!(
+        stdout.contains("\"root.pf\" -> \"a.pf\" [style=dashed];"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("\"a.pf\" -> \"b.pf\" [label=\"as B\"];"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("\"b.pf\" -> \"a.pf\" [color=red];"),
+        "{stdout}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_scopes_output_to_one_module() {
+    let dir = make_temp_dir("pf-cli-module-scope");
+    let root_path = dir.join("root.pf");
+    fs::write(
+        &root_path,
+        "problem: Plant\nimport \"domains.pf\"\ndomain Controller kind causal role machine\n",
+    )
+    .expect("failed to write root model");
+    fs::write(
+        dir.join("domains.pf"),
+        "domain Gate kind causal role given\n",
+    )
+    .expect("failed to write domains.pf");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .arg("--module=domains.pf")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("- **Gate** (Causal/Given)"), "{stdout}");
+    assert!(!stdout.contains("Controller"), "{stdout}");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .arg("--module")
+        .arg("missing.pf")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    assert!(!output.status.success(), "unknown module must fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no module of the model matches"));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_warnings_fail_only_with_deny_warnings() {
+    let dir = make_temp_dir("pf-cli-deny-warnings");
+    let root_path = dir.join("root.pf");
+    fs::write(
+        &root_path,
+        "problem: Plant\nimport \"a.pf\"\nimport \"a.pf\"\ndomain M kind causal role machine\n",
+    )
+    .expect("failed to write root model");
+    fs::write(dir.join("a.pf"), "domain A kind causal role given\n").expect("failed to write a.pf");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "warnings must not fail: {stderr}");
+    assert!(
+        stderr.contains("warning: ")
+            && stderr.contains("root.pf:3:1: Redundant import of \"a.pf\""),
+        "{stderr}"
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("# Problem Report: Plant"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .arg("--deny-warnings")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "--deny-warnings must fail");
+    assert!(
+        stderr.contains("Validation Errors:\n- warning: "),
+        "{stderr}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_applies_project_lints_and_lists_suppressions() {
+    let dir = make_temp_dir("pf-cli-lints");
+    let root_path = dir.join("plant.pf");
+    fs::write(
+        &root_path,
+        r#"problem: Plant
+domain Controller kind causal role machine
+domain Gate kind causal role given
+domain Valve kind causal role given
+// pf-allow(PF-VAL-007): the gate is wired by the operations team
+requirement "Open" {
+  frame: RequiredBehavior
+  constrains: Gate
+}
+requirement "Close" {
+  frame: RequiredBehavior
+  constrains: Valve
+}
+"#,
+    )
+    .expect("failed to write model");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "Valve is not suppressed");
+    assert!(
+        stderr.contains("between 'Valve' and 'machine'")
+            && !stderr.contains("between 'Gate' and 'machine'"),
+        "{stderr}"
+    );
+
+    fs::write(dir.join("pf.toml"), "[lints]\nPF-VAL-007 = \"allow\"\n")
+        .expect("failed to write pf.toml");
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("## 6. Suppressions\n"), "{stdout}");
+    assert!(
+        stdout.contains("plant.pf:5:1: the gate is wired by the operations team (1 suppressed)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- allowed in pf.toml: `PF-VAL-007` (1 suppressed)"),
+        "{stdout}"
+    );
+
+    fs::write(dir.join("pf.toml"), "[lints]\nPF-VAL-007 = \"warn\"\n")
+        .expect("failed to write pf.toml");
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--report")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("warning: ") && stderr.contains("between 'Valve' and 'machine'"),
+        "{stderr}"
+    );
+
+    // an undefined domain stays an error, whatever the comment says
+    fs::remove_file(dir.join("pf.toml")).expect("failed to remove pf.toml");
+    let model = fs::read_to_string(&root_path).expect("failed to read model");
+    fs::write(
+        &root_path,
+        model
+            .replace("pf-allow(PF-VAL-007)", "pf-allow(PF-VAL-002)")
+            .replace("constrains: Gate", "constrains: Operator"),
+    )
+    .expect("failed to write model");
+    let output = Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+        .arg(&root_path)
+        .arg("--dot")
+        .output()
+        .expect("failed to execute pf_dsl binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("Domain 'Operator' referenced in requirement 'Open' but not defined.")
+            && stderr.contains("cannot be suppressed [PF-VAL-050]"),
+        "{stderr}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_explains_the_rule_behind_a_finding() {
+    let dir = make_temp_dir("pf-cli-explain");
+    let root_path = dir.join("root.pf");
+    fs::write(
+        &root_path,
+        "problem: Plant\nimport \"a.pf\"\nimport \"a.pf\"\ndomain M kind causal role machine\n",
+    )
+    .expect("failed to write root model");
+    fs::write(dir.join("a.pf"), "domain A kind causal role given\n").expect("failed to write a.pf");
+
+    let output = run_pf_dsl(&root_path, "--report");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Redundant import of \"a.pf\"")
+            && stderr.contains("root.pf:2:1 [PF-VAL-040]"),
+        "{stderr}"
+    );
+
+    let explain = |rule: &str| {
+        Command::new(env!("CARGO_BIN_EXE_pf_dsl"))
+            .args(["explain", rule])
+            .output()
+            .expect("failed to execute pf_dsl binary")
+    };
+    let output = explain("PF-VAL-040");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("**PF-VAL-040** `RedundantImport` (warning): "),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Valid:\n\n```pf\n// file: plant.pf\n"));
+    assert!(stdout.contains("Invalid:\n\n```pf\n"));
+    assert!(stdout.contains("import \"gate.pf\"\nimport \"gate.pf\"\n"));
+
+    assert_eq!(explain("redundantimport").stdout, output.stdout);
+    let output = explain("PF-VAL-999");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown rule 'PF-VAL-999'"));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn dogfooding_cli_fix_applies_did_you_mean_suggestions() {
+    let dir = make_temp_dir("pf-cli-fix");
+    let root_path = dir.join("root.pf");
+    fs::write(
+        &root_path,
+        "problem: Plant\nimport \"gates.pf\"\ndomain Controller kind causal role machine\ninterface \"C-G\" connects Controller, Gate {\n  shared: {\n    phenomenon Open : event [Controller -> Gate] controlledBy Controller\n  }\n}\nrequirement \"Open gate\" {\n  frame: RequiredBehavior\n  constrains: Gat\n}\nrequirement \"Close gate\" {\n  frame: RequiredBehavior\n  constrains: Valve\n}\n",
+    )
+    .expect("failed to write root model");
+    fs::write(
+        dir.join("gates.pf"),
+        "domain Gate kind causal role given\nsubproblem Gates {\n  machine: Controler\n  participants: Controller, Gate\n  requirements: \"open gate\"\n}\n",
+    )
+    .expect("failed to write gates.pf");
+
+    let output = run_pf_dsl(&root_path, "--report");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("Domain 'Gat' referenced in requirement 'Open gate' but not defined. [PF-VAL-002]\n  help: did you mean `Gate`?"),
+        "{stderr}"
+    );
+
+    let output = run_pf_dsl(&root_path, "--fix");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Fixed 1 finding(s) in "), "{stdout}");
+    assert!(stdout.contains("Fixed 2 finding(s) in "), "{stdout}");
+    assert!(!output.status.success(), "the unfixable finding remains");
+    assert!(stderr.contains("'Valve'"), "{stderr}");
+    assert!(!stderr.contains("'Gat'"), "{stderr}");
+
+    let root = fs::read_to_string(&root_path).expect("root model");
+    assert!(root.contains("constrains: Gate\n") && root.contains("constrains: Valve\n"));
+    let gates = fs::read_to_string(dir.join("gates.pf")).expect("gates.pf");
+    assert!(gates.contains("machine: Controller\n"), "{gates}");
+    assert!(gates.contains("requirements: \"Open gate\"\n"), "{gates}");
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -1,7 +1,7 @@
 use lsp_types::{CompletionItem, CompletionItemKind, CompletionList};
 use pf_dsl::language::{
-    DOMAIN_KINDS, DOMAIN_ROLES, FRAME_TYPES, PHENOMENON_TYPES, REQUIREMENT_FIELDS,
-    STATEMENT_KEYWORDS,
+    DOMAIN_KINDS, DOMAIN_ROLES, FRAME_TYPES, PHENOMENON_TYPES, PRIMITIVE_TYPES, REQUIREMENT_FIELDS,
+    REQUIREMENT_PRIORITIES, REQUIREMENT_STATUSES, STATEMENT_KEYWORDS, TYPE_FORMS,
 };
 
 pub fn get_completions() -> CompletionList {
@@ -13,6 +13,12 @@ pub fn get_completions() -> CompletionList {
     for field in REQUIREMENT_FIELDS {
         keywords.push((*field, requirement_field_detail(field).to_string()));
     }
+    for priority in REQUIREMENT_PRIORITIES {
+        keywords.push((*priority, format!("Requirement priority: {priority}")));
+    }
+    for status in REQUIREMENT_STATUSES {
+        keywords.push((*status, format!("Requirement status: {status}")));
+    }
     for kind in DOMAIN_KINDS {
         keywords.push((*kind, format!("Domain kind: {kind}")));
     }
@@ -28,6 +34,12 @@ pub fn get_completions() -> CompletionList {
     for frame in FRAME_TYPES {
         keywords.push((*frame, format!("Frame type: {frame}")));
     }
+    for form in TYPE_FORMS {
+        keywords.push((*form, format!("Type definition: {form}")));
+    }
+    for primitive in PRIMITIVE_TYPES {
+        keywords.push((*primitive, format!("Primitive type: {primitive}")));
+    }
 
     let items = keywords
         .into_iter()
@@ -47,10 +59,23 @@ pub fn get_completions() -> CompletionList {
 
 fn statement_keyword_detail(keyword: &str) -> &'static str {
     match keyword {
+        "import" => "Import another model file, optionally `{ A, B } from` it",
+        "as" => "Qualify an imported file's declarations as `Alias.Name`",
         "problem:" => "Define a new problem",
+        "frame" => "Declare a problem frame",
+        "any" => "Accept any domain kind in a frame slot",
+        "type" => "Declare a data type for phenomenon parameters",
         "domain" => "Define a problem domain",
         "kind" => "Set domain kind",
         "role" => "Set domain role",
+        "behavior" => "Describe a causal domain as a state machine",
+        "on" => "Transition triggered by a received phenomenon",
+        "from" => "Source state of a transition, or the file of a selective import",
+        "to" => "Target state of a transition",
+        "emits" => "Phenomena the domain emits on a transition",
+        "schema" => "Describe the data held by a lexical domain",
+        "entity" => "Declare a schema entity",
+        "field" => "Declare a typed field of a schema entity or value",
         "interface" => "Define an interface between domains",
         "connects" => "List domains connected by an interface",
         "phenomenon" => "Declare a shared phenomenon",
@@ -75,10 +100,15 @@ fn statement_keyword_detail(keyword: &str) -> &'static str {
 
 fn requirement_field_detail(field: &str) -> &'static str {
     match field {
+        "id:" => "Stable requirement identifier used by exports",
+        "priority:" => "Requirement priority (high, medium, low)",
+        "status:" => "Requirement lifecycle status",
+        "owner:" => "Team or person owning the requirement",
         "frame:" => "Requirement frame type",
         "constraint:" => "Requirement textual constraint",
         "constrains:" => "Domain constrained by requirement",
         "reference:" => "Reference domain for frame",
+        "phenomena:" => "Shared phenomena referred to or constrained by requirement",
         _ => "Requirement field",
     }
 }
@@ -1,18 +1,24 @@
 use anyhow::Result;
 use lsp_server::{Connection, Message, Notification};
 use lsp_types::{
-    Diagnostic, DiagnosticSeverity, InitializeParams, Position, PublishDiagnosticsParams, Range,
-    ServerCapabilities, TextDocumentIdentifier, TextDocumentSyncCapability, TextDocumentSyncKind,
-    Uri,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, InitializeParams, Location,
+    NumberOrString, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentIdentifier, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Uri,
 };
-use pf_dsl::resolver::resolve;
+use pf_dsl::ast::Problem;
+use pf_dsl::lints::{lint, LintLevel};
+use pf_dsl::manifest::Manifest;
+use pf_dsl::resolver::{resolve_modules_recovering, resolve_recovering_with_files};
+use pf_dsl::rule_catalog::{Rule, Severity};
+use pf_dsl::source_map::{line_col_utf16, offset_at_utf16, LineCol};
 use pf_dsl::traceability::{build_traceability_graph, TraceEntity};
-use pf_dsl::validator::{validate_with_sources, validation_error_span};
+use pf_dsl::validator::validation_error_span;
+use pf_dsl::vfs::{DiskFiles, OverlayFiles};
 use pf_lsp::completion::get_completions;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 const JSONRPC_METHOD_NOT_FOUND: i32 = -32601;
@@ -40,6 +46,8 @@ struct ImpactRequirementsResponse {
 struct ServerState {
     documents: HashMap<Uri, String>,
     diagnostic_targets_by_owner: HashMap<String, HashSet<String>>,
+    // files each open document imported when it was last resolved
+    imports_by_owner: HashMap<String, HashSet<PathBuf>>,
 }
 
 impl ServerState {
@@ -55,6 +63,41 @@ impl ServerState {
         self.documents.get(uri).map(String::as_str)
     }
 
+    // Open buffers shadow the files on disk, so unsaved edits to an imported file
+    // are seen by the files importing it.
+    fn files(&self) -> OverlayFiles<'static> {
+        let mut files = OverlayFiles::new(&DiskFiles);
+        for (uri, text) in &self.documents {
+            if let Some(path) = uri_to_path(uri) {
+                files.insert(path, text.as_str());
+            }
+        }
+        files
+    }
+
+    fn set_imports(&mut self, owner_uri: &Uri, imports: HashSet<PathBuf>) {
+        self.imports_by_owner
+            .insert(owner_uri.as_str().to_string(), imports);
+    }
+
+    // Other open documents that imported `uri` when they were last resolved.
+    fn importers_of(&self, uri: &Uri) -> Vec<Uri> {
+        let Some(path) = uri_to_path(uri) else {
+            return Vec::new();
+        };
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        self.documents
+            .keys()
+            .filter(|owner| *owner != uri)
+            .filter(|owner| {
+                self.imports_by_owner
+                    .get(owner.as_str())
+                    .is_some_and(|imports| imports.contains(&path))
+            })
+            .cloned()
+            .collect()
+    }
+
     fn update_diagnostic_targets(
         &mut self,
         owner_uri: &Uri,
@@ -98,6 +141,9 @@ fn main() -> Result<()> {
             completion_item: None,
         }),
         definition_provider: Some(lsp_types::OneOf::Left(true)),
+        document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(lsp_types::CodeActionProviderCapability::Simple(true)),
         ..Default::default()
     })?;
 
@@ -162,6 +208,76 @@ fn main_loop(connection: Connection, params: Value) -> Result<()> {
                         let resp = lsp_server::Response::new_ok(req.id, response_payload);
                         connection.sender.send(Message::Response(resp))?;
                     }
+                    "textDocument/hover" => {
+                        let params: lsp_types::HoverParams =
+                            match serde_json::from_value(req.params) {
+                                Ok(params) => params,
+                                Err(err) => {
+                                    send_response_error(
+                                        &connection,
+                                        req.id,
+                                        JSONRPC_INVALID_PARAMS,
+                                        format!("Invalid hover params: {err}"),
+                                    )?;
+                                    continue;
+                                }
+                            };
+
+                        let response_payload = match resolve_hover(&state, params) {
+                            Some(hover) => serde_json::to_value(hover)?,
+                            None => Value::Null,
+                        };
+
+                        let resp = lsp_server::Response::new_ok(req.id, response_payload);
+                        connection.sender.send(Message::Response(resp))?;
+                    }
+                    "textDocument/codeAction" => {
+                        let params: lsp_types::CodeActionParams =
+                            match serde_json::from_value(req.params) {
+                                Ok(params) => params,
+                                Err(err) => {
+                                    send_response_error(
+                                        &connection,
+                                        req.id,
+                                        JSONRPC_INVALID_PARAMS,
+                                        format!("Invalid code action params: {err}"),
+                                    )?;
+                                    continue;
+                                }
+                            };
+
+                        let response_payload = match resolve_code_actions(&state, params) {
+                            Some(actions) => serde_json::to_value(actions)?,
+                            None => Value::Null,
+                        };
+
+                        let resp = lsp_server::Response::new_ok(req.id, response_payload);
+                        connection.sender.send(Message::Response(resp))?;
+                    }
+                    "textDocument/formatting" => {
+                        let params: lsp_types::DocumentFormattingParams =
+                            match serde_json::from_value(req.params) {
+                                Ok(params) => params,
+                                Err(err) => {
+                                    send_response_error(
+                                        &connection,
+                                        req.id,
+                                        JSONRPC_INVALID_PARAMS,
+                                        format!("Invalid formatting params: {err}"),
+                                    )?;
+                                    continue;
+                                }
+                            };
+
+                        let response_payload = match format_document(&state, &params.text_document)
+                        {
+                            Some(edits) => serde_json::to_value(edits)?,
+                            None => Value::Null,
+                        };
+
+                        let resp = lsp_server::Response::new_ok(req.id, response_payload);
+                        connection.sender.send(Message::Response(resp))?;
+                    }
                     "problemFrames/impactRequirements" => {
                         let params: ImpactRequirementsParams =
                             match serde_json::from_value(req.params) {
@@ -223,6 +339,7 @@ fn main_loop(connection: Connection, params: Value) -> Result<()> {
                             params.text_document.uri.clone(),
                             params.text_document.text.clone(),
                         );
+                        revalidate_importers(&connection, &mut state, &params.text_document.uri);
                         if let Err(err) = validate_document(
                             &connection,
                             &mut state,
@@ -247,6 +364,11 @@ fn main_loop(connection: Connection, params: Value) -> Result<()> {
                                 params.text_document.uri.clone(),
                                 change.text.clone(),
                             );
+                            revalidate_importers(
+                                &connection,
+                                &mut state,
+                                &params.text_document.uri,
+                            );
                             if let Err(err) = validate_document(
                                 &connection,
                                 &mut state,
@@ -282,6 +404,8 @@ fn main_loop(connection: Connection, params: Value) -> Result<()> {
                                 eprintln!("Failed to clear diagnostics on close: {err}");
                             }
                         }
+                        // importers fall back to the saved file
+                        revalidate_importers(&connection, &mut state, &params.text_document.uri);
                     }
                     _ => {}
                 }
@@ -405,7 +529,7 @@ fn resolve_impact_requirements(
     };
 
     let offset = offset_at_position(text.as_ref(), params.position);
-    let problem = resolve(&path, Some(text.as_ref()))?;
+    let (problem, _) = resolve_recovering_with_files(&path, &state.files())?;
     let seed = match resolve_impact_seed(&problem, &path, offset) {
         Some(seed) => seed,
         None => return Ok(None),
@@ -441,7 +565,7 @@ fn resolve_definition(
     };
 
     let offset = offset_at_position(text.as_ref(), position);
-    let problem = resolve(&path, Some(text.as_ref())).ok()?;
+    let (problem, _) = resolve_recovering_with_files(&path, &state.files()).ok()?;
     let (source_path_opt, span) = pf_dsl::resolver::find_definition(&problem, &path, offset)?;
 
     let target_path = source_path_opt.unwrap_or_else(|| path.clone());
@@ -462,6 +586,131 @@ fn resolve_definition(
     })
 }
 
+fn resolve_hover(state: &ServerState, params: lsp_types::HoverParams) -> Option<lsp_types::Hover> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let path = uri_to_path(&uri)?;
+    let text = if let Some(buffer_text) = state.document_text(&uri) {
+        Cow::Borrowed(buffer_text)
+    } else {
+        Cow::Owned(std::fs::read_to_string(&path).ok()?)
+    };
+
+    let offset = offset_at_position(text.as_ref(), position);
+    let (problem, _) = resolve_recovering_with_files(&path, &state.files()).ok()?;
+    let mut sections: Vec<String> = pf_dsl::resolver::find_documentation(&problem, &path, offset)
+        .into_iter()
+        .collect();
+    sections.extend(explain_findings_at(state, &problem, &path, offset));
+    if sections.is_empty() {
+        return None;
+    }
+
+    Some(lsp_types::Hover {
+        contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: sections.join("\n---\n\n"),
+        }),
+        range: None,
+    })
+}
+
+// Quick fixes for the findings in the requested range of the document.
+fn resolve_code_actions(
+    state: &ServerState,
+    params: lsp_types::CodeActionParams,
+) -> Option<Vec<lsp_types::CodeActionOrCommand>> {
+    let uri = params.text_document.uri;
+    let path = uri_to_path(&uri)?;
+    let text = if let Some(buffer_text) = state.document_text(&uri) {
+        Cow::Borrowed(buffer_text)
+    } else {
+        Cow::Owned(std::fs::read_to_string(&path).ok()?)
+    };
+
+    let start = offset_at_position(text.as_ref(), params.range.start);
+    let end = offset_at_position(text.as_ref(), params.range.end);
+    let (problem, _) = resolve_recovering_with_files(&path, &state.files()).ok()?;
+    let mut actions = Vec::new();
+    for issue in lint(&problem, &project_lints(state, &path)).issues {
+        let span = validation_error_span(&issue.error);
+        let in_document = issue.source_path.as_deref().unwrap_or(path.as_path()) == path;
+        if !in_document || span.start > end || span.end < start {
+            continue;
+        }
+        for fix in &issue.fixes {
+            let edit = TextEdit {
+                range: span_to_range(text.as_ref(), fix.span),
+                new_text: fix.replacement.clone(),
+            };
+            actions.push(lsp_types::CodeActionOrCommand::CodeAction(
+                lsp_types::CodeAction {
+                    title: format!("Replace with `{}`", fix.replacement),
+                    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                    edit: Some(lsp_types::WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                },
+            ));
+        }
+    }
+    Some(actions)
+}
+
+// The catalog entry of each finding under the cursor, as `pf_dsl explain` prints it.
+fn explain_findings_at(
+    state: &ServerState,
+    problem: &Problem,
+    path: &Path,
+    offset: usize,
+) -> Vec<String> {
+    let mut rules: Vec<&'static Rule> = Vec::new();
+    for issue in lint(problem, &project_lints(state, path)).issues {
+        let in_document = issue.source_path.as_deref().unwrap_or(path) == path;
+        let rule = issue.error.rule();
+        if in_document
+            && in_span(offset, validation_error_span(&issue.error))
+            && !rules.contains(&rule)
+        {
+            rules.push(rule);
+        }
+    }
+    rules.into_iter().map(Rule::explain).collect()
+}
+
+fn project_lints(state: &ServerState, path: &Path) -> BTreeMap<String, LintLevel> {
+    Manifest::discover(path, &state.files())
+        .ok()
+        .flatten()
+        .map(|manifest| manifest.lints)
+        .unwrap_or_default()
+}
+
+// Documents that do not parse are left untouched; the parse diagnostics already
+// tell the user what to fix.
+fn format_document(
+    state: &ServerState,
+    text_document: &TextDocumentIdentifier,
+) -> Option<Vec<TextEdit>> {
+    let text = state.document_text(&text_document.uri)?;
+    let formatted = pf_dsl::formatter::format_source(text).ok()?;
+    if formatted == text {
+        return Some(vec![]);
+    }
+
+    Some(vec![TextEdit {
+        range: Range {
+            start: Position::new(0, 0),
+            end: position_at_byte(text, text.len()),
+        },
+        new_text: formatted,
+    }])
+}
+
 fn send_response_error(
     connection: &Connection,
     id: lsp_server::RequestId,
@@ -474,67 +723,28 @@ fn send_response_error(
 }
 
 fn span_to_range(text: &str, span: pf_dsl::ast::Span) -> Range {
-    // This is a naive implementation.
-    // Ideally we should use a line index to be faster.
-    // Be careful with byte indices vs char indices if utf8.
-    // pest Span is byte offsets.
-
-    let start_byte = span.start;
-    let end_byte = span.end;
-
-    let start = position_at_byte(text, start_byte);
-    let end = position_at_byte(text, end_byte);
-
-    Range { start, end }
+    Range {
+        start: position_at_byte(text, span.start),
+        end: position_at_byte(text, span.end),
+    }
 }
 
 fn position_at_byte(text: &str, offset: usize) -> Position {
-    let mut line = 0_u32;
-    let mut character = 0_u32;
-
-    for (i, c) in text.char_indices() {
-        if i >= offset {
-            break;
-        }
-        if c == '\n' {
-            line += 1;
-            character = 0;
-        } else {
-            // LSP character offsets are UTF-16 code units.
-            character += c.len_utf16() as u32;
-        }
+    let position = line_col_utf16(text, offset);
+    Position {
+        line: position.line as u32,
+        character: position.column as u32,
     }
-
-    Position { line, character }
 }
 
 fn offset_at_position(text: &str, position: Position) -> usize {
-    let mut line = 0_u32;
-    let mut character = 0_u32;
-    for (i, c) in text.char_indices() {
-        if line == position.line && character == position.character {
-            return i;
-        }
-
-        if c == '\n' {
-            line += 1;
-            character = 0;
-        } else {
-            let next_character = character + c.len_utf16() as u32;
-            if line == position.line && next_character > position.character {
-                // Clamp to the start of a code point when client points into a surrogate pair.
-                return i;
-            }
-            character = next_character;
-        }
-    }
-
-    // Fallback if at end of file
-    if line == position.line && character == position.character {
-        return text.len();
-    }
-
-    text.len() // Invalid position fallback
+    offset_at_utf16(
+        text,
+        LineCol {
+            line: position.line as usize,
+            column: position.character as usize,
+        },
+    )
 }
 
 fn parse_error_range(text: &str) -> Option<(Range, String)> {
@@ -562,6 +772,45 @@ fn text_for_path<'a>(
     ))
 }
 
+fn error_diagnostic(range: Range, message: String) -> Diagnostic {
+    diagnostic(range, DiagnosticSeverity::ERROR, message)
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: None,
+        code_description: None,
+        source: Some("pf-lsp".to_string()),
+        message,
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+// The catalog rationale of a finding's rule, attached at the finding itself so the
+// Problems list shows why the rule exists without hovering the code.
+fn rule_explanation(uri: &Uri, range: Range, rule: &Rule) -> DiagnosticRelatedInformation {
+    DiagnosticRelatedInformation {
+        location: Location {
+            uri: uri.clone(),
+            range,
+        },
+        message: format!("{} {}: {}", rule.rule_id, rule.title, rule.rationale),
+    }
+}
+
+fn diagnostic_severity(severity: Severity) -> DiagnosticSeverity {
+    match severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Info => DiagnosticSeverity::INFORMATION,
+        Severity::Hint => DiagnosticSeverity::HINT,
+    }
+}
+
 fn push_diagnostic_for_uri(
     diagnostics_by_uri: &mut Vec<(Uri, Vec<Diagnostic>)>,
     target_uri: Uri,
@@ -577,6 +826,19 @@ fn push_diagnostic_for_uri(
     diagnostics_by_uri.push((target_uri, vec![diagnostic]));
 }
 
+// Importers are validated before the edited document itself, so that its own
+// diagnostics are the last ones published for its URI.
+fn revalidate_importers(connection: &Connection, state: &mut ServerState, uri: &Uri) {
+    for importer in state.importers_of(uri) {
+        let Some(text) = state.document_text(&importer).map(str::to_string) else {
+            continue;
+        };
+        if let Err(err) = validate_document(connection, state, importer, &text) {
+            eprintln!("Failed to validate importing document: {err}");
+        }
+    }
+}
+
 fn validate_document(
     connection: &Connection,
     state: &mut ServerState,
@@ -589,35 +851,59 @@ fn validate_document(
     // We need to convert URI to Path
     let path = uri_to_path(&uri).ok_or_else(|| anyhow::anyhow!("Invalid URI scheme"))?;
 
-    match resolve(&path, Some(text)) {
-        Ok(problem) => {
-            // 2. Semantic Validate
-            match validate_with_sources(&problem) {
-                Ok(_) => {}
-                Err(issues) => {
-                    for issue in issues {
-                        let span = validation_error_span(&issue.error);
-                        let target_path = issue.source_path.as_deref().unwrap_or(path.as_path());
-                        let Some((target_uri, target_text)) =
-                            text_for_path(state, &uri, path.as_path(), text, target_path)
-                        else {
-                            continue;
-                        };
+    // Every loaded module is a dependency, including ones reached only through
+    // an import that is not merged.
+    let resolved = resolve_modules_recovering(&path, &state.files()).and_then(
+        |(modules, parse_diagnostics)| {
+            state.set_imports(
+                &uri,
+                modules
+                    .modules
+                    .iter()
+                    .skip(1)
+                    .map(|module| module.path.clone())
+                    .collect(),
+            );
+            Ok((modules.flatten()?, parse_diagnostics))
+        },
+    );
+    match resolved {
+        Ok((problem, parse_diagnostics)) => {
+            for (span, message) in parse_diagnostics {
+                push_diagnostic_for_uri(
+                    &mut diagnostics_by_uri,
+                    uri.clone(),
+                    error_diagnostic(span_to_range(text, span), message),
+                );
+            }
 
-                        let diagnostic = Diagnostic {
-                            range: span_to_range(target_text.as_ref(), span),
-                            severity: Some(DiagnosticSeverity::ERROR),
-                            code: None,
-                            code_description: None,
-                            source: Some("pf-lsp".to_string()),
-                            message: issue.error.to_string(),
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        };
-                        push_diagnostic_for_uri(&mut diagnostics_by_uri, target_uri, diagnostic);
-                    }
-                }
+            // 2. Semantic Validate (on whatever the parser could recover), minus
+            // suppressed findings
+            for issue in lint(&problem, &project_lints(state, &path)).issues {
+                let span = validation_error_span(&issue.error);
+                let target_path = issue.source_path.as_deref().unwrap_or(path.as_path());
+                let Some((target_uri, target_text)) =
+                    text_for_path(state, &uri, path.as_path(), text, target_path)
+                else {
+                    continue;
+                };
+
+                let message = match issue.help() {
+                    Some(help) => format!("{}\n{help}", issue.error),
+                    None => issue.error.to_string(),
+                };
+                let mut diagnostic = diagnostic(
+                    span_to_range(target_text.as_ref(), span),
+                    diagnostic_severity(issue.severity),
+                    message,
+                );
+                diagnostic.code = Some(NumberOrString::String(issue.rule_id.to_string()));
+                diagnostic.related_information = Some(vec![rule_explanation(
+                    &target_uri,
+                    diagnostic.range,
+                    issue.error.rule(),
+                )]);
+                push_diagnostic_for_uri(&mut diagnostics_by_uri, target_uri, diagnostic);
             }
         }
         Err(e) => {
@@ -637,18 +923,11 @@ fn validate_document(
                 )
             });
 
-            let diagnostic = Diagnostic {
-                range,
-                severity: Some(DiagnosticSeverity::ERROR),
-                code: None,
-                code_description: None,
-                source: Some("pf-lsp".to_string()),
-                message,
-                related_information: None,
-                tags: None,
-                data: None,
-            };
-            push_diagnostic_for_uri(&mut diagnostics_by_uri, uri.clone(), diagnostic);
+            push_diagnostic_for_uri(
+                &mut diagnostics_by_uri,
+                uri.clone(),
+                error_diagnostic(range, message),
+            );
         }
     }
 
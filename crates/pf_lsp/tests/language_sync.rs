@@ -1,6 +1,6 @@
 use pf_dsl::language::{
-    DOMAIN_KINDS, DOMAIN_ROLES, FRAME_TYPES, PHENOMENON_TYPES, REQUIREMENT_FIELDS,
-    STATEMENT_KEYWORDS,
+    DOMAIN_KINDS, DOMAIN_ROLES, FRAME_TYPES, PHENOMENON_TYPES, PRIMITIVE_TYPES, REQUIREMENT_FIELDS,
+    REQUIREMENT_PRIORITIES, REQUIREMENT_STATUSES, STATEMENT_KEYWORDS, TYPE_FORMS,
 };
 use pf_lsp::completion::get_completions;
 use serde_json::Value;
@@ -26,6 +26,15 @@ fn completion_items_cover_language_spec() {
     for field in REQUIREMENT_FIELDS {
         assert!(labels.contains(field), "missing completion field: {field}");
     }
+    for value in REQUIREMENT_PRIORITIES
+        .iter()
+        .chain(REQUIREMENT_STATUSES.iter())
+    {
+        assert!(
+            labels.contains(value),
+            "missing requirement metadata completion: {value}"
+        );
+    }
     for domain in DOMAIN_KINDS.iter().chain(DOMAIN_ROLES.iter()) {
         assert!(
             labels.contains(domain),
@@ -41,6 +50,12 @@ fn completion_items_cover_language_spec() {
     for frame in FRAME_TYPES {
         assert!(labels.contains(frame), "missing frame completion: {frame}");
     }
+    for type_token in TYPE_FORMS.iter().chain(PRIMITIVE_TYPES.iter()) {
+        assert!(
+            labels.contains(type_token),
+            "missing type completion: {type_token}"
+        );
+    }
 }
 
 #[test]
@@ -53,7 +68,11 @@ fn vscode_syntax_contains_language_tokens() {
     let keyword_match = json["repository"]["keywords"]["patterns"][0]["match"]
         .as_str()
         .expect("missing keyword regex");
-    for keyword in STATEMENT_KEYWORDS.iter().chain(REQUIREMENT_FIELDS.iter()) {
+    for keyword in STATEMENT_KEYWORDS
+        .iter()
+        .chain(REQUIREMENT_FIELDS.iter())
+        .chain(TYPE_FORMS.iter())
+    {
         let token = keyword.trim_end_matches(':');
         assert!(
             keyword_match.contains(token),
@@ -64,7 +83,11 @@ fn vscode_syntax_contains_language_tokens() {
     let storage_match = json["repository"]["types"]["patterns"][0]["match"]
         .as_str()
         .expect("missing storage type regex");
-    for domain in DOMAIN_KINDS.iter().chain(DOMAIN_ROLES.iter()) {
+    for domain in DOMAIN_KINDS
+        .iter()
+        .chain(DOMAIN_ROLES.iter())
+        .chain(PRIMITIVE_TYPES.iter())
+    {
         assert!(
             storage_match.contains(domain),
             "domain type regex does not contain token: {domain}"
@@ -86,4 +109,13 @@ fn vscode_syntax_contains_language_tokens() {
             "phenomenon regex does not contain token: {phenomenon}"
         );
     }
+    for value in REQUIREMENT_PRIORITIES
+        .iter()
+        .chain(REQUIREMENT_STATUSES.iter())
+    {
+        assert!(
+            support_match.contains(value),
+            "requirement metadata regex does not contain token: {value}"
+        );
+    }
 }
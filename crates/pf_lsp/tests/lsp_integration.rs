@@ -222,8 +222,8 @@ fn diagnostics_follow_did_change_buffer_state() {
         .as_array()
         .expect("diagnostics must be array");
     assert!(
-        !diagnostics.is_empty(),
-        "expected non-empty diagnostics for invalid buffer"
+        diagnostics.iter().any(|d| d["severity"] == json!(1)),
+        "expected error diagnostics for invalid buffer"
     );
 
     let fixed_text = "problem: P\ndomain A kind causal role machine\ndomain B kind causal role given\ninterface \"A-B\" connects A, B { shared: { phenomenon e : event [A -> B] controlledBy A } }\n";
@@ -247,9 +247,13 @@ fn diagnostics_follow_did_change_buffer_state() {
     let diagnostics = change_diag["params"]["diagnostics"]
         .as_array()
         .expect("diagnostics must be array");
+    let errors: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d["severity"] == json!(1))
+        .collect();
     assert!(
-        diagnostics.is_empty(),
-        "expected empty diagnostics after fix"
+        errors.is_empty(),
+        "expected no error diagnostics after fix, got {errors:?}"
     );
 
     let _ = fs::remove_dir_all(dir);
@@ -546,6 +550,226 @@ fn parse_errors_report_precise_range() {
     let _ = fs::remove_dir_all(dir);
 }
 
+#[test]
+fn broken_declarations_keep_definition_and_report_every_parse_error() {
+    let dir = make_temp_dir("pf-lsp-recovery");
+    let path = dir.join("problem.pf");
+    let uri = file_uri(&path);
+    let mut client = TestLspClient::spawn();
+
+    let text = "problem: P\ndomain A kind causal role machine\ndomain B kind causal\ndomain C kind causal role given\ninterface \"A-C\" connects A, C {\n  shared: {\n    phenomenon e : event [A -> C] controlledBy A\n  }\n}\nrequirement \"R\" {\n  frame: RequiredBehavior\n";
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": text
+            }
+        }
+    }));
+
+    let open_diag = client
+        .wait_for(|msg| msg.get("method") == Some(&json!("textDocument/publishDiagnostics")))
+        .expect("did not receive diagnostics after didOpen");
+    let diagnostics = open_diag["params"]["diagnostics"]
+        .as_array()
+        .expect("diagnostics must be array");
+    let lines: Vec<u64> = diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic["range"]["start"]["line"].as_u64())
+        .collect();
+    assert!(
+        lines.contains(&2),
+        "expected diagnostic for broken domain B: {lines:?}"
+    );
+    assert!(
+        lines.iter().any(|line| *line >= 9),
+        "expected diagnostic for unterminated requirement R: {lines:?}"
+    );
+
+    let position = position_of(text, "C", 2);
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/definition",
+        "params": {
+            "textDocument": { "uri": uri },
+            "position": {
+                "line": position.line,
+                "character": position.character
+            }
+        }
+    }));
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(2)))
+        .expect("did not receive definition response");
+    assert_eq!(
+        response["result"]["range"]["start"]["line"].as_u64(),
+        Some(3),
+        "definition should still resolve to 'domain C' while other declarations are broken"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn hover_shows_doc_comment_of_referenced_declaration() {
+    let dir = make_temp_dir("pf-lsp-hover");
+    let path = dir.join("problem.pf");
+    let uri = file_uri(&path);
+
+    let text = "problem: P\n/// Drives the gate.\ndomain M kind causal role machine\ndomain T kind causal role given\ninterface \"M-T\" connects M, T { shared: { phenomenon e : event [M -> T] controlledBy M } }\n";
+    fs::write(&path, text).expect("failed to write test file");
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": text
+            }
+        }
+    }));
+
+    let _ =
+        client.wait_for(|msg| msg.get("method") == Some(&json!("textDocument/publishDiagnostics")));
+
+    let documented = position_of(text, "M } }", 0);
+    let undocumented = position_of(text, "T {", 0);
+    for (id, position) in [(2, documented), (3, undocumented)] {
+        client.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "textDocument/hover",
+            "params": {
+                "textDocument": { "uri": uri },
+                "position": {
+                    "line": position.line,
+                    "character": position.character
+                }
+            }
+        }));
+    }
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(2)))
+        .expect("did not receive hover response");
+    assert_eq!(response["result"]["contents"]["kind"], json!("markdown"));
+    let value = response["result"]["contents"]["value"]
+        .as_str()
+        .expect("hover value must be a string");
+    assert!(
+        value.starts_with("Drives the gate."),
+        "hover must lead with the doc comment, got {value:?}"
+    );
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(3)))
+        .expect("did not receive hover response");
+    assert_eq!(
+        response.get("result"),
+        Some(&Value::Null),
+        "undocumented declarations have no hover"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn formatting_returns_whole_document_edit_and_keeps_comments() {
+    let dir = make_temp_dir("pf-lsp-formatting");
+    let path = dir.join("problem.pf");
+    let uri = file_uri(&path);
+
+    let text = "problem: P\n// controller\ndomain M kind causal role machine\ndomain T kind causal role given\ninterface \"M-T\" connects M, T { shared: { phenomenon e : event [M -> T] controlledBy M } }\n";
+    fs::write(&path, text).expect("failed to write test file");
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": text
+            }
+        }
+    }));
+
+    let _ =
+        client.wait_for(|msg| msg.get("method") == Some(&json!("textDocument/publishDiagnostics")));
+
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/formatting",
+        "params": {
+            "textDocument": { "uri": uri },
+            "options": { "tabSize": 4, "insertSpaces": true }
+        }
+    }));
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(2)))
+        .expect("did not receive formatting response");
+    let edits = response["result"]
+        .as_array()
+        .expect("formatting should return edits");
+    assert_eq!(
+        edits.len(),
+        1,
+        "formatting should replace the whole document"
+    );
+    assert_eq!(edits[0]["range"]["start"]["line"].as_u64(), Some(0));
+    assert_eq!(edits[0]["range"]["end"]["line"].as_u64(), Some(5));
+
+    let new_text = edits[0]["newText"]
+        .as_str()
+        .expect("edit should carry text");
+    assert!(new_text.contains("// controller\ndomain M kind causal role machine\n"));
+    assert!(new_text
+        .contains("    shared: {\n        phenomenon e : event [M -> T] controlledBy M\n    }\n"));
+
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": "problem: P\ndomain Broken kind\n" }]
+        }
+    }));
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "textDocument/formatting",
+        "params": {
+            "textDocument": { "uri": uri },
+            "options": { "tabSize": 4, "insertSpaces": true }
+        }
+    }));
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(3)))
+        .expect("did not receive formatting response");
+    assert_eq!(
+        response.get("result"),
+        Some(&Value::Null),
+        "unparseable documents should not be reformatted"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
 #[test]
 fn definition_ignores_imported_span_collisions() {
     let dir = make_temp_dir("pf-lsp-definition-collision");
@@ -649,11 +873,12 @@ fn diagnostics_are_attributed_to_imported_file_uri() {
         !diagnostics.is_empty(),
         "expected imported file diagnostics to be non-empty"
     );
+    // M is also reported there, as one of two machines that no subproblem owns.
     assert!(
-        diagnostics[0]["message"]
+        diagnostics.iter().any(|diagnostic| diagnostic["message"]
             .as_str()
             .unwrap_or_default()
-            .contains("Missing"),
+            .contains("Missing")),
         "expected message to mention missing domain"
     );
 
@@ -996,3 +1221,310 @@ fn impact_request_large_model_stays_under_timeout() {
 
     let _ = fs::remove_dir_all(dir);
 }
+
+#[test]
+fn unsaved_edits_in_imported_file_update_importer_diagnostics() {
+    let dir = make_temp_dir("pf-lsp-import-overlay");
+    let root_path = dir.join("root.pf");
+    let import_path = dir.join("imp.pf");
+    let root_uri = file_uri(&root_path);
+    let import_uri = file_uri(&import_path);
+
+    fs::write(&import_path, "domain Gate kind causal role given\n")
+        .expect("failed to write imported file");
+    let root_text = "problem: Root\nimport \"imp.pf\"\ndomain M kind causal role machine\ninterface \"M-G\" connects M, Gate { shared: { phenomenon Go : event [M -> Gate] controlledBy M } }\nrequirement \"R\" { frame: RequiredBehavior constrains: Gate }\n";
+
+    let is_root_diagnostics = |msg: &Value| {
+        msg.get("method") == Some(&json!("textDocument/publishDiagnostics"))
+            && msg["params"]["uri"] == json!(root_uri)
+    };
+    let messages = |msg: &Value| -> Vec<String> {
+        msg["params"]["diagnostics"]
+            .as_array()
+            .expect("diagnostics must be array")
+            .iter()
+            .map(|diagnostic| {
+                diagnostic["message"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect()
+    };
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": { "uri": root_uri, "languageId": "pf", "version": 1, "text": root_text }
+        }
+    }));
+    let opened = client
+        .wait_for(is_root_diagnostics)
+        .expect("did not receive diagnostics for root");
+    assert!(messages(&opened).is_empty(), "{:?}", messages(&opened));
+
+    // rename the domain in the open, unsaved import buffer
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": import_uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": "domain Valve kind causal role given\n"
+            }
+        }
+    }));
+    let broken = client
+        .wait_for(is_root_diagnostics)
+        .expect("importer was not revalidated after import buffer opened");
+    assert!(
+        messages(&broken)
+            .iter()
+            .any(|message| message.contains("Domain 'Gate' referenced in requirement 'R'")),
+        "{:?}",
+        messages(&broken)
+    );
+
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": import_uri, "version": 2 },
+            "contentChanges": [
+                { "text": "domain Gate kind causal role given\n" }
+            ]
+        }
+    }));
+    let fixed = client
+        .wait_for(is_root_diagnostics)
+        .expect("importer was not revalidated after import buffer changed");
+    assert!(messages(&fixed).is_empty(), "{:?}", messages(&fixed));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn diagnostics_carry_catalog_severity_and_rule_id() {
+    let dir = make_temp_dir("pf-lsp-severity");
+    let root_path = dir.join("root.pf");
+    fs::write(dir.join("imp.pf"), "domain Gate kind causal role given\n")
+        .expect("failed to write imported file");
+    let root_text = "problem: Root\nimport \"imp.pf\"\nimport \"imp.pf\"\ndomain M kind causal role machine\nrequirement \"R\" {\n  frame: RequiredBehavior\n  constrains: Missing\n}\n";
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": file_uri(&root_path),
+                "languageId": "pf",
+                "version": 1,
+                "text": root_text
+            }
+        }
+    }));
+
+    let root_diag = client
+        .wait_for(|msg| {
+            msg.get("method") == Some(&json!("textDocument/publishDiagnostics"))
+                && msg["params"]["uri"] == json!(file_uri(&root_path))
+        })
+        .expect("did not receive diagnostics for root file");
+    let diagnostics = root_diag["params"]["diagnostics"]
+        .as_array()
+        .expect("diagnostics must be array");
+    let redundant = diagnostics
+        .iter()
+        .find(|d| {
+            d["message"]
+                .as_str()
+                .unwrap_or_default()
+                .contains("Redundant import")
+        })
+        .expect("redundant import diagnostic");
+    assert_eq!(redundant["severity"], json!(2));
+    assert_eq!(redundant["code"], json!("PF-VAL-040"));
+    let explanation = redundant["relatedInformation"][0]["message"]
+        .as_str()
+        .expect("diagnostic links its rule explanation");
+    assert!(
+        explanation.starts_with("PF-VAL-040 "),
+        "unexpected explanation: {explanation}"
+    );
+    assert_eq!(
+        redundant["relatedInformation"][0]["location"]["range"],
+        redundant["range"]
+    );
+    let undefined = diagnostics
+        .iter()
+        .find(|d| {
+            d["message"]
+                .as_str()
+                .unwrap_or_default()
+                .contains("Missing")
+        })
+        .expect("undefined domain diagnostic");
+    assert_eq!(undefined["severity"], json!(1));
+    assert_eq!(undefined["code"], json!("PF-VAL-002"));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn suppressed_findings_are_not_published() {
+    let dir = make_temp_dir("pf-lsp-suppressed");
+    let root_path = dir.join("root.pf");
+    let root_text = "problem: Root\ndomain M kind causal role machine\ndomain G kind causal role given\n// pf-allow(PF-VAL-007): wired elsewhere\nrequirement \"R\" {\n  frame: RequiredBehavior\n  constrains: G\n}\n";
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": file_uri(&root_path),
+                "languageId": "pf",
+                "version": 1,
+                "text": root_text
+            }
+        }
+    }));
+
+    let root_diag = client
+        .wait_for(|msg| {
+            msg.get("method") == Some(&json!("textDocument/publishDiagnostics"))
+                && msg["params"]["uri"] == json!(file_uri(&root_path))
+        })
+        .expect("did not receive diagnostics for root file");
+    let diagnostics = root_diag["params"]["diagnostics"]
+        .as_array()
+        .expect("diagnostics must be array");
+    assert!(
+        !diagnostics.iter().any(|d| d["code"] == json!("PF-VAL-007")),
+        "{diagnostics:?}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn hover_on_finding_explains_its_catalog_rule() {
+    let dir = make_temp_dir("pf-lsp-explain");
+    let root_path = dir.join("root.pf");
+    let uri = file_uri(&root_path);
+    let root_text = "problem: Root\ndomain M kind causal role machine\nrequirement \"R\" {\n  frame: RequiredBehavior\n  constrains: Missing\n}\n";
+    fs::write(&root_path, root_text).expect("failed to write test file");
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": root_text
+            }
+        }
+    }));
+    let _ =
+        client.wait_for(|msg| msg.get("method") == Some(&json!("textDocument/publishDiagnostics")));
+
+    let position = position_of(root_text, "Missing", 0);
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/hover",
+        "params": {
+            "textDocument": { "uri": uri },
+            "position": {
+                "line": position.line,
+                "character": position.character + 2
+            }
+        }
+    }));
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(2)))
+        .expect("did not receive hover response");
+    let rule = pf_dsl::rule_catalog::rule_by_id("PF-VAL-002").expect("catalog rule");
+    assert_eq!(
+        response["result"]["contents"]["value"],
+        json!(rule.explain())
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn code_actions_offer_did_you_mean_fixes() {
+    let dir = make_temp_dir("pf-lsp-fixes");
+    let root_path = dir.join("root.pf");
+    let uri = file_uri(&root_path);
+    let root_text = "problem: Root\ndomain Machine kind causal role machine\ndomain Gate kind causal role given\nrequirement \"R\" {\n  frame: RequiredBehavior\n  constrains: Gat\n}\n";
+    fs::write(&root_path, root_text).expect("failed to write test file");
+
+    let mut client = TestLspClient::spawn();
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "pf",
+                "version": 1,
+                "text": root_text
+            }
+        }
+    }));
+    let diagnostics = client
+        .wait_for(|msg| msg.get("method") == Some(&json!("textDocument/publishDiagnostics")))
+        .expect("did not receive diagnostics");
+    assert!(diagnostics["params"]["diagnostics"]
+        .as_array()
+        .expect("diagnostics must be array")
+        .iter()
+        .any(|d| d["message"]
+            .as_str()
+            .unwrap_or_default()
+            .ends_with("did you mean `Gate`?")));
+
+    let start = position_of(root_text, "Gat\n", 0);
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/codeAction",
+        "params": {
+            "textDocument": { "uri": uri },
+            "range": {
+                "start": { "line": start.line, "character": start.character },
+                "end": { "line": start.line, "character": start.character + 1 }
+            },
+            "context": { "diagnostics": [] }
+        }
+    }));
+
+    let response = client
+        .wait_for(|msg| msg.get("id") == Some(&json!(2)))
+        .expect("did not receive code action response");
+    let actions = response["result"]
+        .as_array()
+        .expect("code actions must be array");
+    assert_eq!(actions.len(), 1, "{actions:?}");
+    assert_eq!(actions[0]["title"], json!("Replace with `Gate`"));
+    assert_eq!(actions[0]["kind"], json!("quickfix"));
+    let edits = &actions[0]["edit"]["changes"][uri.as_str()];
+    assert_eq!(edits[0]["newText"], json!("Gate"));
+    assert_eq!(
+        edits[0]["range"]["start"],
+        json!({ "line": start.line, "character": start.character })
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}